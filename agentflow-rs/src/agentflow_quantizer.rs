@@ -14,9 +14,10 @@
 
 
 use crate::{AgentFlowServer, AgentTask};
+use crate::privacy::{add_dp_noise, InvalidEpsilon};
 use kvquant_rs::{kv_cache::KVCache, KVQuantizer as Quantizer};
 use kvquant_rs::pb::sidecar_service_client::SidecarServiceClient;
-// QuantizedFeatures not available in kvquant_rs::pb
+use kvquant_rs::pb::CacheUpdate;
 use log::{info, error};
 use prost::Message;
 use std::sync::Arc;
@@ -27,11 +28,16 @@
 pub struct AgentFlowQuantizer {
     pub sidecar_address: String,
     pub channel: mpsc::Sender<AgentTask>,
+    /// Differential-privacy budget applied to quantized values before they leave this client
+    pub privacy_epsilon: f32,
 }
 
 impl AgentFlowQuantizer {
-    pub async fn new(sidecar_address: String, server: Arc<AgentFlowServer>) -> Self {
+    /// Fails if the server's privacy budget is not a finite, positive epsilon
+    pub async fn new(sidecar_address: String, server: Arc<AgentFlowServer>) -> Result<Self, InvalidEpsilon> {
+        server.config().validate()?;
         let (tx, rx) = mpsc::channel(100);
+        let privacy_epsilon = server.config().privacy_epsilon;
 
         thread::spawn(move || loop {
             let request = rx.recv().expect("Failed to receive request");
@@ -42,22 +48,27 @@ pub async fn new(sidecar_address: String, server: Arc<AgentFlowServer>) -> Self
                     let mut client = SidecarServiceClient::connect(sidecar_address.clone()).unwrap();
 
                     let token_features: Vec<KVCache> = server.attention_store.get_token_features(model_id.clone());
-                    let quantized_features = server.quantizer.quantize(token_features, bit_width);
+                    let mut quantized_features = server.quantizer.quantize(token_features, bit_width);
+                    if let Err(e) = add_dp_noise(&mut quantized_features, privacy_epsilon) {
+                        error!("Not sending features for {}: {}", model_id, e);
+                        continue;
+                    }
 
-                    // Create a simple message structure since QuantizedFeatures is not available
-                    let quantized_features_bytes = format!("model_id:{},bit_width:{}", model_id, bit_width).into_bytes();
-                    let request = tonic::Request::new(quantized_features_bytes);
-                    let response = client.store_quantized_features(request).unwrap();
+                    // Only the noised values leave this client, as little-endian f32s
+                    let data = quantized_features.iter().flat_map(|v| v.to_le_bytes()).collect();
+                    let request = tonic::Request::new(CacheUpdate { vector_id: model_id, data });
+                    let response = client.update_cache(request).unwrap();
                     info!("Store quantized features response: {:?}", response);
                 }
                 _ => {}
             }
         });
 
-        Self {
+        Ok(Self {
             sidecar_address,
             channel: tx,
-        }
+            privacy_epsilon,
+        })
     }
 
     pub async fn quantize(&self, model_id: String, bit_width: usize) {
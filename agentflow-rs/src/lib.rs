@@ -59,6 +59,13 @@ pub struct AgentFlowConfig {
     pub privacy_epsilon: f32,
 }
 
+impl AgentFlowConfig {
+    /// Reject a privacy budget that would send values out without noise
+    pub fn validate(&self) -> Result<(), privacy::InvalidEpsilon> {
+        privacy::validate_epsilon(self.privacy_epsilon).map(|_| ())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AgentTask {
     Quantization { model_id: String, bit_width: usize },
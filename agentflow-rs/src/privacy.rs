@@ -110,3 +110,74 @@ pub fn deserialize(data: &str) -> Self {
     }
 }
 
+/// L1 sensitivity assumed for quantized values, which lie in a unit range
+pub const DP_SENSITIVITY: f32 = 1.0;
+
+/// A privacy budget that cannot bound noise: zero, negative or non-finite
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidEpsilon(pub f32);
+
+impl std::fmt::Display for InvalidEpsilon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "privacy epsilon must be finite and positive, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidEpsilon {}
+
+/// Check that `epsilon` is a usable differential-privacy budget
+pub fn validate_epsilon(epsilon: f32) -> Result<f32, InvalidEpsilon> {
+    if epsilon.is_finite() && epsilon > 0.0 {
+        Ok(epsilon)
+    } else {
+        Err(InvalidEpsilon(epsilon))
+    }
+}
+
+/// Add Laplace noise with scale `DP_SENSITIVITY / epsilon` to every value
+///
+/// Smaller `epsilon` means stronger privacy and larger noise. A non-positive
+/// or non-finite `epsilon` is rejected and leaves the values untouched.
+pub fn add_dp_noise(values: &mut [f32], epsilon: f32) -> Result<(), InvalidEpsilon> {
+    let scale = (DP_SENSITIVITY / validate_epsilon(epsilon)?) as f64;
+    let mut rng = thread_rng();
+    for val in values.iter_mut() {
+        // Inverse-CDF sample of Laplace(0, scale)
+        let u: f64 = rng.gen_range(-0.5..0.5);
+        let noise = -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln();
+        *val += noise as f32;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_abs_noise(epsilon: f32) -> f32 {
+        let mut values = vec![0.0f32; 20_000];
+        add_dp_noise(&mut values, epsilon).unwrap();
+        values.iter().map(|v| v.abs()).sum::<f32>() / values.len() as f32
+    }
+
+    #[test]
+    fn test_dp_noise_scales_inversely_with_epsilon() {
+        let strong = mean_abs_noise(0.5);
+        let weak = mean_abs_noise(5.0);
+
+        // E|Laplace(0, b)| = b = sensitivity / epsilon
+        assert!((strong - 2.0).abs() < 0.2, "mean noise {} at epsilon 0.5", strong);
+        assert!((weak - 0.2).abs() < 0.02, "mean noise {} at epsilon 5.0", weak);
+        assert!(strong > 5.0 * weak);
+    }
+
+    #[test]
+    fn test_dp_noise_rejects_invalid_epsilon() {
+        for epsilon in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let mut values = vec![0.25f32; 8];
+            assert!(add_dp_noise(&mut values, epsilon).is_err(), "epsilon {} accepted", epsilon);
+            assert!(values.iter().all(|&v| v == 0.25));
+        }
+        assert_eq!(validate_epsilon(0.0), Err(InvalidEpsilon(0.0)));
+    }
+}
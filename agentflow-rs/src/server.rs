@@ -85,6 +85,11 @@ pub async fn run(&self) {
     pub fn get_client(&self, id: usize) -> Option<Arc<Client>> {
         self.clients.get(&id).map(|c| c.clone())
     }
+
+    /// The configuration this server was started with
+    pub fn config(&self) -> &AgentFlowConfig {
+        &self.config
+    }
 }
 
 pub fn initialize_agent_flow_server(config: AgentFlowConfig) -> AgentFlowServer {
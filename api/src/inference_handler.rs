@@ -63,6 +63,8 @@ enum InferenceError {
     Database(#[from] sqlx::Error),
     #[error("Compaction error: {0}")]
     Compaction(String),
+    #[error("Inference engine error: {0}")]
+    Engine(#[from] llm_rs::InferenceError),
 }
 
 // InferenceRequest represents a request to perform inference.
@@ -124,7 +126,9 @@ pub struct InferenceHandler {
 
 impl InferenceHandler {
     pub async fn new(model_store: ModelStore, db_pool: PgPool) -> Self {
-        let engine = InferenceEngine::new(768).await;
+        let engine = InferenceEngine::new(768)
+            .await
+            .unwrap_or_else(|e| panic!("Inference engine init failed: {:?}", e));
         #[cfg(feature = "server")]
         let sidecar_client = pb::sidecar_service_client::SidecarServiceClient::connect("http://localhost:50051")
             .await
@@ -222,7 +226,9 @@ pub async fn infer(&self, req: web::Json<InferenceRequest>) -> Result<HttpRespon
         let customized_input = self.lua_engine.customize_inference(&req.input)?;
         let quantized_input = self.python_engine.execute_quantization(&customized_input)?;
         let start = std::time::Instant::now();
-        let output = self.engine.infer(&quantized_input, &routing_plan, &[]).await; // Empty weights as loaded earlier
+        let output = self.engine.infer(&quantized_input, &routing_plan, &[]) // Empty weights as loaded earlier
+            .await
+            .map_err(|e| actix_web::error::ErrorInternalServerError(InferenceError::Engine(e)))?;
         let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
 
         // Enqueue compaction for updated KV cache
@@ -448,7 +454,8 @@ pub fn infer_wasm(input: String, model_name: String, precision: String, user_id:
             let output = handler.engine.infer(&quantized_input, &ns_router_rs::NSRoutingPlan {
                 model_config: ns_router_rs::ModelConfig { precision: results.clone() },
                 kv_cache_config: ns_router_rs::KVCacheConfig { sparsity: 0.7, priority_tokens: vec![] },
-            }, &[]).await;
+            }, &[]).await
+                .map_err(|e| js_sys::Error::new(&e.to_string()))?;
 
             let subscription = handler.get_subscription_data(&req.user_id).await?;
             let upgrade_prompt = handler.check_usage_limit(&req.user_id, output.tokens_processed as u32, "inference", &subscription)?;
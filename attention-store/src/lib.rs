@@ -15,4 +15,4 @@
 pub mod store;
 pub mod scheduler;
 
-pub use store::AttentionStore;
\ No newline at end of file
+pub use store::{AttentionStore, TruncationWindow};
\ No newline at end of file
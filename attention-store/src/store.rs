@@ -40,6 +40,16 @@ pub enum AttentionStoreError {
     LLM(String),
 }
 
+/// Which tokens `truncate_cache` keeps when a session exceeds its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationWindow {
+    /// Keep the first `max_tokens` tokens
+    #[default]
+    Prefix,
+    /// Keep the most recent `max_tokens` tokens
+    Suffix,
+}
+
 #[derive(Debug)]
 pub struct SessionContext {
     session_id: String,
@@ -204,11 +214,19 @@ async fn allocate_segment(&self, session_id: String) -> Result<String, Attention
     }
 
     pub async fn truncate_cache(&self, session_id: String, max_tokens: usize) -> Result<(), AttentionStoreError> {
+        self.truncate_cache_with_window(session_id, max_tokens, TruncationWindow::Prefix).await
+    }
+
+    /// Truncate a session's cache to `max_tokens`, keeping the tokens selected by `window`
+    pub async fn truncate_cache_with_window(
+        &self,
+        session_id: String,
+        max_tokens: usize,
+        window: TruncationWindow,
+    ) -> Result<(), AttentionStoreError> {
         let mut sessions = self.sessions.write().await;
         if let Some(ctx) = sessions.get_mut(&session_id) {
-            let token_count = ctx.kv_cache.len() / LAYER_COUNT;
-            if token_count > max_tokens {
-                ctx.kv_cache.truncate(max_tokens * LAYER_COUNT);
+            if compact_kv_cache(&mut ctx.kv_cache, max_tokens, window) {
                 ctx.truncated = true;
                 if let Some(segment_id) = &ctx.segment {
                     self.segment_ops.remount_segment(vec![Segment {
@@ -270,4 +288,66 @@ async fn async_save_task(store: Arc<Self>) {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Drop tokens outside `window` from a token-major cache (`LAYER_COUNT` entries per token)
+///
+/// Every retained entry keeps its original token position: entries without a
+/// positional encoding are stamped with their index before compaction so the
+/// surviving sequence stays ordered. Returns whether anything was removed.
+pub fn compact_kv_cache(kv_cache: &mut Vec<KVCache>, max_tokens: usize, window: TruncationWindow) -> bool {
+    let token_count = kv_cache.len() / LAYER_COUNT;
+    if token_count <= max_tokens {
+        return false;
+    }
+
+    for (idx, entry) in kv_cache.iter_mut().enumerate() {
+        if entry.positional_encoding.is_none() {
+            entry.positional_encoding = Some(vec![(idx / LAYER_COUNT) as i32]);
+        }
+    }
+
+    let start = match window {
+        TruncationWindow::Prefix => 0,
+        TruncationWindow::Suffix => token_count - max_tokens,
+    };
+    kv_cache.drain(..start * LAYER_COUNT);
+    kv_cache.truncate(max_tokens * LAYER_COUNT);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_positions(kv_cache: &[KVCache]) -> Vec<i32> {
+        kv_cache
+            .chunks(LAYER_COUNT)
+            .map(|layers| {
+                let position = layers[0].positional_encoding.clone().unwrap();
+                assert!(layers.iter().all(|l| l.positional_encoding.as_ref() == Some(&position)));
+                position[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compact_keeps_original_positions() {
+        let mut prefix = vec![KVCache::new(Vec::new()); 10 * LAYER_COUNT];
+        let mut suffix = prefix.clone();
+
+        assert!(compact_kv_cache(&mut prefix, 4, TruncationWindow::Prefix));
+        assert!(compact_kv_cache(&mut suffix, 4, TruncationWindow::Suffix));
+
+        assert_eq!(prefix.len(), 4 * LAYER_COUNT);
+        assert_eq!(token_positions(&prefix), vec![0, 1, 2, 3]);
+        assert_eq!(token_positions(&suffix), vec![6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_compact_within_budget_is_noop() {
+        let mut kv_cache = vec![KVCache::new(Vec::new()); 3 * LAYER_COUNT];
+        assert!(!compact_kv_cache(&mut kv_cache, 4, TruncationWindow::Suffix));
+        assert!(kv_cache.iter().all(|entry| entry.positional_encoding.is_none()));
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zeta_kv_cache::{KVCacheConfig, UnifiedKVCache};
+
+const OPS_PER_TASK: u32 = 2_000;
+
+fn concurrent_store_retrieve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("concurrent_store_retrieve");
+
+    for threads in [1usize, 2, 4, 8] {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(threads)
+            .build()
+            .unwrap();
+
+        group.throughput(Throughput::Elements(threads as u64 * OPS_PER_TASK as u64 * 2));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let cache = Arc::new(UnifiedKVCache::new(KVCacheConfig::default()));
+                    let tasks: Vec<_> = (0..threads as u32)
+                        .map(|task| {
+                            let cache = Arc::clone(&cache);
+                            tokio::spawn(async move {
+                                for i in 0..OPS_PER_TASK {
+                                    let key = task * OPS_PER_TASK + i;
+                                    cache.store(key, i as f32, 0.9).await.unwrap();
+                                    cache.retrieve(key).await.unwrap();
+                                }
+                            })
+                        })
+                        .collect();
+
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                })
+            });
+        });
+    }
+
+    group.finish();
+}
+
+const BATCH_LEN: u32 = 4_096;
+
+fn batched_vs_single(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batched_vs_single");
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let items: Vec<(u32, f32, f32)> = (0..BATCH_LEN).map(|key| (key, key as f32, 0.9)).collect();
+    let keys: Vec<u32> = (0..BATCH_LEN).collect();
+    group.throughput(Throughput::Elements(BATCH_LEN as u64 * 2));
+
+    group.bench_function("single", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let cache = UnifiedKVCache::new(KVCacheConfig::default());
+                for &(key, value, salience) in &items {
+                    cache.store(key, value, salience).await.unwrap();
+                }
+                for &key in &keys {
+                    cache.retrieve(key).await.unwrap();
+                }
+            })
+        });
+    });
+
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let cache = UnifiedKVCache::new(KVCacheConfig::default());
+                cache.store_many(&items).await.unwrap();
+                cache.retrieve_many(&keys).await.unwrap();
+            })
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, concurrent_store_retrieve, batched_vs_single);
+criterion_main!(benches);
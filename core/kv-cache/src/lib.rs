@@ -20,11 +20,14 @@
 //! - llm-rs/src/kv_cache_manager.rs
 //! - zeta-vault-synergy implementations
 
+use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::path::Path;
 use dashmap::DashMap;
 use serde::{Serialize, Deserialize};
-use tokio::sync::RwLock;
 use anyhow::Result;
 use thiserror::Error;
 
@@ -38,11 +41,17 @@ pub enum KVCacheError {
     CacheMiss(String),
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    #[error("Compression error: {0}")]
+    Compression(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KVCacheConfig {
     pub precision: PrecisionLevel,
+    /// Number of blocks keys are spread over; must be at least 1, and
+    /// `UnifiedKVCache::new` raises 0 to 1
     pub block_size: usize,
     pub spot_capacity: usize,
     pub max_cache_items: usize,
@@ -50,24 +59,44 @@ pub struct KVCacheConfig {
     pub enable_debug_logging: bool,
     pub eviction_policy: EvictionPolicy,
     pub compression_enabled: bool,
+    /// Factor applied to every LFU frequency once per decay cycle
+    #[serde(default = "default_lfu_decay_factor")]
+    pub lfu_decay_factor: f64,
+    /// Number of block accesses between LFU decay cycles
+    #[serde(default = "default_lfu_decay_interval")]
+    pub lfu_decay_interval: u64,
+    /// Sliding window for entries written with `store_at`; `None` keeps every position
+    #[serde(default)]
+    pub window: Option<WindowConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum PrecisionLevel {
-    Int1,
-    Int2,
-    Int4,
-    Int8,
-    FP16,
-    FP32,
+/// Sliding window over position-keyed entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowConfig {
+    /// Number of most recent positions kept; must be at least 1, and
+    /// `UnifiedKVCache::new` raises 0 to 1
+    pub window_size: usize,
+}
+
+fn default_lfu_decay_factor() -> f64 {
+    0.5
 }
 
+fn default_lfu_decay_interval() -> u64 {
+    10_000
+}
+
+/// The cache stores precision with the same enum the quantizer uses
+pub use zeta_quantization::PrecisionLevel;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EvictionPolicy {
     LRU,
     LFU,
     SalienceBased,
     Adaptive,
+    /// Drop blocks not accessed within `max_age_secs`
+    TTL { max_age_secs: u64 },
 }
 
 impl Default for KVCacheConfig {
@@ -81,8 +110,110 @@ fn default() -> Self {
             enable_debug_logging: false,
             eviction_policy: EvictionPolicy::SalienceBased,
             compression_enabled: true,
+            lfu_decay_factor: default_lfu_decay_factor(),
+            lfu_decay_interval: default_lfu_decay_interval(),
+            window: None,
+        }
+    }
+}
+
+/// Number of recent adaptive evictions remembered for regret tracking
+pub const ADAPTIVE_HISTORY: usize = 64;
+/// How far one regretted eviction moves the adaptive blend
+pub const ADAPTIVE_LEARNING_RATE: f32 = 0.05;
+const INITIAL_RECENCY_WEIGHT: f32 = 0.3;
+const MIN_ADAPTIVE_WEIGHT: f32 = 0.05;
+
+/// Writes buffered on a compressed block are merged into its payload once they
+/// reach its capacity or this fraction (one in N) of its compressed entries
+const PENDING_MERGE_DIVISOR: usize = 4;
+
+/// Existing nodes a key written through `DataBlock::put` is linked to
+const GRAPH_NEIGHBOURS: usize = 4;
+/// Edges kept per node; the farthest are pruned beyond this
+const MAX_GRAPH_DEGREE: usize = 2 * GRAPH_NEIGHBOURS;
+/// Candidates kept while walking the navigation graph
+const GRAPH_SEARCH_WIDTH: usize = 16;
+/// Keys written through `DataBlock::put` before they are linked into the graph together
+const GRAPH_LINK_BATCH: usize = 32;
+
+/// A block evicted by the adaptive policy, with the scores it was judged on
+#[derive(Debug, Clone, Copy)]
+struct AdaptiveEviction {
+    block_id: usize,
+    salience: f32,
+    recency: f32,
+}
+
+/// Learned salience/recency blend for `EvictionPolicy::Adaptive`
+///
+/// When a recently evicted block is requested again, the eviction was a
+/// mistake; the blend moves toward whichever signal would have kept it.
+/// Requests only flag the evicted block; the flags are folded into the
+/// blend when it is next read.
+#[derive(Debug)]
+struct AdaptiveState {
+    recency_weight: f32,
+    recent_evictions: VecDeque<AdaptiveEviction>,
+}
+
+impl AdaptiveState {
+    fn new() -> Self {
+        Self {
+            recency_weight: INITIAL_RECENCY_WEIGHT,
+            recent_evictions: VecDeque::with_capacity(ADAPTIVE_HISTORY),
+        }
+    }
+
+    /// Remember `eviction`, watching its block for a later request
+    fn record_eviction(&mut self, eviction: AdaptiveEviction, requested: &DashMap<usize, AtomicBool>) {
+        // Only the latest eviction of a block is judged
+        self.recent_evictions.retain(|e| e.block_id != eviction.block_id);
+        if self.recent_evictions.len() == ADAPTIVE_HISTORY {
+            if let Some(oldest) = self.recent_evictions.pop_front() {
+                requested.remove(&oldest.block_id);
+            }
+        }
+        self.recent_evictions.push_back(eviction);
+        requested.insert(eviction.block_id, AtomicBool::new(false));
+    }
+
+    /// Apply the regret of every remembered eviction whose block was requested since
+    fn settle(&mut self, requested: &DashMap<usize, AtomicBool>) {
+        let mut regretted = Vec::new();
+        self.recent_evictions.retain(|e| {
+            let was_requested = requested.get(&e.block_id).is_some_and(|r| r.load(Ordering::Relaxed));
+            if was_requested {
+                regretted.push(*e);
+            }
+            !was_requested
+        });
+        for regret in regretted {
+            requested.remove(&regret.block_id);
+            self.apply_regret(regret);
         }
     }
+
+    fn apply_regret(&mut self, regret: AdaptiveEviction) {
+        let step = if regret.recency > regret.salience {
+            ADAPTIVE_LEARNING_RATE
+        } else if regret.salience > regret.recency {
+            -ADAPTIVE_LEARNING_RATE
+        } else {
+            0.0
+        };
+        self.recency_weight = (self.recency_weight + step)
+            .clamp(MIN_ADAPTIVE_WEIGHT, 1.0 - MIN_ADAPTIVE_WEIGHT);
+    }
+}
+
+/// Result of `UnifiedKVCache::store`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreOutcome {
+    /// The value was written to its block
+    Stored,
+    /// The salience score was below `salience_threshold`; nothing was written
+    Skipped,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -107,6 +238,21 @@ pub struct DataBlock {
     pub salience_scores: HashMap<u32, f32>,
     pub access_count: u64,
     pub last_accessed: u64,
+    /// LZ4-compressed `data` map; while set, `data` is empty
+    #[serde(default)]
+    pub compressed: Option<Vec<u8>>,
+    /// Writes made since the block was compressed, shadowing `compressed`
+    /// until `compress_if_due` merges them in
+    #[serde(default)]
+    pub pending: HashMap<u32, f32>,
+    /// Node of each id in `vector_ids`, covering its first `graph_indexed` entries
+    #[serde(skip)]
+    graph_nodes: HashMap<u32, usize>,
+    #[serde(skip)]
+    graph_indexed: usize,
+    /// Keys whose value was written since they were last linked into the graph
+    #[serde(skip)]
+    graph_stale: HashSet<u32>,
 }
 
 impl DataBlock {
@@ -124,6 +270,11 @@ pub fn new(id: usize, capacity: usize) -> Self {
             capacity,
             access_count: 0,
             last_accessed: 0,
+            compressed: None,
+            pending: HashMap::new(),
+            graph_nodes: HashMap::new(),
+            graph_indexed: 0,
+            graph_stale: HashSet::new(),
         }
     }
 
@@ -166,6 +317,372 @@ pub fn erase(&mut self) {
         self.size = 0;
         self.state = BlockState::Free;
         self.access_count = 0;
+        self.compressed = None;
+        self.pending.clear();
+        self.graph_nodes.clear();
+        self.graph_indexed = 0;
+        self.graph_stale.clear();
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed.is_some()
+    }
+
+    /// Look up a value, decoding the compressed payload without inflating the block
+    pub fn lookup(&self, token_id: u32) -> Result<Option<f32>, KVCacheError> {
+        if let Some(&value) = self.pending.get(&token_id) {
+            return Ok(Some(value));
+        }
+        match &self.compressed {
+            Some(bytes) => Self::decode_value(bytes, token_id),
+            None => Ok(self.data.get(&token_id).copied()),
+        }
+    }
+
+    /// Write a value, buffering it in `pending` while the block is compressed
+    ///
+    /// New and changed keys are linked into `navigation_graph` in batches of
+    /// `GRAPH_LINK_BATCH`, and when the block is next compressed; until then
+    /// `nearest` scans them directly.
+    pub fn put(&mut self, token_id: u32, value: f32) {
+        if self.compressed.is_some() {
+            self.pending.insert(token_id, value);
+        } else if self.data.insert(token_id, value) != Some(value) {
+            self.graph_stale.insert(token_id);
+            if self.graph_stale.len() >= GRAPH_LINK_BATCH {
+                self.link_stale();
+            }
+        }
+    }
+
+    /// Link every key of `data` that is not yet a node, and relink stale ones
+    /// against their current value, in key order
+    fn link_stale(&mut self) {
+        self.index_graph();
+        let mut stale: Vec<u32> = self.data.keys()
+            .filter(|id| self.graph_stale.contains(id) || !self.graph_nodes.contains_key(id))
+            .copied()
+            .collect();
+        self.graph_stale.clear();
+        stale.sort_unstable();
+        for token_id in stale {
+            match self.graph_nodes.get(&token_id) {
+                Some(&node) => self.relink_node(node),
+                None => self.insert_node(token_id),
+            }
+        }
+    }
+
+    /// Index `vector_ids` entries added since the last call, e.g. by `write`
+    /// or a restored snapshot. An id listed twice keeps its first node.
+    fn index_graph(&mut self) {
+        for node in self.graph_indexed..self.vector_ids.len() {
+            self.graph_nodes.entry(self.vector_ids[node]).or_insert(node);
+        }
+        self.graph_indexed = self.vector_ids.len();
+    }
+
+    /// Append a node for `token_id` linked both ways to the closest existing nodes
+    fn insert_node(&mut self, token_id: u32) {
+        let Some(&value) = self.data.get(&token_id) else {
+            return;
+        };
+        let neighbours = self.closest_nodes(value, None);
+        let node = self.vector_ids.len();
+        self.vector_ids.push(token_id);
+        self.graph_nodes.insert(token_id, node);
+        self.graph_indexed = self.vector_ids.len();
+        self.connect(node, value, neighbours);
+    }
+
+    /// Replace the edges of an existing node whose value changed with edges to
+    /// the nodes closest to its new value
+    fn relink_node(&mut self, node: usize) {
+        let Some(value) = self.node_value(node) else {
+            return;
+        };
+        // Search before dropping the old edges, so the walk can still leave the node
+        let neighbours = self.closest_nodes(value, Some(node));
+        for old in self.navigation_graph.remove(&node).unwrap_or_default() {
+            if let Some(edges) = self.navigation_graph.get_mut(&old) {
+                edges.retain(|&edge| edge != node);
+            }
+        }
+        self.connect(node, value, neighbours);
+    }
+
+    /// The `GRAPH_NEIGHBOURS` nodes closest to `value`, other than `exclude`
+    fn closest_nodes(&self, value: f32, exclude: Option<usize>) -> Vec<usize> {
+        let mut closest = self.walk(&self.data, value, GRAPH_SEARCH_WIDTH + 1);
+        closest.sort_unstable();
+        closest.into_iter()
+            .map(|(_, node)| node)
+            .filter(|&node| Some(node) != exclude)
+            .take(GRAPH_NEIGHBOURS)
+            .collect()
+    }
+
+    /// Link `node` both ways to `neighbours`, pruning each neighbour back to
+    /// its `MAX_GRAPH_DEGREE` closest edges
+    fn connect(&mut self, node: usize, value: f32, neighbours: Vec<usize>) {
+        for &neighbour in &neighbours {
+            let mut edges = self.navigation_graph.remove(&neighbour).unwrap_or_default();
+            edges.push(node);
+            if edges.len() > MAX_GRAPH_DEGREE {
+                let origin = self.node_value(neighbour).unwrap_or(value);
+                edges.sort_by_key(|&edge| {
+                    self.node_value(edge).map_or(u32::MAX, |v| (v - origin).abs().to_bits())
+                });
+                edges.truncate(MAX_GRAPH_DEGREE);
+            }
+            self.navigation_graph.insert(neighbour, edges);
+        }
+        self.navigation_graph.insert(node, neighbours);
+    }
+
+    fn node_value(&self, node: usize) -> Option<f32> {
+        self.data.get(self.vector_ids.get(node)?).copied()
+    }
+
+    /// Compress the block once it holds `capacity` entries. On an already
+    /// compressed block, merge buffered writes and recompress once there are
+    /// enough of them, so the cost of recompressing is spread over many writes.
+    pub fn compress_if_due(&mut self) -> Result<(), KVCacheError> {
+        let due = match &self.compressed {
+            Some(_) => self.pending.len() >= self.capacity.max(self.compressed_len() / PENDING_MERGE_DIVISOR),
+            None => self.data.len() >= self.capacity,
+        };
+        if due {
+            // Merged pending writes may change the values of existing nodes
+            self.graph_stale.extend(self.pending.keys().copied());
+            self.decompress()?;
+            self.link_stale();
+            self.compress();
+        }
+        Ok(())
+    }
+
+    /// Every stored entry, decoding a compressed block together with its pending writes
+    pub fn entries(&self) -> Result<Cow<'_, HashMap<u32, f32>>, KVCacheError> {
+        match &self.compressed {
+            Some(bytes) => {
+                let mut data = Self::decode_data(bytes)?;
+                data.extend(self.pending.iter().map(|(&k, &v)| (k, v)));
+                Ok(Cow::Owned(data))
+            }
+            None => Ok(Cow::Borrowed(&self.data)),
+        }
+    }
+
+    /// Replace `data` with its LZ4-compressed encoding
+    pub fn compress(&mut self) {
+        if self.compressed.is_some() {
+            return;
+        }
+
+        // Columnar layout with delta-encoded sorted keys compresses far better
+        // than interleaved pairs, since keys within a block share a stride
+        let mut entries: Vec<(u32, f32)> = self.data.iter().map(|(&k, &v)| (k, v)).collect();
+        entries.sort_unstable_by_key(|&(k, _)| k);
+
+        let mut raw = Vec::with_capacity(entries.len() * 8);
+        let mut previous = 0u32;
+        for &(key, _) in &entries {
+            raw.extend_from_slice(&key.wrapping_sub(previous).to_le_bytes());
+            previous = key;
+        }
+        for &(_, value) in &entries {
+            raw.extend_from_slice(&value.to_le_bytes());
+        }
+
+        self.compressed = Some(lz4_flex::compress_prepend_size(&raw));
+        self.data = HashMap::new();
+    }
+
+    /// Restore `data` from the compressed payload and pending writes so it can be mutated
+    pub fn decompress(&mut self) -> Result<(), KVCacheError> {
+        if let Some(bytes) = self.compressed.take() {
+            let mut data = Self::decode_data(&bytes)?;
+            data.extend(self.pending.drain());
+            self.data = data;
+        }
+        Ok(())
+    }
+
+    fn decode_raw(bytes: &[u8]) -> Result<Vec<u8>, KVCacheError> {
+        let raw = lz4_flex::decompress_size_prepended(bytes)
+            .map_err(|e| KVCacheError::Compression(e.to_string()))?;
+        if raw.len() % 8 != 0 {
+            return Err(KVCacheError::Compression(format!("corrupt block payload of {} bytes", raw.len())));
+        }
+        Ok(raw)
+    }
+
+    /// Find one key in a compressed payload without building the whole map
+    fn decode_value(bytes: &[u8], token_id: u32) -> Result<Option<f32>, KVCacheError> {
+        let raw = Self::decode_raw(bytes)?;
+        let (keys, values) = raw.split_at(raw.len() / 2);
+        let mut key = 0u32;
+        for (i, k) in keys.chunks_exact(4).enumerate() {
+            key = key.wrapping_add(u32::from_le_bytes([k[0], k[1], k[2], k[3]]));
+            if key == token_id {
+                let v = &values[i * 4..i * 4 + 4];
+                return Ok(Some(f32::from_le_bytes([v[0], v[1], v[2], v[3]])));
+            }
+            // Keys are stored in ascending order
+            if key > token_id {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    fn decode_data(bytes: &[u8]) -> Result<HashMap<u32, f32>, KVCacheError> {
+        let raw = Self::decode_raw(bytes)?;
+        let (keys, values) = raw.split_at(raw.len() / 2);
+        let mut key = 0u32;
+        Ok(keys.chunks_exact(4)
+            .zip(values.chunks_exact(4))
+            .map(|(k, v)| {
+                key = key.wrapping_add(u32::from_le_bytes([k[0], k[1], k[2], k[3]]));
+                (key, f32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+            })
+            .collect())
+    }
+
+    /// Number of stored key/value entries, without decompressing. A key
+    /// rewritten since the block was compressed counts twice until it is merged.
+    pub fn item_count(&self) -> usize {
+        match &self.compressed {
+            Some(_) => self.compressed_len() + self.pending.len(),
+            None => self.data.len(),
+        }
+    }
+
+    /// Number of entries in the compressed payload
+    fn compressed_len(&self) -> usize {
+        match &self.compressed {
+            // lz4_flex prepends the uncompressed length; each entry is 8 bytes
+            Some(bytes) if bytes.len() >= 4 => {
+                u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize / 8
+            }
+            _ => 0,
+        }
+    }
+
+    /// Approximate bytes held by the block's occupied entries
+    pub fn memory_footprint(&self) -> usize {
+        use std::mem::size_of;
+
+        let entry = size_of::<u32>() + size_of::<f32>();
+        let data = match &self.compressed {
+            Some(bytes) => bytes.len() + self.pending.len() * entry,
+            None => self.data.len() * entry,
+        };
+        let graph: usize = self.navigation_graph.values()
+            .map(|edges| size_of::<usize>() * (edges.len() + 1))
+            .sum();
+
+        data + graph
+            + self.pointers.len() * size_of::<usize>()
+            + self.biases.len() * size_of::<f32>()
+            + self.vector_ids.len() * size_of::<u32>()
+            + self.salience_scores.len() * (size_of::<u32>() + size_of::<f32>())
+    }
+
+    /// Ids of the `k` stored values closest to `query`, nearest first
+    ///
+    /// Nodes of `navigation_graph` are indices into `vector_ids`, each id naming
+    /// an entry of `data`; `put` adds a node per new key. The search starts at the
+    /// first node with a value and greedily expands the closest unvisited
+    /// neighbours, keeping the best `max(k, GRAPH_SEARCH_WIDTH)` seen, until no
+    /// candidate can improve on them. Pending writes and keys not yet linked
+    /// are scanned directly.
+    /// Blocks without a graph fall back to scanning every value.
+    pub fn nearest(&self, query: f32, k: usize) -> Vec<u32> {
+        self.nearest_entries(query, k).into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Greedy best-first search of `navigation_graph` for the `width` nodes
+    /// whose values in `data` are closest to `query`, as `(distance bits, node)`
+    fn walk(&self, data: &HashMap<u32, f32>, query: f32, width: usize) -> Vec<(u32, usize)> {
+        // Distances are non-negative, so their bit patterns sort like the floats
+        let distance = |node: usize| {
+            let id = self.vector_ids.get(node)?;
+            data.get(id).map(|&value| (value - query).abs().to_bits())
+        };
+        let Some((entry, first)) = (0..self.vector_ids.len())
+            .find_map(|node| distance(node).map(|dist| (node, dist)))
+        else {
+            return Vec::new();
+        };
+
+        let mut visited = HashSet::from([entry]);
+        let mut candidates = BinaryHeap::from([Reverse((first, entry))]);
+        let mut best = BinaryHeap::from([(first, entry)]);
+        while let Some(Reverse((dist, node))) = candidates.pop() {
+            if best.len() == width && best.peek().is_some_and(|&(worst, _)| dist > worst) {
+                break;
+            }
+            for &neighbour in self.navigation_graph.get(&node).into_iter().flatten() {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                match distance(neighbour) {
+                    Some(dist) => {
+                        if best.len() < width || best.peek().is_some_and(|&(worst, _)| dist < worst) {
+                            candidates.push(Reverse((dist, neighbour)));
+                            best.push((dist, neighbour));
+                            if best.len() > width {
+                                best.pop();
+                            }
+                        }
+                    }
+                    // A deleted key keeps its node, so the walk still passes through it
+                    None => candidates.push(Reverse((dist, neighbour))),
+                }
+            }
+        }
+        best.into_vec()
+    }
+
+    /// `(id, value)` pairs behind `nearest`
+    fn nearest_entries(&self, query: f32, k: usize) -> Vec<(u32, f32)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let data = match self.entries() {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Skipping block {} in nearest-neighbour search: {}", self.id, e);
+                return Vec::new();
+            }
+        };
+        // Distances are non-negative, so their bit patterns sort like the floats
+        let distance = |value: f32| (value - query).abs().to_bits();
+
+        let mut found: Vec<(u32, u32, f32)> = if self.navigation_graph.is_empty() {
+            data.iter().map(|(&id, &value)| (distance(value), id, value)).collect()
+        } else {
+            let mut found: Vec<(u32, u32, f32)> = self.walk(&data, query, k.max(GRAPH_SEARCH_WIDTH))
+                .into_iter()
+                .filter_map(|(dist, node)| {
+                    let id = self.vector_ids[node];
+                    data.get(&id).map(|&value| (dist, id, value))
+                })
+                .collect();
+            // Pending writes only join the graph when they are merged, and
+            // other writes once a batch of them has built up
+            found.extend(self.pending.iter().map(|(&id, &value)| (distance(value), id, value)));
+            found.extend(self.graph_stale.iter()
+                .filter_map(|id| data.get(id).map(|&value| (distance(value), *id, value))));
+            found
+        };
+
+        found.sort_unstable_by_key(|&(dist, id, _)| (dist, id));
+        found.dedup_by_key(|&mut (_, id, _)| id);
+        found.truncate(k);
+        found.into_iter().map(|(_, id, value)| (id, value)).collect()
     }
 }
 
@@ -174,58 +691,147 @@ pub struct UnifiedKVCache {
     config: KVCacheConfig,
     blocks: DashMap<usize, DataBlock>,
     valid_bitmap: DashMap<(usize, usize), bool>,
-    lock: Arc<Mutex<()>>,
-    access_order: Arc<RwLock<Vec<usize>>>, // For LRU
-    access_frequency: Arc<RwLock<HashMap<usize, u64>>>, // For LFU
+    access_order: DashMap<usize, u64>, // For LRU: block -> logical access time
+    access_frequency: DashMap<usize, f64>, // For LFU: decayed access counts
+    access_clock: AtomicU64,
+    evicting: AtomicBool,
+    last_expiry_sweep: AtomicU64, // Unix seconds of the last TTL sweep
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    adaptive: Mutex<AdaptiveState>,
+    /// Blocks the adaptive policy evicted recently, flagged once requested again
+    adaptive_requests: DashMap<usize, AtomicBool>,
+    /// Entries written by `store_at`, sharded by position
+    positions: DashMap<usize, HashMap<u32, f32>>,
+    /// One past the highest position written so far; 0 before any `store_at`
+    position_end: AtomicUsize,
+    /// Every position below this has been dropped by the window
+    position_floor: AtomicUsize,
 }
 
 impl UnifiedKVCache {
-    pub fn new(config: KVCacheConfig) -> Self {
+    pub fn new(mut config: KVCacheConfig) -> Self {
+        if config.block_size == 0 {
+            tracing::warn!("KV cache block_size of 0 is invalid, using 1");
+            config.block_size = 1;
+        }
+        if let Some(window) = &mut config.window {
+            if window.window_size == 0 {
+                tracing::warn!("KV cache window_size of 0 is invalid, using 1");
+                window.window_size = 1;
+            }
+        }
         Self {
             config,
             blocks: DashMap::new(),
             valid_bitmap: DashMap::new(),
-            lock: Arc::new(Mutex::new(())),
-            access_order: Arc::new(RwLock::new(Vec::new())),
-            access_frequency: Arc::new(RwLock::new(HashMap::new())),
+            access_order: DashMap::new(),
+            access_frequency: DashMap::new(),
+            access_clock: AtomicU64::new(0),
+            evicting: AtomicBool::new(false),
+            last_expiry_sweep: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            adaptive: Mutex::new(AdaptiveState::new()),
+            adaptive_requests: DashMap::new(),
+            positions: DashMap::new(),
+            position_end: AtomicUsize::new(0),
+            position_floor: AtomicUsize::new(0),
         }
     }
 
-    pub async fn store(&self, key: u32, value: f32, salience_score: f32) -> Result<(), KVCacheError> {
+    pub async fn store(&self, key: u32, value: f32, salience_score: f32) -> Result<StoreOutcome, KVCacheError> {
         if salience_score < self.config.salience_threshold {
-            return Ok(()); // Skip low salience items
+            return Ok(StoreOutcome::Skipped); // Skip low salience items
         }
 
         let block_id = (key as usize) % self.config.block_size;
+        self.record_adaptive_access(block_id);
         
         {
-            let _guard = self.lock.lock().unwrap();
+            // DashMap's shard lock serializes writers to the same block only
             let mut block = self.blocks.entry(block_id).or_insert_with(|| {
                 DataBlock::new(block_id, self.config.block_size)
             });
 
-            block.data.insert(key, value);
+            block.put(key, value);
+            if self.config.compression_enabled {
+                block.compress_if_due()?;
+            }
             block.update_salience(key, salience_score);
+            block.state = BlockState::Valid;
             block.access_count += 1;
             block.last_accessed = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs();
-        } // Guard is dropped here
+        } // Shard lock is released here
 
         // Update access tracking for eviction policies
-        self.update_access_tracking(block_id).await;
+        self.update_access_tracking(block_id);
+        self.enforce_limits().await?;
 
-        // Check if eviction is needed
-        if self.blocks.len() > self.config.max_cache_items {
-            self.evict_blocks().await?;
+        Ok(StoreOutcome::Stored)
+    }
+
+    /// Store a batch of `(key, value, salience)` items, touching each block once.
+    /// Outcomes are returned in input order.
+    pub async fn store_many(&self, items: &[(u32, f32, f32)]) -> Result<Vec<StoreOutcome>, KVCacheError> {
+        let mut outcomes = vec![StoreOutcome::Skipped; items.len()];
+        let grouped = self.group_by_block(
+            items.iter().enumerate()
+                .filter(|(_, &(_, _, salience_score))| salience_score >= self.config.salience_threshold)
+                .map(|(i, &(key, _, _))| (i, key)),
+        );
+
+        let now = unix_timestamp();
+        for indices in block_runs(&grouped) {
+            let block_id = indices[0].0;
+            self.record_adaptive_access(block_id);
+            {
+                let mut block = self.blocks.entry(block_id).or_insert_with(|| {
+                    DataBlock::new(block_id, self.config.block_size)
+                });
+
+                for &(_, i) in indices {
+                    let (key, value, salience_score) = items[i];
+                    block.put(key, value);
+                    block.update_salience(key, salience_score);
+                    outcomes[i] = StoreOutcome::Stored;
+                }
+                if self.config.compression_enabled {
+                    block.compress_if_due()?;
+                }
+                block.state = BlockState::Valid;
+                block.access_count += indices.len() as u64;
+                block.last_accessed = now;
+            }
+
+            self.update_access_tracking(block_id);
         }
 
-        Ok(())
+        self.enforce_limits().await?;
+        Ok(outcomes)
+    }
+
+    /// Run the TTL sweep and capacity eviction that follow a write
+    async fn enforce_limits(&self) -> Result<(), KVCacheError> {
+        // Age out stale blocks regardless of cache pressure, at most once per second
+        if let EvictionPolicy::TTL { max_age_secs } = self.config.eviction_policy {
+            let now = unix_timestamp();
+            if self.last_expiry_sweep.swap(now, Ordering::AcqRel) != now {
+                self.remove_blocks(self.select_expired_blocks(max_age_secs, now));
+            }
+        }
+
+        self.enforce_capacity().await
     }
 
     pub async fn retrieve(&self, key: u32) -> Result<Option<f32>, KVCacheError> {
         let block_id = (key as usize) % self.config.block_size;
+        self.record_adaptive_access(block_id);
         
         if let Some(mut block) = self.blocks.get_mut(&block_id) {
             block.access_count += 1;
@@ -234,64 +840,353 @@ pub async fn retrieve(&self, key: u32) -> Result<Option<f32>, KVCacheError> {
                 .unwrap_or_default()
                 .as_secs();
 
-            self.update_access_tracking(block_id).await;
-            Ok(block.data.get(&key).copied())
+            let value = block.lookup(key)?;
+            drop(block);
+
+            self.update_access_tracking(block_id);
+            self.record_lookup(value.is_some());
+            Ok(value)
         } else {
+            self.record_lookup(false);
             Ok(None)
         }
     }
 
+    /// Look up a batch of keys, touching each block once.
+    /// Results are returned in input order.
+    pub async fn retrieve_many(&self, keys: &[u32]) -> Result<Vec<Option<f32>>, KVCacheError> {
+        let mut results = vec![None; keys.len()];
+        let grouped = self.group_by_block(keys.iter().copied().enumerate());
+
+        let now = unix_timestamp();
+        let mut hits = 0;
+        for indices in block_runs(&grouped) {
+            let block_id = indices[0].0;
+            self.record_adaptive_access(block_id);
+            let Some(mut block) = self.blocks.get_mut(&block_id) else {
+                continue;
+            };
+            block.access_count += indices.len() as u64;
+            block.last_accessed = now;
+
+            // Decode a compressed block once for the whole group
+            let data = block.entries()?;
+            for &(_, i) in indices {
+                results[i] = data.get(&keys[i]).copied();
+                hits += results[i].is_some() as u64;
+            }
+            drop(data);
+            drop(block);
+
+            self.update_access_tracking(block_id);
+        }
+
+        self.hits.fetch_add(hits, Ordering::Relaxed);
+        self.misses.fetch_add(keys.len() as u64 - hits, Ordering::Relaxed);
+        Ok(results)
+    }
+
+    /// Store `value` for `key` at sequence `position`.
+    ///
+    /// Unlike `store`, the same key at two positions is kept as two entries.
+    /// With a `window` configured, writing a new highest position drops every
+    /// entry `window_size` or more positions behind it. Each position counts
+    /// as one item toward `max_cache_items`.
+    pub async fn store_at(&self, position: usize, key: u32, value: f32, salience_score: f32) -> Result<StoreOutcome, KVCacheError> {
+        let end = position.saturating_add(1);
+        let end = self.position_end.fetch_max(end, Ordering::AcqRel).max(end);
+        self.drop_positions_before(end);
+
+        if salience_score < self.config.salience_threshold || !self.position_in_window(position) {
+            return Ok(StoreOutcome::Skipped);
+        }
+        self.positions.entry(position).or_default().insert(key, value);
+
+        // A concurrent writer may have moved the window past `position` meanwhile
+        if !self.position_in_window(position) {
+            if let Some((_, entries)) = self.positions.remove(&position) {
+                self.evictions.fetch_add(entries.len() as u64, Ordering::Relaxed);
+            }
+            return Ok(StoreOutcome::Skipped);
+        }
+
+        self.enforce_capacity().await?;
+        Ok(StoreOutcome::Stored)
+    }
+
+    fn position_in_window(&self, position: usize) -> bool {
+        match self.config.window {
+            Some(window) => position.saturating_add(window.window_size) >= self.position_end.load(Ordering::Acquire),
+            None => true,
+        }
+    }
+
+    /// Drop the positions that fall out of a window ending just before `end`
+    fn drop_positions_before(&self, end: usize) {
+        let Some(window) = self.config.window else {
+            return;
+        };
+        let oldest_kept = end.saturating_sub(window.window_size);
+        let floor = self.position_floor.fetch_max(oldest_kept, Ordering::AcqRel);
+        if floor >= oldest_kept {
+            return;
+        }
+
+        // Walk the skipped range when it is short, otherwise scan what is held
+        let dropped = if oldest_kept - floor <= self.positions.len() {
+            (floor..oldest_kept)
+                .filter_map(|position| self.positions.remove(&position))
+                .map(|(_, entries)| entries.len())
+                .sum()
+        } else {
+            let mut dropped = 0;
+            self.positions.retain(|&position, entries| {
+                let keep = position >= oldest_kept;
+                if !keep {
+                    dropped += entries.len();
+                }
+                keep
+            });
+            dropped
+        };
+        self.evictions.fetch_add(dropped as u64, Ordering::Relaxed);
+    }
+
+    /// Approximate `k` nearest neighbours of `query` by value across all blocks,
+    /// as `(key, value)` pairs nearest first. See `DataBlock::nearest`.
+    pub async fn retrieve_approx(&self, query: f32, k: usize) -> Vec<(u32, f32)> {
+        let mut found: Vec<(u32, f32)> = self.blocks.iter()
+            .flat_map(|entry| entry.value().nearest_entries(query, k))
+            .collect();
+        found.sort_by(|a, b| {
+            (a.1 - query).abs().total_cmp(&(b.1 - query).abs()).then(a.0.cmp(&b.0))
+        });
+        found.truncate(k);
+        found
+    }
+
+    /// Look up the value stored for `key` at `position` by `store_at`
+    pub async fn retrieve_at(&self, position: usize, key: u32) -> Result<Option<f32>, KVCacheError> {
+        let value = self.positions.get(&position).and_then(|entries| entries.get(&key).copied());
+        self.record_lookup(value.is_some());
+        Ok(value)
+    }
+
+    /// Sort `(index, key)` pairs into `(block_id, index)` runs
+    fn group_by_block(&self, keys: impl Iterator<Item = (usize, u32)>) -> Vec<(usize, usize)> {
+        let mut grouped: Vec<(usize, usize)> = keys
+            .map(|(i, key)| ((key as usize) % self.config.block_size, i))
+            .collect();
+        grouped.sort_unstable();
+        grouped
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Remove a single key, dropping its block once the block is empty.
+    /// Returns whether the key was present.
+    pub async fn delete(&self, key: u32) -> Result<bool, KVCacheError> {
+        let block_id = (key as usize) % self.config.block_size;
+
+        let (removed, block_empty) = match self.blocks.get_mut(&block_id) {
+            Some(mut block) => {
+                block.decompress()?;
+                let removed = block.data.remove(&key).is_some();
+                block.salience_scores.remove(&key);
+                (removed, block.data.is_empty())
+            }
+            None => return Ok(false),
+        };
+
+        if block_empty {
+            self.blocks.remove(&block_id);
+            self.access_order.remove(&block_id);
+            self.access_frequency.remove(&block_id);
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove every block and reset eviction bookkeeping
+    pub async fn clear(&self) {
+        self.blocks.clear();
+        self.valid_bitmap.clear();
+        self.access_order.clear();
+        self.access_frequency.clear();
+        self.positions.clear();
+        self.position_end.store(0, Ordering::Release);
+        self.position_floor.store(0, Ordering::Release);
+    }
+
     pub async fn get_salience(&self, key: u32) -> Option<f32> {
         let block_id = (key as usize) % self.config.block_size;
         self.blocks.get(&block_id)?.get_salience(key)
     }
 
-    async fn update_access_tracking(&self, block_id: usize) {
+    fn update_access_tracking(&self, block_id: usize) {
         match self.config.eviction_policy {
-            EvictionPolicy::LRU => {
-                let mut access_order = self.access_order.write().await;
-                access_order.retain(|&id| id != block_id);
-                access_order.push(block_id);
+            EvictionPolicy::LRU | EvictionPolicy::TTL { .. } | EvictionPolicy::Adaptive => {
+                let tick = self.access_clock.fetch_add(1, Ordering::Relaxed);
+                self.access_order.insert(block_id, tick);
             }
             EvictionPolicy::LFU => {
-                let mut access_frequency = self.access_frequency.write().await;
-                *access_frequency.entry(block_id).or_insert(0) += 1;
+                *self.access_frequency.entry(block_id).or_insert(0.0) += 1.0;
+
+                let tick = self.access_clock.fetch_add(1, Ordering::Relaxed) + 1;
+                if tick % self.config.lfu_decay_interval.max(1) == 0 {
+                    self.decay_frequencies();
+                }
             }
             _ => {} // Other policies handled elsewhere
         }
     }
 
+    /// Flag a request for a block the adaptive policy recently evicted
+    ///
+    /// Only touches that block's flag; regrets are tallied in `adaptive_weights`.
+    fn record_adaptive_access(&self, block_id: usize) {
+        if matches!(self.config.eviction_policy, EvictionPolicy::Adaptive) {
+            if let Some(requested) = self.adaptive_requests.get(&block_id) {
+                requested.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Current `(salience, recency)` blend used by `EvictionPolicy::Adaptive`
+    pub fn adaptive_weights(&self) -> (f32, f32) {
+        let recency = self
+            .adaptive
+            .lock()
+            .map(|mut a| {
+                a.settle(&self.adaptive_requests);
+                a.recency_weight
+            })
+            .unwrap_or(INITIAL_RECENCY_WEIGHT);
+        (1.0 - recency, recency)
+    }
+
+    /// Scale every LFU frequency by `lfu_decay_factor` so that blocks which
+    /// were hot long ago lose out to blocks that are hot now
+    pub fn decay_frequencies(&self) {
+        let factor = self.config.lfu_decay_factor;
+        self.access_frequency.alter_all(|_, freq| freq * factor);
+    }
+
+    /// Evict once blocks plus `store_at` positions exceed `max_cache_items`,
+    /// taking from whichever of the two holds more
+    async fn enforce_capacity(&self) -> Result<(), KVCacheError> {
+        let (blocks, positions) = (self.blocks.len(), self.positions.len());
+        if blocks + positions <= self.config.max_cache_items {
+            return Ok(());
+        }
+        if positions > blocks {
+            self.evict_oldest_positions();
+            Ok(())
+        } else {
+            self.evict_blocks().await
+        }
+    }
+
+    /// Drop the oldest quarter of the positions written by `store_at`
+    fn evict_oldest_positions(&self) {
+        if self.evicting.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let mut held: Vec<usize> = self.positions.iter().map(|entry| *entry.key()).collect();
+        let evict_count = (held.len() / 4).max(1).min(held.len());
+        if evict_count > 0 {
+            held.select_nth_unstable(evict_count - 1);
+            let dropped: usize = held[..evict_count].iter()
+                .filter_map(|position| self.positions.remove(position))
+                .map(|(_, entries)| entries.len())
+                .sum();
+            self.evictions.fetch_add(dropped as u64, Ordering::Relaxed);
+        }
+        self.evicting.store(false, Ordering::Release);
+    }
+
     async fn evict_blocks(&self) -> Result<(), KVCacheError> {
+        // Only one task evicts at a time; concurrent writers that also crossed
+        // the threshold skip instead of evicting the same 25% again
+        if self.evicting.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+
         let blocks_to_evict = match self.config.eviction_policy {
             EvictionPolicy::LRU => self.select_lru_blocks().await,
             EvictionPolicy::LFU => self.select_lfu_blocks().await,
             EvictionPolicy::SalienceBased => self.select_low_salience_blocks().await,
             EvictionPolicy::Adaptive => self.select_adaptive_blocks().await,
+            EvictionPolicy::TTL { max_age_secs } => {
+                // Fall back to LRU when the cache is full of fresh blocks
+                let expired = self.select_expired_blocks(max_age_secs, unix_timestamp());
+                if expired.is_empty() { self.select_lru_blocks().await } else { expired }
+            }
         };
 
-        for block_id in blocks_to_evict {
+        self.remove_blocks(blocks_to_evict);
+        self.evicting.store(false, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Evict every block older than the TTL policy's `max_age_secs`.
+    /// Intended to be driven by a timer; a no-op under other policies.
+    /// Returns the number of blocks removed.
+    pub async fn evict_expired(&self) -> usize {
+        match self.config.eviction_policy {
+            EvictionPolicy::TTL { max_age_secs } => {
+                self.remove_blocks(self.select_expired_blocks(max_age_secs, unix_timestamp()))
+            }
+            _ => 0,
+        }
+    }
+
+    fn remove_blocks(&self, block_ids: Vec<usize>) -> usize {
+        let mut removed = 0;
+        for block_id in block_ids {
             if let Some(mut block) = self.blocks.get_mut(&block_id) {
                 block.erase();
             }
-            self.blocks.remove(&block_id);
+            if self.blocks.remove(&block_id).is_some() {
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                removed += 1;
+            }
+            self.access_order.remove(&block_id);
+            self.access_frequency.remove(&block_id);
         }
+        removed
+    }
 
-        Ok(())
+    fn select_expired_blocks(&self, max_age_secs: u64, now: u64) -> Vec<usize> {
+        let cutoff = now.saturating_sub(max_age_secs);
+        self.blocks.iter()
+            .filter(|entry| entry.value().last_accessed < cutoff)
+            .map(|entry| *entry.key())
+            .collect()
     }
 
     async fn select_lru_blocks(&self) -> Vec<usize> {
-        let access_order = self.access_order.read().await;
+        let mut order: Vec<(usize, u64)> = self.access_order.iter().map(|entry| (*entry.key(), *entry.value())).collect();
+        order.sort_by_key(|&(_, tick)| tick);
+
         let evict_count = (self.blocks.len() / 4).max(1); // Evict 25%
-        access_order.iter().take(evict_count).copied().collect()
+        order.iter().take(evict_count).map(|&(id, _)| id).collect()
     }
 
     async fn select_lfu_blocks(&self) -> Vec<usize> {
-        let access_frequency = self.access_frequency.read().await;
-        let mut freq_blocks: Vec<_> = access_frequency.iter().collect();
-        freq_blocks.sort_by_key(|(_, &freq)| freq);
+        let mut freq_blocks: Vec<(usize, f64)> = self.access_frequency.iter().map(|entry| (*entry.key(), *entry.value())).collect();
+        freq_blocks.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
         
         let evict_count = (self.blocks.len() / 4).max(1);
-        freq_blocks.iter().take(evict_count).map(|(&id, _)| id).collect()
+        freq_blocks.iter().take(evict_count).map(|&(id, _)| id).collect()
     }
 
     async fn select_low_salience_blocks(&self) -> Vec<usize> {
@@ -309,39 +1204,132 @@ async fn select_low_salience_blocks(&self) -> Vec<usize> {
     }
 
     async fn select_adaptive_blocks(&self) -> Vec<usize> {
-        // Adaptive policy combines salience and access patterns
+        // Blend salience with recency (last access tick relative to the clock),
+        // using weights learned from previous evictions
+        let (salience_weight, recency_weight) = self.adaptive_weights();
+        let clock = self.access_clock.load(Ordering::Relaxed).max(1) as f32;
         let mut adaptive_scores = Vec::new();
         
         for entry in self.blocks.iter() {
             let (block_id, block) = entry.pair();
             let avg_salience: f32 = block.salience_scores.values().sum::<f32>() / block.salience_scores.len().max(1) as f32;
-            let recency_score = 1.0 / (block.access_count as f32 + 1.0);
-            let adaptive_score = avg_salience * 0.7 + recency_score * 0.3;
-            adaptive_scores.push((*block_id, adaptive_score));
+            let recency_score = self.access_order.get(block_id).map_or(0.0, |tick| *tick as f32 / clock);
+            let adaptive_score = avg_salience * salience_weight + recency_score * recency_weight;
+            adaptive_scores.push((*block_id, adaptive_score, avg_salience, recency_score));
         }
 
         adaptive_scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
         let evict_count = (self.blocks.len() / 4).max(1);
-        adaptive_scores.iter().take(evict_count).map(|(id, _)| *id).collect()
+        adaptive_scores.truncate(evict_count);
+
+        if let Ok(mut adaptive) = self.adaptive.lock() {
+            for &(block_id, _, salience, recency) in &adaptive_scores {
+                adaptive.record_eviction(AdaptiveEviction { block_id, salience, recency }, &self.adaptive_requests);
+            }
+        }
+        adaptive_scores.iter().map(|&(id, ..)| id).collect()
+    }
+
+    /// Write all valid blocks and eviction bookkeeping to `path` as JSON
+    pub fn snapshot_to(&self, path: &Path) -> Result<(), KVCacheError> {
+        let positioned = self.positions.iter()
+            .flat_map(|entry| {
+                let position = *entry.key();
+                entry.value().iter().map(|(&key, &value)| (position, key, value)).collect::<Vec<_>>()
+            })
+            .collect();
+        let latest_position = self.position_end.load(Ordering::Acquire).checked_sub(1);
+        let snapshot = KVCacheSnapshot {
+            config: self.config.clone(),
+            blocks: self.blocks.iter()
+                .filter(|entry| entry.value().state == BlockState::Valid)
+                .map(|entry| entry.value().clone())
+                .collect(),
+            access_order: self.access_order.iter().map(|entry| (*entry.key(), *entry.value())).collect(),
+            access_frequency: self.access_frequency.iter().map(|entry| (*entry.key(), *entry.value())).collect(),
+            access_clock: self.access_clock.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            positioned,
+            latest_position,
+        };
+
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, &snapshot)?;
+        Ok(())
+    }
+
+    /// Rebuild a cache from a snapshot written by `snapshot_to`
+    pub fn restore_from(path: &Path) -> Result<Self, KVCacheError> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let snapshot: KVCacheSnapshot = serde_json::from_reader(file)?;
+
+        let mut cache = Self::new(snapshot.config);
+        for block in snapshot.blocks {
+            cache.blocks.insert(block.id, block);
+        }
+        cache.access_order.extend(snapshot.access_order);
+        cache.access_frequency.extend(snapshot.access_frequency);
+        cache.access_clock.store(snapshot.access_clock, Ordering::Relaxed);
+        cache.hits.store(snapshot.hits, Ordering::Relaxed);
+        cache.misses.store(snapshot.misses, Ordering::Relaxed);
+        cache.evictions.store(snapshot.evictions, Ordering::Relaxed);
+        for (position, key, value) in snapshot.positioned {
+            cache.positions.entry(position).or_default().insert(key, value);
+        }
+        let end = snapshot.latest_position.map_or(0, |latest| latest.saturating_add(1));
+        cache.position_end.store(end, Ordering::Release);
+
+        Ok(cache)
     }
 
     pub fn get_stats(&self) -> KVCacheStats {
         let total_blocks = self.blocks.len();
         let valid_blocks = self.blocks.iter().filter(|entry| entry.value().state == BlockState::Valid).count();
-        let total_items: usize = self.blocks.iter().map(|entry| entry.value().size).sum();
-        let memory_usage = total_blocks * self.config.block_size * std::mem::size_of::<f32>();
+        let positioned_items: usize = self.positions.iter().map(|entry| entry.value().len()).sum();
+        let total_items = positioned_items
+            + self.blocks.iter().map(|entry| entry.value().item_count()).sum::<usize>();
+        let memory_usage = positioned_items * (std::mem::size_of::<u32>() + std::mem::size_of::<f32>())
+            + self.positions.len() * std::mem::size_of::<usize>()
+            + self.blocks.iter().map(|entry| entry.value().memory_footprint()).sum::<usize>();
+
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let hit_rate = if hits + misses > 0 {
+            hits as f32 / (hits + misses) as f32
+        } else {
+            0.0
+        };
 
         KVCacheStats {
             total_blocks,
             valid_blocks,
             total_items,
             memory_usage_bytes: memory_usage,
-            hit_rate: 0.0, // Would need to track hits/misses
-            eviction_count: 0, // Would need to track evictions
+            hit_rate,
+            eviction_count: self.evictions.load(Ordering::Relaxed),
         }
     }
 }
 
+/// On-disk representation of a `UnifiedKVCache`
+#[derive(Serialize, Deserialize)]
+struct KVCacheSnapshot {
+    config: KVCacheConfig,
+    blocks: Vec<DataBlock>,
+    access_order: Vec<(usize, u64)>,
+    access_frequency: Vec<(usize, f64)>,
+    access_clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    #[serde(default)]
+    positioned: Vec<(usize, u32, f32)>,
+    #[serde(default)]
+    latest_position: Option<usize>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KVCacheStats {
     pub total_blocks: usize,
@@ -352,6 +1340,25 @@ pub struct KVCacheStats {
     pub eviction_count: u64,
 }
 
+/// Split block-sorted `(block_id, index)` pairs into one slice per block
+fn block_runs(grouped: &[(usize, usize)]) -> impl Iterator<Item = &[(usize, usize)]> {
+    let mut rest = grouped;
+    std::iter::from_fn(move || {
+        let block_id = rest.first()?.0;
+        let len = rest.iter().take_while(|&&(id, _)| id == block_id).count();
+        let (run, tail) = rest.split_at(len);
+        rest = tail;
+        Some(run)
+    })
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Factory function to create KV cache instances
 pub fn create_kv_cache(config: KVCacheConfig) -> UnifiedKVCache {
     UnifiedKVCache::new(config)
@@ -377,22 +1384,27 @@ pub fn new(cache: UnifiedKVCache) -> Self {
             cache: Arc::new(cache),
         }
     }
+
+    /// Convert a string key to the cache's u32 key space
+    fn key_hash(key: &str) -> u32 {
+        key.chars().map(|c| c as u32).sum::<u32>()
+    }
 }
 
 #[async_trait::async_trait]
 impl KVCacheManager for KVCacheManagerAdapter {
     async fn store(&self, key: String, value: Vec<u8>) -> Result<()> {
-        // Convert string key to u32 hash
-        let key_hash = key.chars().map(|c| c as u32).sum::<u32>();
+        let key_hash = Self::key_hash(&key);
         
         // Store as f32 (simplified for this trait implementation)
         let value_f32 = value.len() as f32;
         self.cache.store(key_hash, value_f32, 1.0).await
+            .map(|_| ())
             .map_err(|e| anyhow::anyhow!("Store failed: {}", e))
     }
 
     async fn retrieve(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        let key_hash = key.chars().map(|c| c as u32).sum::<u32>();
+        let key_hash = Self::key_hash(key);
         match self.cache.retrieve(key_hash).await? {
             Some(value) => {
                 // Convert f32 back to bytes (simplified)
@@ -403,13 +1415,528 @@ async fn retrieve(&self, key: &str) -> Result<Option<Vec<u8>>> {
         }
     }
 
-    async fn delete(&self, _key: &str) -> Result<bool> {
-        // Implementation would require adding delete method to UnifiedKVCache
-        Ok(true) // Placeholder
+    async fn delete(&self, key: &str) -> Result<bool> {
+        self.cache.delete(Self::key_hash(key)).await
+            .map_err(|e| anyhow::anyhow!("Delete failed: {}", e))
     }
 
     async fn clear(&self) -> Result<()> {
-        // Implementation would require adding clear method to UnifiedKVCache
-        Ok(()) // Placeholder
+        self.cache.clear().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delete_removes_key() {
+        let cache = UnifiedKVCache::new(KVCacheConfig::default());
+        cache.store(7, 1.5, 0.9).await.unwrap();
+        cache.store(7 + 1024, 2.5, 0.9).await.unwrap();
+
+        assert!(cache.delete(7).await.unwrap());
+        assert!(!cache.delete(7).await.unwrap());
+        assert_eq!(cache.retrieve(7).await.unwrap(), None);
+        assert_eq!(cache.retrieve(7 + 1024).await.unwrap(), Some(2.5));
+    }
+
+    #[tokio::test]
+    async fn test_clear_empties_cache() {
+        let cache = UnifiedKVCache::new(KVCacheConfig::default());
+        for key in 0..32 {
+            cache.store(key, key as f32, 0.9).await.unwrap();
+        }
+        assert_eq!(cache.get_stats().total_blocks, 32);
+
+        cache.clear().await;
+        assert_eq!(cache.get_stats().total_blocks, 0);
+        assert_eq!(cache.retrieve(3).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_adapter_delegates_delete_and_clear() {
+        let adapter = KVCacheManagerAdapter::new(UnifiedKVCache::new(KVCacheConfig::default()));
+        adapter.store("alpha".to_string(), vec![1, 2, 3]).await.unwrap();
+
+        assert!(adapter.delete("alpha").await.unwrap());
+        assert_eq!(adapter.retrieve("alpha").await.unwrap(), None);
+
+        adapter.store("beta".to_string(), vec![1]).await.unwrap();
+        adapter.clear().await.unwrap();
+        assert_eq!(adapter.retrieve("beta").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_hit_rate_and_eviction_count() {
+        let cache = UnifiedKVCache::new(KVCacheConfig::default());
+        for key in 0..4 {
+            cache.store(key, key as f32, 0.9).await.unwrap();
+        }
+        for key in 0..4 {
+            assert!(cache.retrieve(key).await.unwrap().is_some());
+        }
+        for key in 100..106 {
+            assert!(cache.retrieve(key).await.unwrap().is_none());
+        }
+
+        let stats = cache.get_stats();
+        assert!((stats.hit_rate - 0.4).abs() < 1e-6);
+        assert_eq!(stats.eviction_count, 0);
+
+        let small = UnifiedKVCache::new(KVCacheConfig {
+            max_cache_items: 4,
+            ..Default::default()
+        });
+        for key in 0..8 {
+            small.store(key, 1.0, 0.9).await.unwrap();
+        }
+        assert!(small.get_stats().eviction_count > 0);
+    }
+
+    #[tokio::test]
+    async fn test_compression_shrinks_full_blocks() {
+        let footprint = |compression_enabled: bool| async move {
+            let cache = UnifiedKVCache::new(KVCacheConfig {
+                block_size: 256,
+                compression_enabled,
+                ..Default::default()
+            });
+            for i in 0..256u32 {
+                cache.store(i * 256, 0.5, 0.9).await.unwrap();
+            }
+            for i in 0..256u32 {
+                assert_eq!(cache.retrieve(i * 256).await.unwrap(), Some(0.5));
+            }
+            assert_eq!(cache.get_stats().total_items, 256);
+            cache.get_stats().memory_usage_bytes
+        };
+
+        let compressed = footprint(true).await;
+        let uncompressed = footprint(false).await;
+        assert!(compressed < uncompressed, "{} >= {}", compressed, uncompressed);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_block_buffers_writes() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 4,
+            salience_threshold: 0.0,
+            ..Default::default()
+        });
+        for key in [0, 4, 8, 12] {
+            cache.store(key, key as f32, 0.9).await.unwrap();
+        }
+        let payload = cache.blocks.get(&0).unwrap().compressed.clone().expect("full block is compressed");
+
+        // Writes to a compressed block are buffered rather than recompressed
+        for key in [16, 20] {
+            cache.store(key, key as f32, 0.9).await.unwrap();
+        }
+        cache.store(4, -1.0, 0.9).await.unwrap();
+        {
+            let block = cache.blocks.get(&0).unwrap();
+            assert_eq!(block.compressed.as_ref(), Some(&payload));
+            assert_eq!(block.pending.len(), 3);
+        }
+        assert_eq!(cache.retrieve(20).await.unwrap(), Some(20.0));
+        assert_eq!(cache.retrieve(4).await.unwrap(), Some(-1.0));
+        assert_eq!(cache.retrieve(8).await.unwrap(), Some(8.0));
+
+        // A full buffer is merged in one recompression
+        cache.store(24, 24.0, 0.9).await.unwrap();
+        let block = cache.blocks.get(&0).unwrap();
+        assert!(block.pending.is_empty());
+        assert_eq!(block.item_count(), 7);
+        assert_eq!(block.lookup(4).unwrap(), Some(-1.0));
+        assert_eq!(block.lookup(24).unwrap(), Some(24.0));
+        assert_eq!(block.lookup(5).unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_store_and_retrieve() {
+        let cache = Arc::new(UnifiedKVCache::new(KVCacheConfig {
+            block_size: 64,
+            max_cache_items: 48,
+            eviction_policy: EvictionPolicy::LRU,
+            ..Default::default()
+        }));
+
+        let tasks: Vec<_> = (0..32u32)
+            .map(|task| {
+                let cache = Arc::clone(&cache);
+                tokio::spawn(async move {
+                    for i in 0..500u32 {
+                        let key = task * 1000 + i;
+                        cache.store(key, i as f32, 0.9).await.unwrap();
+                        cache.retrieve(key).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        let all = async {
+            for task in tasks {
+                task.await.unwrap();
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(30), all)
+            .await
+            .expect("concurrent store/retrieve deadlocked");
+
+        assert!(cache.get_stats().total_blocks <= 64);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            eviction_policy: EvictionPolicy::LRU,
+            ..Default::default()
+        });
+        for key in 0..20 {
+            cache.store(key, key as f32 * 0.5, 0.9).await.unwrap();
+        }
+        cache.retrieve(3).await.unwrap();
+        cache.snapshot_to(&path).unwrap();
+
+        let restored = UnifiedKVCache::restore_from(&path).unwrap();
+        let (before, after) = (cache.get_stats(), restored.get_stats());
+        assert_eq!(before.total_blocks, after.total_blocks);
+        assert_eq!(before.valid_blocks, after.valid_blocks);
+        assert_eq!(before.memory_usage_bytes, after.memory_usage_bytes);
+        assert_eq!(before.hit_rate, after.hit_rate);
+        assert_eq!(restored.access_order.len(), cache.access_order.len());
+
+        for key in 0..25 {
+            assert_eq!(cache.retrieve(key).await.unwrap(), restored.retrieve(key).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ttl_evicts_stale_blocks() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 16,
+            eviction_policy: EvictionPolicy::TTL { max_age_secs: 60 },
+            ..Default::default()
+        });
+        cache.store(1, 1.0, 0.9).await.unwrap();
+        cache.store(2, 2.0, 0.9).await.unwrap();
+
+        // Backdate block 1 past the TTL
+        cache.blocks.get_mut(&1).unwrap().last_accessed -= 120;
+
+        assert_eq!(cache.evict_expired().await, 1);
+        assert_eq!(cache.retrieve(1).await.unwrap(), None);
+        assert_eq!(cache.retrieve(2).await.unwrap(), Some(2.0));
+        assert_eq!(cache.get_stats().eviction_count, 1);
+
+        // The sweep also runs from store without hitting max_cache_items
+        cache.blocks.get_mut(&2).unwrap().last_accessed -= 120;
+        cache.last_expiry_sweep.store(0, Ordering::Relaxed);
+        cache.store(3, 3.0, 0.9).await.unwrap();
+        assert_eq!(cache.retrieve(2).await.unwrap(), None);
+        assert_eq!(cache.retrieve(3).await.unwrap(), Some(3.0));
+    }
+
+    #[tokio::test]
+    async fn test_low_salience_store_is_skipped() {
+        let cache = UnifiedKVCache::new(KVCacheConfig::default());
+        let threshold = cache.config.salience_threshold;
+
+        assert_eq!(cache.store(1, 1.0, threshold - 0.1).await.unwrap(), StoreOutcome::Skipped);
+        assert_eq!(cache.retrieve(1).await.unwrap(), None);
+        assert_eq!(cache.store(1, 1.0, threshold).await.unwrap(), StoreOutcome::Stored);
+        assert_eq!(cache.retrieve(1).await.unwrap(), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_batched_store_and_retrieve() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 8,
+            spot_capacity: 100,
+            ..Default::default()
+        });
+        let items: Vec<(u32, f32, f32)> = (0..64)
+            .map(|key| (key, key as f32 * 0.25, if key % 5 == 0 { 0.1 } else { 0.9 }))
+            .collect();
+
+        let outcomes = cache.store_many(&items).await.unwrap();
+        for (&(key, _, _), outcome) in items.iter().zip(&outcomes) {
+            let expected = if key % 5 == 0 { StoreOutcome::Skipped } else { StoreOutcome::Stored };
+            assert_eq!(*outcome, expected);
+        }
+
+        let keys: Vec<u32> = (0..80).rev().collect();
+        let batched = cache.retrieve_many(&keys).await.unwrap();
+        for (&key, value) in keys.iter().zip(&batched) {
+            assert_eq!(*value, cache.retrieve(key).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lfu_decay_demotes_stale_hot_block() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 16,
+            eviction_policy: EvictionPolicy::LFU,
+            lfu_decay_factor: 0.5,
+            lfu_decay_interval: 10,
+            ..Default::default()
+        });
+
+        // Block A (id 1) is hammered early
+        cache.store(1, 1.0, 0.9).await.unwrap();
+        for _ in 0..99 {
+            cache.retrieve(1).await.unwrap();
+        }
+        cache.store(2, 2.0, 0.9).await.unwrap();
+        assert_eq!(cache.select_lfu_blocks().await, vec![2]);
+
+        // Block B (id 2) stays warm while A goes cold
+        for _ in 0..100 {
+            cache.retrieve(2).await.unwrap();
+        }
+        assert_eq!(cache.select_lfu_blocks().await, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_usage_tracks_occupancy() {
+        let cache = UnifiedKVCache::new(KVCacheConfig::default());
+        assert_eq!(cache.get_stats().memory_usage_bytes, 0);
+
+        for key in 0..10 {
+            cache.store(key, key as f32, 0.9).await.unwrap();
+        }
+
+        // One entry and salience score per key, with too few keys yet for a
+        // graph batch, nowhere near block_size scaling
+        let bytes = cache.get_stats().memory_usage_bytes;
+        assert_eq!(bytes, 10 * 16);
+        assert!(bytes < cache.config.block_size * std::mem::size_of::<f32>());
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_weights_drift_toward_recency() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 1 << 20,
+            max_cache_items: 8,
+            salience_threshold: 0.0,
+            eviction_policy: EvictionPolicy::Adaptive,
+            ..Default::default()
+        });
+        let (_, initial_recency) = cache.adaptive_weights();
+
+        // Fresh low-salience keys are re-read right after being written, while
+        // high-salience keys are written once and never touched again
+        for key in 0..400u32 {
+            cache.store(key, 1.0, 0.2).await.unwrap();
+            if key % 4 == 0 {
+                cache.store(500_000 + key, 1.0, 0.95).await.unwrap();
+            }
+            for back in 1..6 {
+                if key >= back {
+                    cache.retrieve(key - back).await.unwrap();
+                }
+            }
+        }
+
+        let (salience, recency) = cache.adaptive_weights();
+        assert!(recency > initial_recency + 0.2, "recency weight only reached {}", recency);
+        assert!((salience + recency - 1.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_zero_block_size_is_clamped() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 0,
+            salience_threshold: 0.0,
+            ..Default::default()
+        });
+        assert_eq!(cache.config.block_size, 1);
+
+        assert_eq!(cache.store(7, 1.5, 0.9).await.unwrap(), StoreOutcome::Stored);
+        assert_eq!(cache.retrieve(7).await.unwrap(), Some(1.5));
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_drops_old_positions() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            salience_threshold: 0.0,
+            window: Some(WindowConfig { window_size: 100 }),
+            ..Default::default()
+        });
+
+        // The same token id at two positions is two entries
+        cache.store_at(1, 42, 1.0, 0.9).await.unwrap();
+        cache.store_at(50, 42, 2.0, 0.9).await.unwrap();
+        assert_eq!(cache.retrieve_at(1, 42).await.unwrap(), Some(1.0));
+        assert_eq!(cache.retrieve_at(50, 42).await.unwrap(), Some(2.0));
+
+        cache.store_at(500, 42, 3.0, 0.9).await.unwrap();
+        assert_eq!(cache.retrieve_at(1, 42).await.unwrap(), None);
+        assert_eq!(cache.retrieve_at(50, 42).await.unwrap(), None);
+        assert_eq!(cache.retrieve_at(500, 42).await.unwrap(), Some(3.0));
+        assert_eq!(cache.get_stats().eviction_count, 2);
+
+        // Positions already behind the window aren't written
+        assert_eq!(cache.store_at(300, 42, 4.0, 0.9).await.unwrap(), StoreOutcome::Skipped);
+        assert_eq!(cache.store_at(401, 42, 4.0, 0.9).await.unwrap(), StoreOutcome::Stored);
+    }
+
+    #[tokio::test]
+    async fn test_positions_count_toward_stats_and_capacity() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            salience_threshold: 0.0,
+            max_cache_items: 8,
+            ..Default::default()
+        });
+
+        cache.store_at(0, 1, 1.0, 0.9).await.unwrap();
+        cache.store_at(0, 2, 2.0, 0.9).await.unwrap();
+        let stats = cache.get_stats();
+        assert_eq!(stats.total_items, 2);
+        assert!(stats.memory_usage_bytes > 0);
+
+        // Past max_cache_items the oldest positions go first
+        for position in 1..20 {
+            cache.store_at(position, 1, position as f32, 0.9).await.unwrap();
+        }
+        assert!(cache.positions.len() <= 8, "{} positions held", cache.positions.len());
+        assert_eq!(cache.retrieve_at(0, 2).await.unwrap(), None);
+        assert_eq!(cache.retrieve_at(19, 1).await.unwrap(), Some(19.0));
+        assert!(cache.get_stats().eviction_count >= 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("positions.json");
+        cache.snapshot_to(&path).unwrap();
+        let restored = UnifiedKVCache::restore_from(&path).unwrap();
+        assert_eq!(restored.get_stats().total_items, cache.get_stats().total_items);
+        assert_eq!(restored.retrieve_at(19, 1).await.unwrap(), Some(19.0));
+    }
+
+    #[test]
+    fn test_nearest_walks_navigation_graph() {
+        // Ten values on a line graph, inserted out of order so node 0 is mid-range
+        let values: [f32; 10] = [4.0, 0.0, 9.0, 1.0, 7.0, 2.0, 8.0, 3.0, 6.0, 5.0];
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let mut block = DataBlock::new(0, 16);
+        for (node, &value) in values.iter().enumerate() {
+            let rank = order.iter().position(|&n| n == node).unwrap();
+            let neighbours = [rank.checked_sub(1), order.get(rank + 1).map(|_| rank + 1)]
+                .into_iter()
+                .flatten()
+                .map(|r| order[r])
+                .collect();
+            block.write(100 + node as u32, value, node, 0.0, 100 + node as u32, (node, neighbours));
+        }
+
+        let brute_force = |query: f32, k: usize| {
+            let mut ids: Vec<u32> = (0..values.len() as u32).map(|n| 100 + n).collect();
+            ids.sort_by(|a, b| {
+                let da = (values[(a - 100) as usize] - query).abs();
+                let db = (values[(b - 100) as usize] - query).abs();
+                da.total_cmp(&db).then(a.cmp(b))
+            });
+            ids.truncate(k);
+            ids
+        };
+        for (query, k) in [(6.2, 3), (0.4, 2), (9.9, 4), (4.5, 1)] {
+            assert_eq!(block.nearest(query, k), brute_force(query, k), "query {} k {}", query, k);
+        }
+        assert_eq!(block.nearest(6.2, 3), vec![108, 104, 109]);
+        assert!(block.nearest(1.0, 0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_approx_spans_blocks() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 4,
+            salience_threshold: 0.0,
+            ..Default::default()
+        });
+        for key in 0..16 {
+            cache.store(key, key as f32 * 0.5, 0.9).await.unwrap();
+        }
+
+        assert_eq!(cache.retrieve_approx(3.1, 3).await, vec![(6, 3.0), (7, 3.5), (5, 2.5)]);
+    }
+
+    #[test]
+    fn test_overwritten_key_is_relinked() {
+        let mut block = DataBlock::new(0, 1024);
+        for key in 0..2 * GRAPH_LINK_BATCH as u32 {
+            block.put(key, key as f32);
+        }
+        assert!(block.graph_stale.is_empty());
+
+        // Found at its new value straight away, before the graph catches up
+        block.put(3, 40.5);
+        assert_eq!(block.nearest(40.4, 2), vec![3, 40]);
+        assert_eq!(block.nearest(3.1, 1), vec![4]);
+
+        // The next batch moves the node's edges to its new neighbourhood
+        for key in 1000..1000 + GRAPH_LINK_BATCH as u32 - 1 {
+            block.put(key, key as f32);
+        }
+        assert!(block.graph_stale.is_empty());
+        let node = block.graph_nodes[&3];
+        let neighbours = &block.navigation_graph[&node];
+        assert!(!neighbours.is_empty());
+        assert!(neighbours.iter().all(|&n| (block.node_value(n).unwrap() - 40.5).abs() < 4.0));
+        for old in [2, 4] {
+            assert!(!block.navigation_graph[&block.graph_nodes[&old]].contains(&node));
+        }
+        assert_eq!(block.nearest(40.4, 2), vec![3, 40]);
+        assert_eq!(block.nearest(3.1, 1), vec![4]);
+    }
+
+    #[tokio::test]
+    async fn test_store_builds_navigation_graph() {
+        let cache = UnifiedKVCache::new(KVCacheConfig {
+            block_size: 1,
+            salience_threshold: 0.0,
+            compression_enabled: false,
+            ..Default::default()
+        });
+        // Values 0..512 written in a scrambled order, a whole number of link batches
+        let values: Vec<f32> = (0..512u32).map(|key| ((key * 211) % 512) as f32).collect();
+        for (key, &value) in values.iter().enumerate() {
+            cache.store(key as u32, value, 0.9).await.unwrap();
+        }
+
+        {
+            let block = cache.blocks.get(&0).unwrap();
+            assert_eq!(block.vector_ids.len(), values.len());
+            assert_eq!(block.navigation_graph.len(), values.len());
+            assert!(block.graph_stale.is_empty());
+            assert!(block.navigation_graph.values().all(|edges| edges.len() <= MAX_GRAPH_DEGREE));
+            // With a graph in place, nearest goes through the walk
+            for query in [0.3, 77.7, 250.5, 499.9] {
+                let nearest = block.nearest(query, 5);
+                let mut expected: Vec<u32> = (0..values.len() as u32).collect();
+                expected.sort_by(|&a, &b| {
+                    let (da, db) = ((values[a as usize] - query).abs(), (values[b as usize] - query).abs());
+                    da.total_cmp(&db).then(a.cmp(&b))
+                });
+                assert_eq!(nearest, expected[..5], "query {}", query);
+            }
+        }
+
+        let key_of = |value: f32| values.iter().position(|&v| v == value).unwrap() as u32;
+        assert_eq!(
+            cache.retrieve_approx(321.2, 3).await,
+            vec![(key_of(321.0), 321.0), (key_of(322.0), 322.0), (key_of(320.0), 320.0)],
+        );
+
+        // Deleted keys stay walkable, and a rewrite reuses their node
+        cache.delete(key_of(321.0)).await.unwrap();
+        assert_eq!(cache.retrieve_approx(321.2, 1).await, vec![(key_of(322.0), 322.0)]);
+        cache.store(key_of(321.0), 321.0, 0.9).await.unwrap();
+        assert_eq!(cache.blocks.get(&0).unwrap().vector_ids.len(), values.len());
+        assert_eq!(cache.retrieve_approx(321.2, 1).await, vec![(key_of(321.0), 321.0)]);
     }
 }
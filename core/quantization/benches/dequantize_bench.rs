@@ -0,0 +1,27 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zeta_quantization::{PrecisionLevel, QuantizationAlgorithm, QuantizationConfig, UnifiedQuantizer};
+
+fn int8_dequantize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("int8_dequantize");
+    let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+        precision: PrecisionLevel::Int8,
+        algorithm: QuantizationAlgorithm::Linear,
+        ..Default::default()
+    });
+
+    // One size that stays in cache and one that streams from memory
+    for len in [1usize << 14, 1 << 20] {
+        let data: Vec<f32> = (0..len).map(|i| (i as f32 * 0.001).sin()).collect();
+        let result = quantizer.quantize(&data).unwrap();
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &result, |b, result| {
+            b.iter(|| quantizer.dequantize(black_box(&result.quantized_data), &result.parameters))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, int8_dequantize);
+criterion_main!(benches);
@@ -23,7 +23,10 @@
 //! - shared/src/quantization.rs
 
 use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::str::FromStr;
 use serde::{Serialize, Deserialize};
+use half::f16;
 use anyhow::Result;
 use thiserror::Error;
 
@@ -41,6 +44,8 @@ pub enum QuantizationError {
     ValidationError(String),
     #[error("Configuration error: {0}")]
     ConfigError(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -49,17 +54,45 @@ pub enum PrecisionLevel {
     Int1,
     Int2,
     Int4,
+    /// 4-bit NormalFloat: codes index `NF4_CODEBOOK`, scaled by each block's absolute maximum
+    NF4,
     Int8,
     FP16,
     FP32,
 }
 
+impl FromStr for PrecisionLevel {
+    type Err = QuantizationError;
+
+    /// Parse a precision name such as `int4` or `FP16`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "int1" => Ok(PrecisionLevel::Int1),
+            "int2" => Ok(PrecisionLevel::Int2),
+            "int4" => Ok(PrecisionLevel::Int4),
+            "nf4" => Ok(PrecisionLevel::NF4),
+            "int8" => Ok(PrecisionLevel::Int8),
+            "fp16" => Ok(PrecisionLevel::FP16),
+            "fp32" => Ok(PrecisionLevel::FP32),
+            _ => Err(QuantizationError::InvalidPrecision(format!(
+                "'{}' (expected one of int1, int2, int4, nf4, int8, fp16, fp32)",
+                s
+            ))),
+        }
+    }
+}
+
+/// A `PrecisionLevel` that another subsystem's precision enum can't represent
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("No equivalent for {0:?} precision")]
+pub struct UnsupportedPrecision(pub PrecisionLevel);
+
 impl PrecisionLevel {
     pub fn bits(&self) -> u8 {
         match self {
             PrecisionLevel::Int1 => 1,
             PrecisionLevel::Int2 => 2,
-            PrecisionLevel::Int4 => 4,
+            PrecisionLevel::Int4 | PrecisionLevel::NF4 => 4,
             PrecisionLevel::Int8 => 8,
             PrecisionLevel::FP16 => 16,
             PrecisionLevel::FP32 => 32,
@@ -70,12 +103,19 @@ pub fn max_value(&self) -> f32 {
         match self {
             PrecisionLevel::Int1 => 1.0,
             PrecisionLevel::Int2 => 3.0,
-            PrecisionLevel::Int4 => 15.0,
+            PrecisionLevel::Int4 | PrecisionLevel::NF4 => 15.0,
             PrecisionLevel::Int8 => 255.0,
-            PrecisionLevel::FP16 => f32::MAX,
+            // A 16-bit grid, matching the code range `signed_range` uses for FP16
+            PrecisionLevel::FP16 => 65535.0,
             PrecisionLevel::FP32 => f32::MAX,
         }
     }
+
+    /// Signed two's-complement code range used by symmetric quantization
+    pub fn signed_range(&self) -> (f32, f32) {
+        let half = (1u64 << (self.bits() - 1)) as f32;
+        (-half, half - 1.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +126,13 @@ pub enum QuantizationAlgorithm {
     BlockWise,
     SalienceBased,
     Adaptive,
+    /// Error-compensated column-wise quantization; `block_size` is the column count
+    GPTQ,
+    /// Salient values at `high_precision`, the rest at `precision`
+    MixedPrecision,
+    /// Per-token quantization for activations: each row of `block_size` values
+    /// gets parameters from its own range, kept in `block_parameters`
+    Dynamic,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +145,27 @@ pub struct QuantizationConfig {
     pub use_symmetric: bool,
     pub calibration_samples: usize,
     pub validation_threshold: f32,
+    /// Seed for the stochastic parts of calibration (e.g. learned quantization)
+    #[serde(default)]
+    pub seed: u64,
+    /// Upper bound on k-means iterations; clustering stops earlier once centroids settle
+    #[serde(default = "default_kmeans_max_iterations")]
+    pub kmeans_max_iterations: usize,
+    /// Precision for values above `salience_threshold` in mixed-precision mode
+    #[serde(default = "default_high_precision")]
+    pub high_precision: PrecisionLevel,
+    /// Clip linear quantization's range to percentiles of `calibration_samples`
+    /// sampled values instead of the literal extremes
+    #[serde(default)]
+    pub percentile_clipping: bool,
+}
+
+fn default_kmeans_max_iterations() -> usize {
+    100
+}
+
+fn default_high_precision() -> PrecisionLevel {
+    PrecisionLevel::Int8
 }
 
 impl Default for QuantizationConfig {
@@ -111,23 +179,87 @@ fn default() -> Self {
             use_symmetric: false,
             calibration_samples: 1000,
             validation_threshold: 0.95,
+            seed: 0,
+            kmeans_max_iterations: default_kmeans_max_iterations(),
+            high_precision: default_high_precision(),
+            percentile_clipping: false,
         }
     }
 }
 
+/// NF4 code values: quantiles of a standard normal normalized to `[-1, 1]`, with an exact zero
+pub const NF4_CODEBOOK: [f32; 16] = [
+    -1.0,
+    -0.696_192_8,
+    -0.525_073_05,
+    -0.394_917_5,
+    -0.284_441_38,
+    -0.184_773_43,
+    -0.091_050_036,
+    0.0,
+    0.079_580_3,
+    0.160_930_2,
+    0.246_112_3,
+    0.337_915_24,
+    0.440_709_83,
+    0.562_617,
+    0.722_956_84,
+    1.0,
+];
+
+/// Precisions tried by `auto_precision`, narrowest first
+const AUTO_PRECISION_CANDIDATES: [PrecisionLevel; 4] = [
+    PrecisionLevel::Int2,
+    PrecisionLevel::Int4,
+    PrecisionLevel::Int8,
+    PrecisionLevel::FP16,
+];
+
+/// Percentiles bounding the calibrated range when `percentile_clipping` is set
+const CLIP_LOWER_PERCENTILE: f32 = 0.1;
+const CLIP_UPPER_PERCENTILE: f32 = 99.9;
+
+/// Values per GGML quantization block (`QK4_1` / `QK8_0`)
+pub const GGUF_BLOCK_VALUES: usize = 32;
+
+/// Bits stored per parameter set (an f32 scale and an i32 zero point)
+const PARAMETER_SET_BITS: usize = 64;
+
+/// Bits stored per preserved outlier (a u32 index and an f32 value)
+const OUTLIER_BITS: usize = 64;
+
+/// Ranges narrower than this are treated as constant tensors
+const RANGE_EPSILON: f32 = 1e-8;
+
+/// Values held in memory at a time by non-blockwise streaming quantization
+const STREAM_CHUNK_LEN: usize = 64 * 1024;
+
+/// Maximum centroid movement at which k-means is considered converged
+const KMEANS_TOLERANCE: f32 = 1e-6;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantizationParameters {
     pub scale: f32,
     pub zero_point: i32,
     pub min_val: f32,
     pub max_val: f32,
+    /// Range values were clipped to before calibration, when percentile clipping is on
+    #[serde(default)]
+    pub clip_range: Option<(f32, f32)>,
 }
 
 impl QuantizationParameters {
     pub fn new(min_val: f32, max_val: f32, precision: &PrecisionLevel) -> Self {
         let qmin = 0.0;
         let qmax = precision.max_value();
-        let scale = (max_val - min_val) / (qmax - qmin);
+        let scale = if max_val - min_val > RANGE_EPSILON {
+            (max_val - min_val) / (qmax - qmin)
+        } else if min_val.abs() > RANGE_EPSILON {
+            // Constant tensor: one step per constant so it lands exactly on a code
+            min_val.abs()
+        } else {
+            1.0
+        };
         let zero_point = (qmin - min_val / scale).round() as i32;
 
         Self {
@@ -135,8 +267,41 @@ pub fn new(min_val: f32, max_val: f32, precision: &PrecisionLevel) -> Self {
             zero_point,
             min_val,
             max_val,
+            clip_range: None,
         }
     }
+
+    /// Symmetric mapping: the range is `[-max(|min|, |max|), max(|min|, |max|)]`,
+    /// the zero point is fixed at 0 and codes are signed
+    pub fn symmetric(min_val: f32, max_val: f32, precision: &PrecisionLevel) -> Self {
+        let (_, qmax) = precision.signed_range();
+        let abs_max = min_val.abs().max(max_val.abs());
+        let scale = if abs_max > RANGE_EPSILON {
+            abs_max / qmax.max(1.0)
+        } else {
+            1.0
+        };
+
+        Self {
+            scale,
+            zero_point: 0,
+            min_val,
+            max_val,
+            clip_range: None,
+        }
+    }
+
+    /// Map a real value onto the integer grid `[qmin, qmax]`
+    pub fn quantize_value(&self, value: f32, qmin: f32, qmax: f32) -> i32 {
+        (value / self.scale + self.zero_point as f32)
+            .round()
+            .clamp(qmin, qmax) as i32
+    }
+
+    /// Map an integer code back to a real value
+    pub fn dequantize_value(&self, quantized: i32) -> f32 {
+        (quantized - self.zero_point) as f32 * self.scale
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +311,193 @@ pub struct QuantizationResult {
     pub compression_ratio: f32,
     pub error_metrics: ErrorMetrics,
     pub salience_preserved: f32,
+    /// Per-block parameters, populated by blockwise quantization
+    #[serde(default)]
+    pub block_parameters: Option<Vec<QuantizationParameters>>,
+    /// Number of values covered by each entry of `block_parameters`
+    #[serde(default)]
+    pub block_size: Option<usize>,
+    /// Outlier values kept at full precision, as `(index, value)` pairs
+    #[serde(default)]
+    pub outliers: Vec<(usize, f32)>,
+    /// Precision each index was quantized at, populated by mixed-precision quantization
+    #[serde(default)]
+    pub precision_map: Vec<PrecisionLevel>,
+    /// Parameters for each precision level appearing in `precision_map`
+    #[serde(default)]
+    pub precision_parameters: Vec<(PrecisionLevel, QuantizationParameters)>,
+    /// Code values for codebook results: NF4 levels scaled by each block's `scale`,
+    /// or k-means centroids used as-is
+    #[serde(default)]
+    pub codebook: Vec<f32>,
+    /// Stored bits per value including amortized parameters, codebook entries and
+    /// outliers; unlike `compression_ratio` this reflects per-block overhead
+    #[serde(default)]
+    pub effective_bits_per_value: f32,
+}
+
+impl QuantizationResult {
+    /// Reconstruct the original values from this result alone, using the
+    /// per-block parameters when present and splicing preserved outliers back in
+    ///
+    /// ```
+    /// use zeta_quantization::{quantize_tensor, PrecisionLevel};
+    ///
+    /// let data = vec![0.0, 0.25, 0.5, 0.75, 1.0];
+    /// let result = quantize_tensor(&data, PrecisionLevel::Int8).unwrap();
+    /// let restored = result.dequantized();
+    ///
+    /// assert_eq!(restored.len(), data.len());
+    /// assert!(result.roundtrip_mse(&data) < 1e-4);
+    /// ```
+    pub fn dequantized(&self) -> Vec<f32> {
+        let mut values = self.dequantize_codes();
+        for &(index, value) in &self.outliers {
+            if let Some(slot) = values.get_mut(index) {
+                *slot = value;
+            }
+        }
+        values
+    }
+
+    /// Mean squared error between `original` and the dequantized values
+    pub fn roundtrip_mse(&self, original: &[f32]) -> f32 {
+        if original.is_empty() {
+            return 0.0;
+        }
+
+        let restored = self.dequantized();
+        original.iter()
+            .zip(restored.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>() / original.len() as f32
+    }
+
+    /// Lay the codes out as GGML `Q4_1` or `Q8_0` blocks, the tensor data format of GGUF files
+    ///
+    /// When every code lies within 16 consecutive values (4-bit precisions), each run of
+    /// `GGUF_BLOCK_VALUES` values becomes a 20-byte `Q4_1` block:
+    ///
+    /// | bytes   | contents                                                        |
+    /// |---------|-----------------------------------------------------------------|
+    /// | `0..2`  | scale `d`, little-endian f16                                    |
+    /// | `2..4`  | offset `m`, little-endian f16                                   |
+    /// | `4..20` | nibbles: byte `j` holds value `j` low and value `j + 16` high   |
+    ///
+    /// decoding as `x = n * d + m`. Otherwise each run becomes a 34-byte `Q8_0` block:
+    ///
+    /// | bytes   | contents                                |
+    /// |---------|-----------------------------------------|
+    /// | `0..2`  | scale `d`, little-endian f16            |
+    /// | `2..34` | 32 signed 8-bit codes `q`               |
+    ///
+    /// decoding as `x = q * d`. Blocks are concatenated with no header and the last one is
+    /// zero-padded. A block's codes are copied as-is (offset by its lowest code for `Q4_1`,
+    /// by the zero point for `Q8_0`) when its values share one set of linear parameters and
+    /// fit the block format; otherwise, as for codebook or mixed precision results, the block is
+    /// requantized from the dequantized values. Preserved outliers are not represented.
+    pub fn to_gguf_block(&self) -> Vec<u8> {
+        let min_code = self.quantized_data.iter().copied().min().unwrap_or(0);
+        let max_code = self.quantized_data.iter().copied().max().unwrap_or(0);
+        let nibbles = max_code as i64 - min_code as i64 <= 15;
+
+        let restored = self.dequantize_codes();
+        let mut blob = Vec::new();
+        for start in (0..self.quantized_data.len()).step_by(GGUF_BLOCK_VALUES) {
+            let end = (start + GGUF_BLOCK_VALUES).min(self.quantized_data.len());
+            let codes = &self.quantized_data[start..end];
+            let shared = self.linear_parameters_at(start)
+                .filter(|first| (start..end).all(|i| {
+                    self.linear_parameters_at(i)
+                        .is_some_and(|p| p.scale == first.scale && p.zero_point == first.zero_point)
+                }));
+
+            let mut values = [0i32; GGUF_BLOCK_VALUES];
+            let (scale, offset) = if nibbles {
+                let block_min = codes.iter().copied().min().unwrap_or(0);
+                match shared {
+                    Some(params) => {
+                        for (slot, &q) in values.iter_mut().zip(codes) {
+                            *slot = q - block_min;
+                        }
+                        (params.scale, (block_min - params.zero_point) as f32 * params.scale)
+                    }
+                    None => requantize_q4_1(&restored[start..end], &mut values),
+                }
+            } else {
+                let fits_i8 = |params: &QuantizationParameters| {
+                    codes.iter().all(|&q| (-128..=127).contains(&(q as i64 - params.zero_point as i64)))
+                };
+                match shared.filter(|params| fits_i8(params)) {
+                    Some(params) => {
+                        for (slot, &q) in values.iter_mut().zip(codes) {
+                            *slot = q - params.zero_point;
+                        }
+                        (params.scale, 0.0)
+                    }
+                    None => requantize_q8_0(&restored[start..end], &mut values),
+                }
+            };
+
+            blob.extend_from_slice(&f16::from_f32(scale).to_le_bytes());
+            if nibbles {
+                blob.extend_from_slice(&f16::from_f32(offset).to_le_bytes());
+                let half_block = GGUF_BLOCK_VALUES / 2;
+                for j in 0..half_block {
+                    blob.push((values[j] as u8 & 0x0F) | ((values[j + half_block] as u8 & 0x0F) << 4));
+                }
+            } else {
+                blob.extend(values.iter().map(|&q| q as i8 as u8));
+            }
+        }
+        blob
+    }
+
+    /// Linear parameters for the code at `index`, or `None` when codes aren't linear in them
+    fn linear_parameters_at(&self, index: usize) -> Option<&QuantizationParameters> {
+        if !self.codebook.is_empty() || !self.precision_map.is_empty() {
+            return None;
+        }
+        match (&self.block_parameters, self.block_size) {
+            (Some(block_params), Some(block_size)) if block_size > 0 => block_params.get(index / block_size),
+            _ => Some(&self.parameters),
+        }
+    }
+
+    fn dequantize_codes(&self) -> Vec<f32> {
+        if !self.precision_map.is_empty() {
+            return self.quantized_data.iter()
+                .zip(&self.precision_map)
+                .map(|(&q, precision)| {
+                    self.precision_parameters.iter()
+                        .find(|(level, _)| level == precision)
+                        .map(|(_, params)| params)
+                        .unwrap_or(&self.parameters)
+                        .dequantize_value(q)
+                })
+                .collect();
+        }
+
+        match (&self.block_parameters, self.block_size) {
+            (Some(block_params), Some(block_size)) if block_size > 0 && !self.codebook.is_empty() => {
+                self.quantized_data.chunks(block_size)
+                    .zip(block_params.iter())
+                    .flat_map(|(chunk, params)| {
+                        chunk.iter().map(move |&q| {
+                            self.codebook.get(q as usize).copied().unwrap_or(0.0) * params.scale
+                        })
+                    })
+                    .collect()
+            }
+            (Some(block_params), Some(block_size)) if block_size > 0 => {
+                dequantize_blocks(&self.quantized_data, block_params, block_size)
+            }
+            _ if !self.codebook.is_empty() => {
+                self.quantized_data.iter().map(|&q| self.codebook.get(q as usize).copied().unwrap_or(0.0)).collect()
+            }
+            _ => self.quantized_data.iter().map(|&q| self.parameters.dequantize_value(q)).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,9 +506,14 @@ pub struct ErrorMetrics {
     pub mae: f32,
     pub max_error: f32,
     pub snr: f32,
+    /// Cosine similarity between the original and dequantized vectors; unlike
+    /// MSE it ignores overall scale, so it tracks downstream accuracy more closely
+    #[serde(default)]
+    pub cosine_similarity: f32,
 }
 
 /// Unified Quantization Engine
+#[derive(Clone)]
 pub struct UnifiedQuantizer {
     config: QuantizationConfig,
     salience_weights: HashMap<usize, f32>,
@@ -174,29 +531,271 @@ pub fn set_salience_weights(&mut self, weights: HashMap<usize, f32>) {
         self.salience_weights = weights;
     }
 
+    /// Derive salience weights from calibration activations, one per index
+    ///
+    /// Each index's weight is its activation magnitude divided by the largest
+    /// magnitude, so the dominant index gets 1.0. Non-finite activations count as
+    /// zero. If every activation is zero the weights are cleared, leaving every
+    /// index at the default weight of 1.0.
+    pub fn set_salience_weights_from_activations(&mut self, activations: &[f32]) {
+        let magnitude = |value: f32| if value.is_finite() { value.abs() } else { 0.0 };
+        let max_magnitude = activations.iter().map(|&a| magnitude(a)).fold(0.0f32, f32::max);
+        if max_magnitude <= 0.0 {
+            self.salience_weights.clear();
+            return;
+        }
+
+        self.salience_weights = activations.iter()
+            .enumerate()
+            .map(|(i, &a)| (i, magnitude(a) / max_magnitude))
+            .collect();
+    }
+
     pub fn quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
-        match self.config.algorithm {
-            QuantizationAlgorithm::Linear => self.linear_quantize(data),
-            QuantizationAlgorithm::KMeans => self.kmeans_quantize(data),
-            QuantizationAlgorithm::Learned => self.learned_quantize(data),
-            QuantizationAlgorithm::BlockWise => self.blockwise_quantize(data),
-            QuantizationAlgorithm::SalienceBased => self.salience_quantize(data),
-            QuantizationAlgorithm::Adaptive => self.adaptive_quantize(data),
+        // Codebook precisions have a single quantization scheme regardless of algorithm
+        let mut result = if self.config.precision == PrecisionLevel::NF4 {
+            self.nf4_quantize(data)?
+        } else {
+            match self.config.algorithm {
+                QuantizationAlgorithm::Linear => self.linear_quantize(data)?,
+                QuantizationAlgorithm::KMeans => self.kmeans_quantize(data)?,
+                QuantizationAlgorithm::Learned => self.learned_quantize(data)?,
+                QuantizationAlgorithm::BlockWise => self.blockwise_quantize(data)?,
+                QuantizationAlgorithm::SalienceBased => self.salience_quantize(data)?,
+                QuantizationAlgorithm::Adaptive => self.adaptive_quantize(data)?,
+                QuantizationAlgorithm::GPTQ => self.gptq_quantize(data)?,
+                QuantizationAlgorithm::MixedPrecision => self.mixed_precision_quantize(data)?,
+                QuantizationAlgorithm::Dynamic => self.dynamic_quantize(data)?,
+            }
+        };
+
+        result.effective_bits_per_value = self.effective_bits_per_value(&result);
+        Ok(result)
+    }
+
+    /// Code bits plus the amortized storage for parameters, codebook entries and outliers
+    fn effective_bits_per_value(&self, result: &QuantizationResult) -> f32 {
+        let len = result.quantized_data.len();
+        if len == 0 {
+            return 0.0;
+        }
+
+        let code_bits: usize = if result.precision_map.is_empty() {
+            len * self.config.precision.bits() as usize
+        } else {
+            result.precision_map.iter().map(|p| p.bits() as usize).sum()
+        };
+        let parameter_sets = if !result.precision_parameters.is_empty() {
+            result.precision_parameters.len()
+        } else {
+            result.block_parameters.as_ref().map_or(1, Vec::len)
+        };
+        let codebook_entries = result.codebook.len();
+
+        let total_bits = code_bits
+            + parameter_sets * PARAMETER_SET_BITS
+            + codebook_entries * 32
+            + result.outliers.len() * OUTLIER_BITS;
+        total_bits as f32 / len as f32
+    }
+
+    /// Quantize at the narrowest of Int2, Int4, Int8 and FP16 whose reconstruction
+    /// MSE is below `max_mse`, failing with a `ValidationError` when none meets it
+    pub fn auto_precision(&self, data: &[f32], max_mse: f32) -> Result<(PrecisionLevel, QuantizationResult), QuantizationError> {
+        let mut best_mse = f32::INFINITY;
+        for precision in AUTO_PRECISION_CANDIDATES.iter() {
+            let quantizer = UnifiedQuantizer {
+                config: QuantizationConfig { precision: precision.clone(), ..self.config.clone() },
+                salience_weights: self.salience_weights.clone(),
+            };
+            let result = quantizer.quantize(data)?;
+            let mse = result.roundtrip_mse(data);
+            if mse < max_mse {
+                return Ok((precision.clone(), result));
+            }
+            best_mse = best_mse.min(mse);
+        }
+
+        Err(QuantizationError::ValidationError(format!(
+            "No precision meets the MSE budget of {}; the best candidate reached {}",
+            max_mse, best_mse
+        )))
+    }
+
+    /// Integer code range for the configured precision and symmetry
+    fn code_range(&self) -> (f32, f32) {
+        self.code_range_for(&self.config.precision)
+    }
+
+    fn code_range_for(&self, precision: &PrecisionLevel) -> (f32, f32) {
+        if self.config.use_symmetric {
+            precision.signed_range()
+        } else {
+            (0.0, precision.max_value())
+        }
+    }
+
+    fn range_parameters(&self, min_val: f32, max_val: f32) -> QuantizationParameters {
+        self.range_parameters_for(min_val, max_val, &self.config.precision)
+    }
+
+    fn range_parameters_for(&self, min_val: f32, max_val: f32, precision: &PrecisionLevel) -> QuantizationParameters {
+        if self.config.use_symmetric {
+            QuantizationParameters::symmetric(min_val, max_val, precision)
+        } else {
+            QuantizationParameters::new(min_val, max_val, precision)
+        }
+    }
+
+    /// Quantize `len` little-endian `f32` values from `reader` without holding
+    /// the tensor in memory, writing the codes to `writer` as they are produced
+    ///
+    /// The reader is scanned twice: once for range statistics (per block for
+    /// `BlockWise`, global for `Linear`) and once to emit codes. Codes are
+    /// written one byte each for precisions up to 8 bits and as little-endian
+    /// `i32` otherwise; the returned result carries parameters and error
+    /// metrics but leaves `quantized_data` empty.
+    pub fn quantize_stream<R: Read + Seek, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        len: usize,
+    ) -> Result<QuantizationResult, QuantizationError> {
+        if self.config.precision == PrecisionLevel::NF4 {
+            return Err(QuantizationError::ConfigError(
+                "Streaming quantization does not support NF4".to_string(),
+            ));
+        }
+        let blockwise = match self.config.algorithm {
+            QuantizationAlgorithm::BlockWise => true,
+            QuantizationAlgorithm::Linear => false,
+            ref other => {
+                return Err(QuantizationError::ConfigError(format!(
+                    "Streaming quantization supports Linear and BlockWise, not {:?}",
+                    other
+                )))
+            }
+        };
+        let chunk_len = if blockwise { self.config.block_size.max(1) } else { STREAM_CHUNK_LEN };
+        let start = reader.stream_position()?;
+        let mut buffer = Vec::with_capacity(chunk_len);
+
+        // First pass: range statistics
+        let mut min_val = f32::INFINITY;
+        let mut max_val = f32::NEG_INFINITY;
+        let mut block_params = Vec::new();
+        let mut remaining = len;
+        while remaining > 0 {
+            let count = remaining.min(chunk_len);
+            read_f32_chunk(&mut reader, count, &mut buffer)?;
+            let chunk_min = buffer.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+            let chunk_max = buffer.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            if blockwise {
+                block_params.push(self.range_parameters(chunk_min, chunk_max));
+            }
+            min_val = min_val.min(chunk_min);
+            max_val = max_val.max(chunk_max);
+            remaining -= count;
         }
+
+        if len == 0 {
+            min_val = 0.0;
+            max_val = 0.0;
+        }
+        let global_params = self.range_parameters(min_val, max_val);
+        let (qmin, qmax) = self.code_range();
+        let narrow_codes = self.config.precision.bits() <= 8;
+
+        // Second pass: emit codes and accumulate error statistics
+        reader.seek(SeekFrom::Start(start))?;
+        let mut squared_error = 0.0f64;
+        let mut absolute_error = 0.0f64;
+        let mut max_error: f32 = 0.0;
+        let mut signal_power = 0.0f64;
+        let mut reconstructed_power = 0.0f64;
+        let mut dot = 0.0f64;
+        let mut remaining = len;
+        let mut block_index = 0;
+        while remaining > 0 {
+            let count = remaining.min(chunk_len);
+            read_f32_chunk(&mut reader, count, &mut buffer)?;
+            let params = if blockwise { &block_params[block_index] } else { &global_params };
+
+            for &value in &buffer {
+                let code = params.quantize_value(value, qmin, qmax);
+                if narrow_codes {
+                    writer.write_all(&[code as u8])?;
+                } else {
+                    writer.write_all(&code.to_le_bytes())?;
+                }
+
+                let restored = params.dequantize_value(code);
+                let error = value - restored;
+                squared_error += (error * error) as f64;
+                absolute_error += error.abs() as f64;
+                max_error = max_error.max(error.abs());
+                signal_power += (value * value) as f64;
+                reconstructed_power += (restored * restored) as f64;
+                dot += (value * restored) as f64;
+            }
+
+            remaining -= count;
+            block_index += 1;
+        }
+        writer.flush()?;
+
+        let n = len.max(1) as f64;
+        let snr = if squared_error > 0.0 {
+            (10.0 * (signal_power / squared_error).log10()) as f32
+        } else {
+            f32::INFINITY
+        };
+        let block_size = blockwise.then_some(chunk_len);
+        let stored_bits = len * self.config.precision.bits() as usize + block_params.len().max(1) * PARAMETER_SET_BITS;
+        let effective_bits_per_value = if len > 0 { stored_bits as f32 / len as f32 } else { 0.0 };
+
+        Ok(QuantizationResult {
+            quantized_data: Vec::new(),
+            parameters: global_params,
+            compression_ratio: 32.0 / self.config.precision.bits() as f32,
+            error_metrics: ErrorMetrics {
+                mse: (squared_error / n) as f32,
+                mae: (absolute_error / n) as f32,
+                max_error,
+                snr,
+                cosine_similarity: cosine_similarity(dot as f32, signal_power as f32, reconstructed_power as f32),
+            },
+            salience_preserved: 1.0, // Streaming doesn't consider salience
+            block_parameters: blockwise.then_some(block_params),
+            block_size,
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value,
+        })
     }
 
     fn linear_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
-        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-        
-        let params = QuantizationParameters::new(min_val, max_val, &self.config.precision);
+        let clip_range = if self.config.percentile_clipping {
+            self.percentile_range(data)
+        } else {
+            None
+        };
+        let (min_val, max_val) = clip_range.unwrap_or_else(|| {
+            (
+                data.iter().fold(f32::INFINITY, |a, &b| a.min(b)),
+                data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b)),
+            )
+        });
+
+        let mut params = self.range_parameters(min_val, max_val);
+        params.clip_range = clip_range;
         let mut quantized_data = Vec::with_capacity(data.len());
         
+        let (qmin, qmax) = self.code_range();
         for &value in data {
-            let quantized = ((value - min_val) / params.scale + params.zero_point as f32)
-                .round()
-                .clamp(0.0, self.config.precision.max_value()) as i32;
-            quantized_data.push(quantized);
+            quantized_data.push(params.quantize_value(value, qmin, qmax));
         }
 
         let error_metrics = self.calculate_error_metrics(data, &quantized_data, &params);
@@ -208,10 +807,45 @@ fn linear_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantizati
             compression_ratio,
             error_metrics,
             salience_preserved: 1.0, // Linear doesn't consider salience
+            block_parameters: None,
+            block_size: None,
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
         })
     }
 
+    /// `CLIP_LOWER_PERCENTILE` and `CLIP_UPPER_PERCENTILE` of up to
+    /// `calibration_samples` values drawn from `data` with the configured seed
+    fn percentile_range(&self, data: &[f32]) -> Option<(f32, f32)> {
+        if data.is_empty() {
+            return None;
+        }
+
+        let mut samples: Vec<f32> = if data.len() <= self.config.calibration_samples {
+            data.to_vec()
+        } else {
+            let mut rng = SplitMix64::new(self.config.seed);
+            (0..self.config.calibration_samples.max(1))
+                .map(|_| data[rng.next_index(data.len())])
+                .collect()
+        };
+        samples.sort_by(|a, b| a.total_cmp(b));
+
+        let at = |percentile: f32| {
+            let rank = (percentile / 100.0 * (samples.len() - 1) as f32).round() as usize;
+            samples[rank.min(samples.len() - 1)]
+        };
+        Some((at(CLIP_LOWER_PERCENTILE), at(CLIP_UPPER_PERCENTILE)))
+    }
+
     fn salience_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        self.preserving_outliers(data, |inliers| self.salience_quantize_inliers(inliers))
+    }
+
+    fn salience_quantize_inliers(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
         // Apply salience-aware quantization
         let mut weighted_data = Vec::with_capacity(data.len());
         let mut salience_preserved = 0.0;
@@ -242,10 +876,7 @@ fn salience_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantiza
         let mut quantized_data = Vec::with_capacity(weighted_data.len());
         
         for &value in &weighted_data {
-            let quantized = ((value - min_val) / params.scale + params.zero_point as f32)
-                .round()
-                .clamp(0.0, self.config.precision.max_value()) as i32;
-            quantized_data.push(quantized);
+            quantized_data.push(params.quantize_value(value, 0.0, self.config.precision.max_value()));
         }
 
         let error_metrics = self.calculate_error_metrics(data, &quantized_data, &params);
@@ -257,34 +888,41 @@ fn salience_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantiza
             compression_ratio,
             error_metrics,
             salience_preserved,
+            block_parameters: None,
+            block_size: None,
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
         })
     }
 
-    fn blockwise_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+    /// Quantize each `chunk_len` run of `data` with parameters fitted to that run
+    fn quantize_chunks(&self, data: &[f32], chunk_len: usize) -> (Vec<i32>, Vec<QuantizationParameters>) {
         let mut quantized_data = Vec::with_capacity(data.len());
         let mut all_params = Vec::new();
-        let mut total_error = 0.0;
+        let (qmin, qmax) = self.code_range();
 
-        for chunk in data.chunks(self.config.block_size) {
+        for chunk in data.chunks(chunk_len) {
             let min_val = chunk.iter().fold(f32::INFINITY, |a, &b| a.min(b));
             let max_val = chunk.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
             
-            let params = QuantizationParameters::new(min_val, max_val, &self.config.precision);
-            all_params.push(params.clone());
-            
+            let params = self.range_parameters(min_val, max_val);
             for &value in chunk {
-                let quantized = ((value - min_val) / params.scale + params.zero_point as f32)
-                    .round()
-                    .clamp(0.0, self.config.precision.max_value()) as i32;
-                quantized_data.push(quantized);
-                
-                // Calculate dequantized value for error
-                let dequantized = (quantized as f32 - params.zero_point as f32) * params.scale + min_val;
-                total_error += (value - dequantized).powi(2);
+                quantized_data.push(params.quantize_value(value, qmin, qmax));
             }
+            all_params.push(params);
         }
 
-        // Use average parameters for the result
+        (quantized_data, all_params)
+    }
+
+    fn blockwise_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        let block_size = self.config.block_size.max(1);
+        let (quantized_data, all_params) = self.quantize_chunks(data, block_size);
+
+        // Average parameters summarise the tensor; per-block parameters are kept for dequantization
         let avg_params = if !all_params.is_empty() {
             let avg_scale = all_params.iter().map(|p| p.scale).sum::<f32>() / all_params.len() as f32;
             let avg_zero_point = all_params.iter().map(|p| p.zero_point).sum::<i32>() / all_params.len() as i32;
@@ -296,12 +934,14 @@ fn blockwise_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantiz
                 zero_point: avg_zero_point,
                 min_val: avg_min,
                 max_val: avg_max,
+                clip_range: None,
             }
         } else {
             QuantizationParameters::new(0.0, 1.0, &self.config.precision)
         };
 
-        let error_metrics = self.calculate_error_metrics(data, &quantized_data, &avg_params);
+        let reconstructed = dequantize_blocks(&quantized_data, &all_params, block_size);
+        let error_metrics = self.calculate_reconstruction_metrics(data, &reconstructed);
         let compression_ratio = (32.0 / self.config.precision.bits() as f32);
 
         Ok(QuantizationResult {
@@ -310,19 +950,77 @@ fn blockwise_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantiz
             compression_ratio,
             error_metrics,
             salience_preserved: 0.8, // Blockwise preserves some structure
+            block_parameters: Some(all_params),
+            block_size: Some(block_size),
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
         })
     }
 
+    /// Quantize activations row by row, one row per token of `block_size` values
+    ///
+    /// Rows are quantized exactly as `blockwise_quantize` quantizes blocks. Unlike
+    /// blockwise weights, which are calibrated once, the ranges are meant to be
+    /// recomputed on every forward pass, so `parameters` covers the whole tensor
+    /// rather than averaging the rows, and rows dequantize only via `block_parameters`.
+    fn dynamic_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        let mut result = self.blockwise_quantize(data)?;
+
+        if !data.is_empty() {
+            let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+            let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            result.parameters = self.range_parameters(min_val, max_val);
+        }
+        result.salience_preserved = 1.0; // Dynamic quantization doesn't consider salience
+        Ok(result)
+    }
+
+    /// Normalize each block by its absolute maximum and snap values to the nearest NF4 code
+    fn nf4_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        let block_size = self.config.block_size.max(1);
+        let mut quantized_data = Vec::with_capacity(data.len());
+        let mut all_params = Vec::new();
+
+        for chunk in data.chunks(block_size) {
+            let min_val = chunk.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+            let max_val = chunk.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            let abs_max = min_val.abs().max(max_val.abs());
+            let scale = if abs_max > RANGE_EPSILON { abs_max } else { 1.0 };
+
+            for &value in chunk {
+                quantized_data.push(nearest_code(value / scale, &NF4_CODEBOOK) as i32);
+            }
+            all_params.push(QuantizationParameters { scale, zero_point: 0, min_val, max_val, clip_range: None });
+        }
+
+        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+        let mut result = QuantizationResult {
+            quantized_data,
+            parameters: QuantizationParameters::symmetric(min_val, max_val, &self.config.precision),
+            compression_ratio: 32.0 / self.config.precision.bits() as f32,
+            error_metrics: ErrorMetrics { mse: 0.0, mae: 0.0, max_error: 0.0, snr: f32::INFINITY, cosine_similarity: 1.0 },
+            salience_preserved: 1.0, // NF4 doesn't consider salience
+            block_parameters: Some(all_params),
+            block_size: Some(block_size),
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: NF4_CODEBOOK.to_vec(),
+            effective_bits_per_value: 0.0,
+        };
+        result.error_metrics = self.calculate_reconstruction_metrics(data, &result.dequantized());
+
+        Ok(result)
+    }
+
     fn kmeans_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
         // Simplified K-means quantization
         let k = (1 << self.config.precision.bits()).min(256) as usize;
-        let mut centroids = self.initialize_centroids(data, k);
-        
-        // Run K-means iterations
-        for _ in 0..10 {
-            let assignments = self.assign_to_centroids(data, &centroids);
-            centroids = self.update_centroids(data, &assignments, k);
-        }
+        let centroids = self.kmeans_centroids(data, k);
 
         // Quantize data using final centroids
         let mut quantized_data = Vec::with_capacity(data.len());
@@ -344,12 +1042,300 @@ fn kmeans_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantizati
             compression_ratio,
             error_metrics,
             salience_preserved: 0.9, // K-means preserves data distribution
+            block_parameters: None,
+            block_size: None,
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: centroids,
+            effective_bits_per_value: 0.0,
         })
     }
 
-    fn learned_quantize(&self, _data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
-        // Placeholder for learned quantization - would require ML model
-        Err(QuantizationError::ConfigError("Learned quantization not yet implemented".to_string()))
+    fn learned_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        if data.is_empty() {
+            return Err(QuantizationError::TensorError("Cannot quantize an empty tensor".to_string()));
+        }
+
+        let qmax = self.config.precision.max_value();
+        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+
+        // Start from the min/max calibration used by linear quantization
+        let initial = QuantizationParameters::new(min_val, max_val, &self.config.precision);
+        let initial_scale = initial.scale;
+        let mut scale = initial.scale;
+        let mut zero_point = initial.zero_point as f32;
+
+        let mut best = initial.clone();
+        let mut best_mse = self.parameters_mse(data, &best);
+
+        // Gradient descent on (scale, zero_point) using the straight-through
+        // estimator for rounding, over seeded mini-batches of the input
+        let steps = self.config.calibration_samples;
+        let batch_size = data.len().min(256);
+        let mut rng = SplitMix64::new(self.config.seed);
+        let base_learning_rate = 0.05;
+
+        for step in 0..steps {
+            let mut grad_scale = 0.0;
+            let mut grad_zero_point = 0.0;
+
+            for _ in 0..batch_size {
+                let value = data[rng.next_index(data.len())];
+                let unclamped = (value / scale + zero_point).round();
+                let quantized = unclamped.clamp(0.0, qmax);
+                let error = (quantized - zero_point) * scale - value;
+
+                let (d_scale, d_zero_point) = if unclamped == quantized {
+                    (quantized - zero_point - value / scale, 0.0)
+                } else {
+                    (quantized - zero_point, -scale)
+                };
+                grad_scale += 2.0 * error * d_scale;
+                grad_zero_point += 2.0 * error * d_zero_point;
+            }
+
+            grad_scale /= batch_size as f32;
+            grad_zero_point /= batch_size as f32;
+
+            // The zero-point gradient grows with the square of the scale; dividing by the
+            // initial scale squared keeps its step in code units whatever the tensor magnitude
+            let learning_rate = base_learning_rate / (1.0 + step as f32 * 0.01);
+            scale = (scale - learning_rate * grad_scale).max(initial_scale * 1e-3);
+            zero_point = (zero_point - learning_rate * grad_zero_point / (initial_scale * initial_scale))
+                .clamp(-qmax, 2.0 * qmax);
+
+            if (step + 1) % 50 == 0 || step + 1 == steps {
+                let candidate = QuantizationParameters {
+                    scale,
+                    zero_point: zero_point.round() as i32,
+                    min_val,
+                    max_val,
+                    clip_range: None,
+                };
+                let mse = self.parameters_mse(data, &candidate);
+                if mse < best_mse {
+                    best_mse = mse;
+                    best = candidate;
+                }
+            }
+        }
+
+        let quantized_data: Vec<i32> = data.iter()
+            .map(|&value| best.quantize_value(value, 0.0, qmax))
+            .collect();
+
+        let error_metrics = self.calculate_error_metrics(data, &quantized_data, &best);
+        let compression_ratio = 32.0 / self.config.precision.bits() as f32;
+
+        Ok(QuantizationResult {
+            quantized_data,
+            parameters: best,
+            compression_ratio,
+            error_metrics,
+            salience_preserved: 1.0, // Learned calibration doesn't consider salience
+            block_parameters: None,
+            block_size: None,
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
+        })
+    }
+
+    fn parameters_mse(&self, data: &[f32], params: &QuantizationParameters) -> f32 {
+        let qmax = self.config.precision.max_value();
+        data.iter()
+            .map(|&value| {
+                let error = params.dequantize_value(params.quantize_value(value, 0.0, qmax)) - value;
+                error * error
+            })
+            .sum::<f32>() / data.len() as f32
+    }
+
+    fn gptq_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        let cols = self.config.block_size;
+        if cols == 0 || data.is_empty() || data.len() % cols != 0 {
+            return Err(QuantizationError::TensorError(format!(
+                "GPTQ expects a row-major matrix with {} columns, got {} values",
+                cols,
+                data.len()
+            )));
+        }
+
+        let hessian_inverse = self.gptq_hessian_inverse_factor(cols)?;
+        let (qmin, qmax) = self.code_range();
+
+        let mut quantized_data = Vec::with_capacity(data.len());
+        let mut row_params = Vec::with_capacity(data.len() / cols);
+
+        for row in data.chunks(cols) {
+            let min_val = row.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+            let max_val = row.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            let params = self.range_parameters(min_val, max_val);
+
+            // Quantize one column at a time, pushing each column's error onto
+            // the columns that are still unquantized
+            let mut weights: Vec<f64> = row.iter().map(|&w| w as f64).collect();
+            for j in 0..cols {
+                let code = params.quantize_value(weights[j] as f32, qmin, qmax);
+                let error = (weights[j] - params.dequantize_value(code) as f64) / hessian_inverse[j][j];
+                for k in (j + 1)..cols {
+                    weights[k] -= error * hessian_inverse[j][k];
+                }
+                quantized_data.push(code);
+            }
+
+            row_params.push(params);
+        }
+
+        let reconstructed = dequantize_blocks(&quantized_data, &row_params, cols);
+        let error_metrics = self.calculate_reconstruction_metrics(data, &reconstructed);
+        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+
+        Ok(QuantizationResult {
+            quantized_data,
+            parameters: self.range_parameters(min_val, max_val),
+            compression_ratio: 32.0 / self.config.precision.bits() as f32,
+            error_metrics,
+            salience_preserved: 1.0, // GPTQ doesn't consider salience
+            block_parameters: Some(row_params),
+            block_size: Some(cols),
+            outliers: Vec::new(),
+            precision_map: Vec::new(),
+            precision_parameters: Vec::new(),
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
+        })
+    }
+
+    /// Upper Cholesky factor of the inverse Hessian `(X^T X / n + damping * I)^-1`,
+    /// with `X` drawn from `calibration_samples` seeded Gaussian activations
+    fn gptq_hessian_inverse_factor(&self, cols: usize) -> Result<Vec<Vec<f64>>, QuantizationError> {
+        let samples = self.config.calibration_samples.max(1);
+        let mut rng = SplitMix64::new(self.config.seed);
+        let mut upper = vec![vec![0.0f64; cols]; cols];
+        let mut activation = vec![0.0f64; cols];
+
+        for _ in 0..samples {
+            for value in activation.iter_mut() {
+                *value = rng.next_gaussian() as f64;
+            }
+            for (i, &a_i) in activation.iter().enumerate() {
+                for (h, &a_j) in upper[i][i..].iter_mut().zip(&activation[i..]) {
+                    *h += a_i * a_j;
+                }
+            }
+        }
+
+        let mean_diagonal = (0..cols).map(|i| upper[i][i]).sum::<f64>() / (cols as f64 * samples as f64);
+        let damping = 0.01 * mean_diagonal.max(1e-12);
+        let hessian: Vec<Vec<f64>> = (0..cols)
+            .map(|i| (0..cols).map(|j| {
+                let value = upper[i.min(j)][i.max(j)] / samples as f64;
+                if i == j { value + damping } else { value }
+            }).collect())
+            .collect();
+
+        let singular = || QuantizationError::TensorError("GPTQ Hessian is not positive definite".to_string());
+
+        // H^-1 = L^-T L^-1, then factor H^-1 = U^T U
+        let lower = cholesky(&hessian).ok_or_else(singular)?;
+        let mut lower_inverse = vec![vec![0.0f64; cols]; cols];
+        for i in 0..cols {
+            let row: Vec<f64> = (0..i)
+                .map(|j| -(j..i).map(|k| lower[i][k] * lower_inverse[k][j]).sum::<f64>() / lower[i][i])
+                .collect();
+            lower_inverse[i][..i].copy_from_slice(&row);
+            lower_inverse[i][i] = 1.0 / lower[i][i];
+        }
+
+        let mut hessian_inverse = vec![vec![0.0f64; cols]; cols];
+        for i in 0..cols {
+            for j in 0..cols {
+                hessian_inverse[i][j] = (i.max(j)..cols).map(|k| lower_inverse[k][i] * lower_inverse[k][j]).sum();
+            }
+        }
+
+        let factor = cholesky(&hessian_inverse).ok_or_else(singular)?;
+        Ok((0..cols).map(|i| (0..cols).map(|j| factor[j][i]).collect()).collect())
+    }
+
+    fn mixed_precision_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
+        let low = self.config.precision.clone();
+        let high = self.config.high_precision.clone();
+
+        let mut salience_preserved = 0.0;
+        let mut total_salience = 0.0;
+        let precision_map: Vec<PrecisionLevel> = (0..data.len())
+            .map(|i| {
+                let salience = self.salience_weights.get(&i).copied().unwrap_or(1.0);
+                total_salience += salience;
+                if salience >= self.config.salience_threshold {
+                    salience_preserved += salience;
+                    high.clone()
+                } else {
+                    low.clone()
+                }
+            })
+            .collect();
+        salience_preserved = if total_salience > 0.0 { salience_preserved / total_salience } else { 0.0 };
+
+        // Each precision group gets its own range calibration
+        let group_parameters = |precision: &PrecisionLevel| {
+            let (min_val, max_val) = data.iter()
+                .zip(&precision_map)
+                .filter(|(_, p)| *p == precision)
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), (&x, _)| (lo.min(x), hi.max(x)));
+            if min_val > max_val {
+                self.range_parameters_for(0.0, 0.0, precision)
+            } else {
+                self.range_parameters_for(min_val, max_val, precision)
+            }
+        };
+        let low_params = group_parameters(&low);
+        let high_params = group_parameters(&high);
+        let low_range = self.code_range_for(&low);
+        let high_range = self.code_range_for(&high);
+
+        let quantized_data: Vec<i32> = data.iter()
+            .zip(&precision_map)
+            .map(|(&value, precision)| {
+                if *precision == high {
+                    high_params.quantize_value(value, high_range.0, high_range.1)
+                } else {
+                    low_params.quantize_value(value, low_range.0, low_range.1)
+                }
+            })
+            .collect();
+
+        let total_bits: f32 = precision_map.iter().map(|p| p.bits() as f32).sum();
+        let compression_ratio = if total_bits > 0.0 {
+            32.0 * data.len() as f32 / total_bits
+        } else {
+            32.0 / low.bits() as f32
+        };
+
+        let mut result = QuantizationResult {
+            quantized_data,
+            parameters: low_params.clone(),
+            compression_ratio,
+            error_metrics: ErrorMetrics { mse: 0.0, mae: 0.0, max_error: 0.0, snr: f32::INFINITY, cosine_similarity: 1.0 },
+            salience_preserved,
+            block_parameters: None,
+            block_size: None,
+            outliers: Vec::new(),
+            precision_map,
+            precision_parameters: vec![(low, low_params), (high, high_params)],
+            codebook: Vec::new(),
+            effective_bits_per_value: 0.0,
+        };
+        result.error_metrics = self.calculate_reconstruction_metrics(data, &result.dequantized());
+
+        Ok(result)
     }
 
     fn adaptive_quantize(&self, data: &[f32]) -> Result<QuantizationResult, QuantizationError> {
@@ -357,16 +1343,57 @@ fn adaptive_quantize(&self, data: &[f32]) -> Result<QuantizationResult, Quantiza
         let variance = self.calculate_variance(data);
         let has_outliers = self.detect_outliers(data);
         
-        if variance > 1.0 && has_outliers {
-            // High variance with outliers: use blockwise
-            self.blockwise_quantize(data)
-        } else if !self.salience_weights.is_empty() {
-            // Has salience information: use salience-based
-            self.salience_quantize(data)
-        } else {
-            // Default: use linear
-            self.linear_quantize(data)
+        self.preserving_outliers(data, |inliers| {
+            if variance > 1.0 && has_outliers {
+                // High variance with outliers: use blockwise
+                self.blockwise_quantize(inliers)
+            } else if !self.salience_weights.is_empty() {
+                // Has salience information: use salience-based
+                self.salience_quantize_inliers(inliers)
+            } else {
+                // Default: use linear
+                self.linear_quantize(inliers)
+            }
+        })
+    }
+
+    /// Run `quantize` with outliers clipped out of the range calibration and
+    /// kept verbatim in `QuantizationResult::outliers` when `preserve_outliers` is set
+    fn preserving_outliers<F>(&self, data: &[f32], quantize: F) -> Result<QuantizationResult, QuantizationError>
+    where
+        F: Fn(&[f32]) -> Result<QuantizationResult, QuantizationError>,
+    {
+        if !self.config.preserve_outliers {
+            return quantize(data);
+        }
+
+        let Some((lower_bound, upper_bound)) = self.outlier_bounds(data) else {
+            return quantize(data);
+        };
+
+        let is_outlier = |x: f32| x < lower_bound || x > upper_bound;
+        let outliers: Vec<(usize, f32)> = data.iter()
+            .enumerate()
+            .filter(|(_, &x)| is_outlier(x))
+            .map(|(i, &x)| (i, x))
+            .collect();
+
+        if outliers.is_empty() {
+            return quantize(data);
         }
+
+        // Clip outliers to the inlier range so they don't stretch the scale
+        let inlier_min = data.iter().copied().filter(|&x| !is_outlier(x)).fold(f32::INFINITY, f32::min);
+        let inlier_max = data.iter().copied().filter(|&x| !is_outlier(x)).fold(f32::NEG_INFINITY, f32::max);
+        let clipped: Vec<f32> = data.iter().map(|&x| x.clamp(inlier_min, inlier_max)).collect();
+
+        let mut result = quantize(&clipped)?;
+        result.outliers = outliers;
+
+        let reconstructed = result.dequantized();
+        result.error_metrics = self.calculate_reconstruction_metrics(data, &reconstructed);
+
+        Ok(result)
     }
 
     fn calculate_error_metrics(&self, original: &[f32], quantized: &[i32], params: &QuantizationParameters) -> ErrorMetrics {
@@ -375,9 +1402,11 @@ fn calculate_error_metrics(&self, original: &[f32], quantized: &[i32], params: &
         let mut max_error: f32 = 0.0;
         let mut signal_power = 0.0;
         let mut noise_power = 0.0;
+        let mut reconstructed_power = 0.0;
+        let mut dot = 0.0;
 
         for (_i, (&orig, &quant)) in original.iter().zip(quantized.iter()).enumerate() {
-            let dequantized = (quant as f32 - params.zero_point as f32) * params.scale + params.min_val;
+            let dequantized = params.dequantize_value(quant);
             let error = orig - dequantized;
             
             mse += error * error;
@@ -386,6 +1415,8 @@ fn calculate_error_metrics(&self, original: &[f32], quantized: &[i32], params: &
             
             signal_power += orig * orig;
             noise_power += error * error;
+            reconstructed_power += dequantized * dequantized;
+            dot += orig * dequantized;
         }
 
         let n = original.len() as f32;
@@ -403,6 +1434,7 @@ fn calculate_error_metrics(&self, original: &[f32], quantized: &[i32], params: &
             mae,
             max_error,
             snr,
+            cosine_similarity: cosine_similarity(dot, signal_power, reconstructed_power),
         }
     }
 
@@ -412,6 +1444,8 @@ fn calculate_kmeans_error_metrics(&self, original: &[f32], assignments: &[i32],
         let mut max_error: f32 = 0.0;
         let mut signal_power = 0.0;
         let mut noise_power = 0.0;
+        let mut reconstructed_power = 0.0;
+        let mut dot = 0.0;
 
         for (&orig, &assignment) in original.iter().zip(assignments.iter()) {
             let centroid = centroids.get(assignment as usize).copied().unwrap_or(0.0);
@@ -423,6 +1457,8 @@ fn calculate_kmeans_error_metrics(&self, original: &[f32], assignments: &[i32],
             
             signal_power += orig * orig;
             noise_power += error * error;
+            reconstructed_power += centroid * centroid;
+            dot += orig * centroid;
         }
 
         let n = original.len() as f32;
@@ -440,16 +1476,110 @@ fn calculate_kmeans_error_metrics(&self, original: &[f32], assignments: &[i32],
             mae,
             max_error,
             snr,
+            cosine_similarity: cosine_similarity(dot, signal_power, reconstructed_power),
         }
     }
 
+    fn calculate_reconstruction_metrics(&self, original: &[f32], reconstructed: &[f32]) -> ErrorMetrics {
+        let mut mse = 0.0;
+        let mut mae = 0.0;
+        let mut max_error: f32 = 0.0;
+        let mut signal_power = 0.0;
+        let mut noise_power = 0.0;
+        let mut reconstructed_power = 0.0;
+        let mut dot = 0.0;
+
+        for (&orig, &value) in original.iter().zip(reconstructed.iter()) {
+            let error = orig - value;
+
+            mse += error * error;
+            mae += error.abs();
+            max_error = max_error.max(error.abs());
+
+            signal_power += orig * orig;
+            noise_power += error * error;
+            reconstructed_power += value * value;
+            dot += orig * value;
+        }
+
+        let n = original.len() as f32;
+        mse /= n;
+        mae /= n;
+
+        let snr = if noise_power > 0.0 {
+            10.0 * (signal_power / noise_power).log10()
+        } else {
+            f32::INFINITY
+        };
+
+        ErrorMetrics {
+            mse,
+            mae,
+            max_error,
+            snr,
+            cosine_similarity: cosine_similarity(dot, signal_power, reconstructed_power),
+        }
+    }
+
+    /// Run Lloyd iterations from a k-means++ seeding until the largest
+    /// centroid movement drops below `KMEANS_TOLERANCE` or the iteration cap is hit
+    fn kmeans_centroids(&self, data: &[f32], k: usize) -> Vec<f32> {
+        let mut centroids = self.initialize_centroids(data, k);
+
+        for _ in 0..self.config.kmeans_max_iterations {
+            let assignments = self.assign_to_centroids(data, &centroids);
+            let updated = self.update_centroids(data, &assignments, &centroids);
+            let max_shift = centroids.iter()
+                .zip(&updated)
+                .map(|(old, new)| (old - new).abs())
+                .fold(0.0f32, f32::max);
+            centroids = updated;
+
+            if max_shift < KMEANS_TOLERANCE {
+                break;
+            }
+        }
+
+        centroids
+    }
+
+    /// k-means++ seeding: each new centroid is drawn with probability
+    /// proportional to its squared distance from the nearest existing one
     fn initialize_centroids(&self, data: &[f32], k: usize) -> Vec<f32> {
-        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-        
-        (0..k).map(|i| {
-            min_val + (max_val - min_val) * (i as f32) / (k as f32 - 1.0)
-        }).collect()
+        if data.is_empty() {
+            return vec![0.0; k];
+        }
+
+        let mut rng = SplitMix64::new(self.config.seed);
+        let mut centroids = Vec::with_capacity(k);
+        centroids.push(data[rng.next_index(data.len())]);
+
+        let mut distances: Vec<f32> = data.iter().map(|&x| (x - centroids[0]).powi(2)).collect();
+        while centroids.len() < k {
+            let total: f32 = distances.iter().sum();
+            let next = if total > 0.0 {
+                let mut target = rng.next_f32() * total;
+                let mut chosen = data.len() - 1;
+                for (i, &distance) in distances.iter().enumerate() {
+                    if target < distance {
+                        chosen = i;
+                        break;
+                    }
+                    target -= distance;
+                }
+                data[chosen]
+            } else {
+                // Fewer distinct values than centroids
+                data[rng.next_index(data.len())]
+            };
+
+            centroids.push(next);
+            for (distance, &x) in distances.iter_mut().zip(data) {
+                *distance = distance.min((x - next).powi(2));
+            }
+        }
+
+        centroids
     }
 
     fn assign_to_centroids(&self, data: &[f32], centroids: &[f32]) -> Vec<usize> {
@@ -462,28 +1592,28 @@ fn find_closest_centroid(&self, value: f32, centroids: &[f32]) -> usize {
         centroids.iter()
             .enumerate()
             .min_by(|(_, &a), (_, &b)| {
-                (value - a).abs().partial_cmp(&(value - b).abs()).unwrap()
+                (value - a).abs().total_cmp(&(value - b).abs())
             })
             .map(|(i, _)| i)
             .unwrap_or(0)
     }
 
-    fn update_centroids(&self, data: &[f32], assignments: &[usize], k: usize) -> Vec<f32> {
-        let mut new_centroids = vec![0.0; k];
+    fn update_centroids(&self, data: &[f32], assignments: &[usize], centroids: &[f32]) -> Vec<f32> {
+        let k = centroids.len();
+        let mut sums = vec![0.0; k];
         let mut counts = vec![0; k];
 
         for (&value, &assignment) in data.iter().zip(assignments.iter()) {
-            new_centroids[assignment] += value;
+            sums[assignment] += value;
             counts[assignment] += 1;
         }
 
-        for i in 0..k {
-            if counts[i] > 0 {
-                new_centroids[i] /= counts[i] as f32;
-            }
-        }
-
-        new_centroids
+        // Empty clusters keep their previous centroid
+        sums.iter()
+            .zip(&counts)
+            .zip(centroids)
+            .map(|((&sum, &count), &previous)| if count > 0 { sum / count as f32 } else { previous })
+            .collect()
     }
 
     fn calculate_variance(&self, data: &[f32]) -> f32 {
@@ -493,8 +1623,17 @@ fn calculate_variance(&self, data: &[f32]) -> f32 {
     }
 
     fn detect_outliers(&self, data: &[f32]) -> bool {
-        let mut sorted_data = data.to_vec();
-        sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        match self.outlier_bounds(data) {
+            Some((lower_bound, upper_bound)) => data.iter().any(|&x| x < lower_bound || x > upper_bound),
+            None => false,
+        }
+    }
+
+    /// IQR fences outside of which values are considered outliers; non-finite
+    /// values are left out of the quartiles
+    fn outlier_bounds(&self, data: &[f32]) -> Option<(f32, f32)> {
+        let mut sorted_data: Vec<f32> = data.iter().copied().filter(|x| x.is_finite()).collect();
+        sorted_data.sort_by(|a, b| a.total_cmp(b));
         
         let q1_idx = sorted_data.len() / 4;
         let q3_idx = 3 * sorted_data.len() / 4;
@@ -503,20 +1642,143 @@ fn detect_outliers(&self, data: &[f32]) -> bool {
             let q1 = sorted_data[q1_idx];
             let q3 = sorted_data[q3_idx];
             let iqr = q3 - q1;
-            let lower_bound = q1 - 1.5 * iqr;
-            let upper_bound = q3 + 1.5 * iqr;
-            
-            data.iter().any(|&x| x < lower_bound || x > upper_bound)
+            Some((q1 - 1.5 * iqr, q3 + 1.5 * iqr))
         } else {
-            false
+            None
         }
     }
 
+    /// Map codes back to real values
+    ///
+    /// The plain iterator is auto-vectorized; see `benches/dequantize_bench.rs`
+    /// before replacing it with hand-written lanes.
     pub fn dequantize(&self, quantized: &[i32], params: &QuantizationParameters) -> Vec<f32> {
-        quantized.iter().map(|&q| {
-            (q as f32 - params.zero_point as f32) * params.scale + params.min_val
-        }).collect()
+        quantized.iter().map(|&q| params.dequantize_value(q)).collect()
+    }
+
+    /// Dequantize a result using its per-block parameters, falling back to
+    /// the global parameters when the result was not produced blockwise
+    pub fn dequantize_blockwise(&self, result: &QuantizationResult) -> Vec<f32> {
+        result.dequantize_codes()
+    }
+}
+
+/// Read exactly `count` little-endian `f32` values into `buffer`
+fn read_f32_chunk<R: Read>(reader: &mut R, count: usize, buffer: &mut Vec<f32>) -> std::io::Result<()> {
+    let mut bytes = vec![0u8; count * 4];
+    reader.read_exact(&mut bytes)?;
+    buffer.clear();
+    buffer.extend(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])));
+    Ok(())
+}
+
+/// Cosine similarity from a dot product and the two vectors' squared norms;
+/// two zero vectors count as identical
+fn cosine_similarity(dot: f32, original_power: f32, reconstructed_power: f32) -> f32 {
+    let norms = (original_power * reconstructed_power).sqrt();
+    if norms > 0.0 {
+        (dot / norms).clamp(-1.0, 1.0)
+    } else if original_power == reconstructed_power {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Dequantize consecutive `block_size` chunks of codes with their own parameters
+fn dequantize_blocks(quantized: &[i32], block_params: &[QuantizationParameters], block_size: usize) -> Vec<f32> {
+    quantized.chunks(block_size)
+        .zip(block_params.iter())
+        .flat_map(|(chunk, params)| chunk.iter().map(move |&q| params.dequantize_value(q)))
+        .collect()
+}
+
+/// Index of the entry of a sorted `codebook` closest to `value`
+fn nearest_code(value: f32, codebook: &[f32]) -> usize {
+    let upper = codebook.partition_point(|&code| code < value);
+    if upper == 0 {
+        0
+    } else if upper == codebook.len() || value - codebook[upper - 1] <= codebook[upper] - value {
+        upper - 1
+    } else {
+        upper
+    }
+}
+
+/// Quantize `values` to `Q4_1` nibbles over their own range, returning `(d, m)`
+fn requantize_q4_1(values: &[f32], codes: &mut [i32]) -> (f32, f32) {
+    let min_val = values.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+    let max_val = values.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+    let scale = (max_val - min_val) / 15.0;
+    for (slot, &value) in codes.iter_mut().zip(values) {
+        *slot = if scale > RANGE_EPSILON { ((value - min_val) / scale).round().clamp(0.0, 15.0) as i32 } else { 0 };
     }
+    (scale, min_val)
+}
+
+/// Quantize `values` to symmetric `Q8_0` codes over their absolute maximum, returning `(d, 0)`
+fn requantize_q8_0(values: &[f32], codes: &mut [i32]) -> (f32, f32) {
+    let abs_max = values.iter().fold(0.0f32, |a, &b| a.max(b.abs()));
+    let scale = abs_max / 127.0;
+    for (slot, &value) in codes.iter_mut().zip(values) {
+        *slot = if scale > RANGE_EPSILON { (value / scale).round().clamp(-127.0, 127.0) as i32 } else { 0 };
+    }
+    (scale, 0.0)
+}
+
+/// Small deterministic PRNG used for seeded calibration
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Standard normal sample via Box-Muller
+    fn next_gaussian(&mut self) -> f32 {
+        let u1 = self.next_f32().max(1e-7);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+    }
+}
+
+/// Lower-triangular Cholesky factor of a symmetric positive-definite matrix
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0f64; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] - sum;
+                if diagonal <= 0.0 {
+                    return None;
+                }
+                lower[i][j] = diagonal.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+    Some(lower)
 }
 
 /// Factory function to create quantizer instances
@@ -534,6 +1796,11 @@ pub fn quantize_tensor(data: &[f32], precision: PrecisionLevel) -> Result<Quanti
     quantizer.quantize(data)
 }
 
+/// Quantize at the narrowest precision whose reconstruction MSE stays below `max_mse`
+pub fn quantize_with_error_budget(data: &[f32], max_mse: f32) -> Result<(PrecisionLevel, QuantizationResult), QuantizationError> {
+    UnifiedQuantizer::new(QuantizationConfig::default()).auto_precision(data, max_mse)
+}
+
 pub fn quantize_with_salience(
     data: &[f32], 
     salience_weights: HashMap<usize, f32>, 
@@ -548,3 +1815,506 @@ pub fn quantize_with_salience(
     quantizer.set_salience_weights(salience_weights);
     quantizer.quantize(data)
 }
+
+/// Quantize salient values at `high_precision` and everything else at `low_precision`
+pub fn quantize_with_mixed_precision(
+    data: &[f32],
+    salience_weights: HashMap<usize, f32>,
+    low_precision: PrecisionLevel,
+    high_precision: PrecisionLevel,
+) -> Result<QuantizationResult, QuantizationError> {
+    let config = QuantizationConfig {
+        precision: low_precision,
+        high_precision,
+        algorithm: QuantizationAlgorithm::MixedPrecision,
+        ..Default::default()
+    };
+    let mut quantizer = UnifiedQuantizer::new(config);
+    quantizer.set_salience_weights(salience_weights);
+    quantizer.quantize(data)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gaussian(n: usize, seed: u64) -> Vec<f32> {
+        let mut rng = SplitMix64::new(seed);
+        (0..n).map(|_| rng.next_gaussian()).collect()
+    }
+
+    #[test]
+    fn test_parse_precision_level() {
+        assert_eq!("int4".parse::<PrecisionLevel>().unwrap(), PrecisionLevel::Int4);
+        assert_eq!("FP16".parse::<PrecisionLevel>().unwrap(), PrecisionLevel::FP16);
+        assert_eq!("nf4".parse::<PrecisionLevel>().unwrap(), PrecisionLevel::NF4);
+
+        for input in ["garbage", "in4", ""] {
+            let err = input.parse::<PrecisionLevel>().unwrap_err();
+            assert!(matches!(err, QuantizationError::InvalidPrecision(_)), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_learned_quantize_beats_linear_on_outliers() {
+        let mut data = gaussian(2048, 7);
+        data[100] = 12.0;
+        data[900] = 9.5;
+
+        let linear = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::Linear,
+            ..Default::default()
+        }).quantize(&data).unwrap();
+
+        let config = QuantizationConfig {
+            algorithm: QuantizationAlgorithm::Learned,
+            seed: 42,
+            ..Default::default()
+        };
+        let learned = UnifiedQuantizer::new(config.clone()).quantize(&data).unwrap();
+        let repeated = UnifiedQuantizer::new(config).quantize(&data).unwrap();
+
+        assert!(learned.error_metrics.mse < linear.error_metrics.mse);
+        assert_eq!(learned.quantized_data, repeated.quantized_data);
+    }
+
+    #[test]
+    fn test_constant_tensor_roundtrip() {
+        let data = vec![3.0; 256];
+        let result = quantize_tensor(&data, PrecisionLevel::Int8).unwrap();
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig::default());
+        let dequantized = quantizer.dequantize(&result.quantized_data, &result.parameters);
+
+        assert_eq!(dequantized, data);
+        assert_eq!(result.error_metrics.mse, 0.0);
+    }
+
+    #[test]
+    fn test_blockwise_roundtrip_keeps_block_parameters() {
+        let data: Vec<f32> = (0..256)
+            .map(|i| if i < 128 { i as f32 * 1e-3 } else { 1000.0 + i as f32 * 10.0 })
+            .collect();
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int8,
+            algorithm: QuantizationAlgorithm::BlockWise,
+            block_size: 128,
+            ..Default::default()
+        });
+        let result = quantizer.quantize(&data).unwrap();
+        assert_eq!(result.block_parameters.as_ref().map(Vec::len), Some(2));
+
+        let blockwise = quantizer.dequantize_blockwise(&result);
+        let averaged = quantizer.dequantize(&result.quantized_data, &result.parameters);
+
+        let max_error = |original: &[f32], reconstructed: &[f32]| original.iter()
+            .zip(reconstructed)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+
+        assert!(max_error(&data[..128], &blockwise[..128]) < 1e-3);
+        assert!(max_error(&data[128..], &blockwise[128..]) < 10.0);
+        assert!(max_error(&data, &averaged) > 100.0);
+    }
+
+    #[test]
+    fn test_salience_weights_from_activations() {
+        let mut activations = vec![0.1f32; 64];
+        activations[3] = 10.0;
+        activations[17] = -12.0;
+        activations[42] = 8.0;
+        activations[50] = f32::NAN;
+
+        let mut quantizer = UnifiedQuantizer::new(QuantizationConfig::default());
+        quantizer.set_salience_weights_from_activations(&activations);
+
+        let mut ranked: Vec<(usize, f32)> = quantizer.salience_weights.iter().map(|(&i, &w)| (i, w)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let top: Vec<usize> = ranked.iter().take(3).map(|&(i, _)| i).collect();
+        assert_eq!(top, vec![17, 3, 42]);
+        assert_eq!(ranked[0].1, 1.0);
+        assert!(ranked.iter().all(|&(_, w)| (0.0..=1.0).contains(&w)));
+        assert_eq!(quantizer.salience_weights[&50], 0.0);
+
+        quantizer.set_salience_weights_from_activations(&[0.0; 8]);
+        assert!(quantizer.salience_weights.is_empty());
+    }
+
+    #[test]
+    fn test_cosine_similarity_ignores_scale() {
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig::default());
+        let original: Vec<f32> = (1..=64).map(|i| i as f32 * 0.1).collect();
+        let codes: Vec<i32> = (1..=64).collect();
+        // Every code dequantizes to three times its original value
+        let tripled = QuantizationParameters { scale: 0.3, zero_point: 0, min_val: 0.0, max_val: 19.2, clip_range: None };
+
+        let metrics = quantizer.calculate_error_metrics(&original, &codes, &tripled);
+        assert!(metrics.mse > 1.0, "{}", metrics.mse);
+        assert!((metrics.cosine_similarity - 1.0).abs() < 1e-5, "{}", metrics.cosine_similarity);
+
+        let centroids: Vec<f32> = original.iter().map(|v| v * -2.0).collect();
+        let assignments: Vec<i32> = (0..64).collect();
+        let metrics = quantizer.calculate_kmeans_error_metrics(&original, &assignments, &centroids);
+        assert!((metrics.cosine_similarity + 1.0).abs() < 1e-5, "{}", metrics.cosine_similarity);
+    }
+
+    #[test]
+    fn test_dynamic_rows_reconstruct_independently() {
+        // Four tokens whose activations differ by up to six orders of magnitude
+        let magnitudes = [1e-3, 1.0, 50.0, 1e3];
+        let data: Vec<f32> = magnitudes.iter()
+            .flat_map(|&m| (0..64).map(move |i| m * ((i as f32 * 0.37).sin())))
+            .collect();
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int8,
+            algorithm: QuantizationAlgorithm::Dynamic,
+            block_size: 64,
+            preserve_outliers: false,
+            ..Default::default()
+        });
+
+        let result = quantizer.quantize(&data).unwrap();
+        assert_eq!(result.block_parameters.as_ref().map(Vec::len), Some(4));
+        assert_eq!(result.block_size, Some(64));
+
+        let restored = result.dequantized();
+        for (row, &magnitude) in magnitudes.iter().enumerate() {
+            let range = row * 64..(row + 1) * 64;
+            let max_error = data[range.clone()].iter()
+                .zip(&restored[range])
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0f32, f32::max);
+            // Int8 over a row's own [-m, m] range: half a step is m / 255
+            assert!(max_error <= magnitude / 255.0 * 1.01, "row {}: {} vs {}", row, max_error, magnitude);
+        }
+    }
+
+    #[test]
+    fn test_preserve_outliers_keeps_exact_value() {
+        let mut data: Vec<f32> = (0..256).map(|i| (i as f32 * 0.1).sin()).collect();
+        data[17] = 100.0;
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int4,
+            preserve_outliers: true,
+            ..Default::default()
+        });
+
+        let result = quantizer.quantize(&data).unwrap();
+        assert_eq!(result.outliers, vec![(17, 100.0)]);
+
+        let reconstructed = result.dequantized();
+        assert!((reconstructed[17] - 100.0).abs() < 1e-6);
+        for (i, (original, value)) in data.iter().zip(&reconstructed).enumerate() {
+            if i != 17 {
+                assert!((original - value).abs() < 0.1, "index {} drifted", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_finite_values_do_not_panic() {
+        let mut data: Vec<f32> = (0..64).map(|i| (i as f32 * 0.1).sin()).collect();
+        data[5] = f32::NAN;
+        data[9] = f32::INFINITY;
+
+        for algorithm in [QuantizationAlgorithm::SalienceBased, QuantizationAlgorithm::Adaptive, QuantizationAlgorithm::KMeans] {
+            let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+                algorithm,
+                preserve_outliers: true,
+                ..Default::default()
+            });
+            let result = quantizer.quantize(&data).unwrap();
+            assert_eq!(result.quantized_data.len(), data.len());
+        }
+    }
+
+    #[test]
+    fn test_symmetric_vs_asymmetric_on_zero_centered_tensor() {
+        let data: Vec<f32> = (0..512).map(|i| (i as f32 * 0.05).sin()).collect();
+        let quantize = |use_symmetric: bool| {
+            let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+                precision: PrecisionLevel::Int8,
+                algorithm: QuantizationAlgorithm::Linear,
+                use_symmetric,
+                ..Default::default()
+            });
+            let result = quantizer.quantize(&data).unwrap();
+            let reconstructed = quantizer.dequantize(&result.quantized_data, &result.parameters);
+            (result, reconstructed)
+        };
+
+        let (symmetric, symmetric_values) = quantize(true);
+        let (asymmetric, _) = quantize(false);
+
+        assert_eq!(symmetric.parameters.zero_point, 0);
+        assert!(symmetric.quantized_data.iter().all(|&q| (-128..=127).contains(&q)));
+        assert!(symmetric.quantized_data.iter().any(|&q| q < 0));
+        assert_eq!(symmetric_values[0], 0.0);
+
+        assert!(symmetric.error_metrics.mse < 1e-4);
+        assert!(asymmetric.error_metrics.mse < 1e-4);
+        assert!(symmetric.error_metrics.mse < asymmetric.error_metrics.mse * 1.5);
+    }
+
+    #[test]
+    fn test_gptq_reduces_error_on_random_matrix() {
+        let cols = 32;
+        let mut data = gaussian(16 * cols, 3);
+        for (row, chunk) in data.chunks_mut(cols).enumerate() {
+            let row_scale = 0.05 * (row + 1) as f32;
+            chunk.iter_mut().for_each(|w| *w *= row_scale);
+        }
+
+        let config = QuantizationConfig {
+            precision: PrecisionLevel::Int4,
+            block_size: cols,
+            calibration_samples: 256,
+            ..Default::default()
+        };
+        let linear = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::Linear,
+            ..config.clone()
+        }).quantize(&data).unwrap();
+        let gptq_quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::GPTQ,
+            ..config
+        });
+        let gptq = gptq_quantizer.quantize(&data).unwrap();
+
+        assert_eq!(gptq.quantized_data.len(), data.len());
+        assert!(gptq.error_metrics.mse < linear.error_metrics.mse * 0.5);
+
+        let reconstructed = gptq.dequantized();
+        let mse = data.iter().zip(&reconstructed).map(|(a, b)| (a - b).powi(2)).sum::<f32>() / data.len() as f32;
+        assert!((mse - gptq.error_metrics.mse).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_kmeans_recovers_cluster_means() {
+        let means = [-4.0f32, 0.5, 6.0];
+        let noise = gaussian(600, 11);
+        let clustered: Vec<f32> = noise.iter()
+            .enumerate()
+            .map(|(i, n)| means[i % 3] + n * 0.01)
+            .collect();
+        let mut sorted = clustered.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::KMeans,
+            ..Default::default()
+        });
+
+        for data in [&clustered, &sorted] {
+            let mut centroids = quantizer.kmeans_centroids(data, 3);
+            centroids.sort_by(|a, b| a.total_cmp(b));
+            for (centroid, mean) in centroids.iter().zip(means) {
+                assert!((centroid - mean).abs() < 0.01, "centroid {} far from {}", centroid, mean);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kmeans_dequantizes_through_centroids() {
+        let means = [-4.0f32, 0.5, 6.0];
+        let data: Vec<f32> = gaussian(300, 5).iter()
+            .enumerate()
+            .map(|(i, n)| means[i % 3] + n * 0.05)
+            .collect();
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::KMeans,
+            precision: PrecisionLevel::Int2,
+            ..Default::default()
+        });
+
+        let result = quantizer.quantize(&data).unwrap();
+        assert_eq!(result.codebook.len(), 4);
+        let roundtrip = result.roundtrip_mse(&data);
+        assert!((roundtrip - result.error_metrics.mse).abs() < 1e-6, "{} vs {}", roundtrip, result.error_metrics.mse);
+        assert!(roundtrip < 0.01);
+    }
+
+    #[test]
+    fn test_mixed_precision_follows_salience() {
+        let data = gaussian(512, 5);
+        let weights: HashMap<usize, f32> = (0..data.len())
+            .map(|i| (i, if i % 2 == 0 { 0.95 } else { 0.05 }))
+            .collect();
+
+        let mixed = quantize_with_mixed_precision(&data, weights, PrecisionLevel::Int4, PrecisionLevel::Int8).unwrap();
+        let uniform = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int4,
+            algorithm: QuantizationAlgorithm::Linear,
+            ..Default::default()
+        }).quantize(&data).unwrap();
+
+        for (i, precision) in mixed.precision_map.iter().enumerate() {
+            let expected = if i % 2 == 0 { PrecisionLevel::Int8 } else { PrecisionLevel::Int4 };
+            assert_eq!(*precision, expected);
+        }
+        assert!((mixed.salience_preserved - 0.95).abs() < 1e-4);
+        assert!(mixed.error_metrics.mse < uniform.error_metrics.mse);
+
+        let restored = mixed.dequantized();
+        let group_mse = |parity: usize| data.iter()
+            .zip(&restored)
+            .enumerate()
+            .filter(|(i, _)| i % 2 == parity)
+            .map(|(_, (a, b))| (a - b).powi(2))
+            .sum::<f32>();
+        assert!(group_mse(0) < group_mse(1));
+    }
+
+    #[test]
+    fn test_nf4_beats_int4_on_normal_weights() {
+        let data = gaussian(4096, 3);
+        let config = |precision| QuantizationConfig {
+            precision,
+            algorithm: QuantizationAlgorithm::BlockWise,
+            block_size: 256,
+            preserve_outliers: false,
+            ..Default::default()
+        };
+
+        let nf4 = UnifiedQuantizer::new(config(PrecisionLevel::NF4)).quantize(&data).unwrap();
+        let int4 = UnifiedQuantizer::new(config(PrecisionLevel::Int4)).quantize(&data).unwrap();
+
+        assert_eq!(nf4.codebook.len(), 16);
+        assert!(nf4.quantized_data.iter().all(|&q| (0..16).contains(&q)));
+        assert!((nf4.roundtrip_mse(&data) - nf4.error_metrics.mse).abs() < 1e-6);
+        assert!(nf4.roundtrip_mse(&data) < int4.roundtrip_mse(&data));
+    }
+
+    #[test]
+    fn test_auto_precision_follows_dynamic_range() {
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+            algorithm: QuantizationAlgorithm::Linear,
+            preserve_outliers: false,
+            ..Default::default()
+        });
+
+        let narrow: Vec<f32> = gaussian(4096, 11).iter().map(|x| 0.1 * x).collect();
+        let (precision, result) = quantizer.auto_precision(&narrow, 1e-3).unwrap();
+        assert_eq!(precision, PrecisionLevel::Int4);
+        assert!(result.roundtrip_mse(&narrow) < 1e-3);
+
+        let mut wide = narrow.clone();
+        wide[10] = 1000.0;
+        wide[20] = -1000.0;
+        let (precision, result) = quantizer.auto_precision(&wide, 1e-3).unwrap();
+        assert!(matches!(precision, PrecisionLevel::Int8 | PrecisionLevel::FP16), "{:?}", precision);
+        assert!(result.roundtrip_mse(&wide) < 1e-3);
+    }
+
+    #[test]
+    fn test_auto_precision_errors_when_budget_unreachable() {
+        let quantizer = UnifiedQuantizer::new(QuantizationConfig::default());
+        let data = gaussian(1024, 13);
+
+        let err = quantizer.auto_precision(&data, 0.0).unwrap_err();
+        assert!(matches!(err, QuantizationError::ValidationError(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_percentile_clipping_protects_bulk_from_outlier() {
+        let mut data = gaussian(4096, 5);
+        data[0] = 1000.0;
+        let quantize = |percentile_clipping| {
+            UnifiedQuantizer::new(QuantizationConfig {
+                precision: PrecisionLevel::Int8,
+                algorithm: QuantizationAlgorithm::Linear,
+                percentile_clipping,
+                ..Default::default()
+            })
+            .quantize(&data)
+            .unwrap()
+        };
+        let bulk_mse = |result: &QuantizationResult| {
+            let restored = result.dequantized();
+            data[1..].iter().zip(&restored[1..]).map(|(a, b)| (a - b).powi(2)).sum::<f32>() / (data.len() - 1) as f32
+        };
+
+        let plain = quantize(false);
+        let clipped = quantize(true);
+
+        assert!(plain.parameters.clip_range.is_none());
+        let (low, high) = clipped.parameters.clip_range.unwrap();
+        assert!(low < 0.0 && high > 0.0 && high < 10.0);
+        assert!(bulk_mse(&clipped) < 0.01 * bulk_mse(&plain));
+    }
+
+    /// Decode a `to_gguf_block` blob back into `(scales, offsets, codes)`
+    fn parse_gguf_blocks(blob: &[u8], nibbles: bool) -> (Vec<f32>, Vec<f32>, Vec<i32>) {
+        let block_len = if nibbles { 20 } else { 34 };
+        let f16_at = |bytes: &[u8]| f16::from_le_bytes([bytes[0], bytes[1]]).to_f32();
+        let mut scales = Vec::new();
+        let mut offsets = Vec::new();
+        let mut codes = Vec::new();
+        for block in blob.chunks_exact(block_len) {
+            scales.push(f16_at(&block[0..2]));
+            if nibbles {
+                offsets.push(f16_at(&block[2..4]));
+                codes.extend(block[4..].iter().map(|&b| (b & 0x0F) as i32));
+                codes.extend(block[4..].iter().map(|&b| (b >> 4) as i32));
+            } else {
+                offsets.push(0.0);
+                codes.extend(block[2..].iter().map(|&b| b as i8 as i32));
+            }
+        }
+        (scales, offsets, codes)
+    }
+
+    #[test]
+    fn test_gguf_block_round_trip() {
+        let data = gaussian(48, 13);
+
+        let int4 = quantize_tensor(&data, PrecisionLevel::Int4).unwrap();
+        let blob = int4.to_gguf_block();
+        assert_eq!(blob.len(), 2 * 20);
+        let (scales, offsets, codes) = parse_gguf_blocks(&blob, true);
+        let restored = int4.dequantized();
+        for (i, &q) in int4.quantized_data.iter().enumerate() {
+            let block = i / GGUF_BLOCK_VALUES;
+            let block_min = int4.quantized_data[block * GGUF_BLOCK_VALUES..].iter().take(GGUF_BLOCK_VALUES).min().unwrap();
+            assert!((scales[block] - int4.parameters.scale).abs() < 1e-3 * int4.parameters.scale);
+            assert_eq!(codes[i], q - block_min);
+            assert!((codes[i] as f32 * scales[block] + offsets[block] - restored[i]).abs() < 1e-2);
+        }
+
+        let int8 = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int8,
+            algorithm: QuantizationAlgorithm::Linear,
+            use_symmetric: true,
+            ..Default::default()
+        }).quantize(&data).unwrap();
+        let blob = int8.to_gguf_block();
+        assert_eq!(blob.len(), 2 * 34);
+        let (scales, _, codes) = parse_gguf_blocks(&blob, false);
+        assert!(scales.iter().all(|&d| (d - int8.parameters.scale).abs() < 1e-3 * int8.parameters.scale));
+        assert_eq!(&codes[..data.len()], &int8.quantized_data[..]);
+        assert!(codes[data.len()..].iter().all(|&q| q == 0));
+    }
+
+    #[test]
+    fn test_effective_bits_include_block_overhead() {
+        let data = gaussian(1024, 17);
+        let blockwise = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int4,
+            algorithm: QuantizationAlgorithm::BlockWise,
+            block_size: 4,
+            preserve_outliers: false,
+            ..Default::default()
+        }).quantize(&data).unwrap();
+
+        // 4 codes of 4 bits plus one 64-bit parameter set per block
+        assert_eq!(blockwise.compression_ratio, 8.0);
+        assert!((blockwise.effective_bits_per_value - 20.0).abs() < 1e-4, "{}", blockwise.effective_bits_per_value);
+
+        let linear = UnifiedQuantizer::new(QuantizationConfig {
+            precision: PrecisionLevel::Int4,
+            algorithm: QuantizationAlgorithm::Linear,
+            ..Default::default()
+        }).quantize(&data).unwrap();
+        assert!(linear.effective_bits_per_value > 4.0 && linear.effective_bits_per_value < 4.1);
+    }
+}
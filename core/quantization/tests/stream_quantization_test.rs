@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+use zeta_quantization::{
+    PrecisionLevel, QuantizationAlgorithm, QuantizationConfig, UnifiedQuantizer,
+};
+
+const VALUE_COUNT: usize = 10_000_000;
+
+/// Peak resident set size in KiB, where the platform exposes it
+fn peak_rss_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines()
+        .find(|line| line.starts_with("VmHWM:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+#[test]
+fn test_quantize_stream_large_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input_path = dir.path().join("weights.f32");
+    let output_path = dir.path().join("weights.q8");
+
+    {
+        let mut writer = BufWriter::new(File::create(&input_path).unwrap());
+        for i in 0..VALUE_COUNT {
+            let value = ((i % 1000) as f32 / 1000.0 - 0.5) * (1.0 + (i / 1_000_000) as f32);
+            writer.write_all(&value.to_le_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+
+    let quantizer = UnifiedQuantizer::new(QuantizationConfig {
+        precision: PrecisionLevel::Int8,
+        algorithm: QuantizationAlgorithm::BlockWise,
+        block_size: 4096,
+        ..Default::default()
+    });
+
+    let rss_before = peak_rss_kib();
+    let reader = BufReader::new(File::open(&input_path).unwrap());
+    let writer = BufWriter::new(File::create(&output_path).unwrap());
+    let result = quantizer.quantize_stream(reader, writer, VALUE_COUNT).unwrap();
+    let rss_after = peak_rss_kib();
+
+    assert!(result.quantized_data.is_empty());
+    assert_eq!(result.block_size, Some(4096));
+    assert_eq!(result.block_parameters.as_ref().map(Vec::len), Some((VALUE_COUNT + 4095) / 4096));
+    assert!(result.error_metrics.mse < 1e-4);
+    assert_eq!(std::fs::metadata(&output_path).unwrap().len(), VALUE_COUNT as u64);
+
+    // The tensor is 40 MB; streaming should stay far below that
+    if let (Some(before), Some(after)) = (rss_before, rss_after) {
+        assert!(after.saturating_sub(before) < 16 * 1024, "peak RSS grew by {} KiB", after - before);
+    }
+}
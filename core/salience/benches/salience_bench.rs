@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use zeta_salience::{SalienceConfig, UnifiedSalienceSystem};
+
+const TOKENS: u32 = 10_000;
+
+fn compute_salience(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_salience");
+    let tokens: Vec<u32> = (0..TOKENS).map(|i| (i * 7919) % 50_000).collect();
+    group.throughput(Throughput::Elements(TOKENS as u64));
+    group.sample_size(10);
+
+    group.bench_function("10k_tokens", |b| {
+        b.iter(|| {
+            let mut system = UnifiedSalienceSystem::new(SalienceConfig::default());
+            system.compute_salience(&tokens).unwrap()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, compute_salience);
+criterion_main!(benches);
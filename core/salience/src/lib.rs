@@ -21,9 +21,13 @@
 //! - Multiple salience analysis implementations
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -36,6 +40,10 @@ pub enum SalienceError {
     ConfigError(String),
     #[error("Memory allocation failed")]
     MemoryError,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +56,55 @@ pub struct SalienceConfig {
     pub phoneme_preservation: bool,
     pub enable_foraging: bool,
     pub adaptive_threshold: bool,
+    /// Per-step weight decay for `token_history`; the entry `k` steps old
+    /// counts `history_decay^k` as much as the newest one. 1.0 disables decay.
+    #[serde(default = "default_history_decay")]
+    pub history_decay: f32,
+    /// Blend of the factors that make up a token's base salience
+    #[serde(default)]
+    pub salience_weights: SalienceWeights,
+    /// Number of leading positions in each `compute_salience` call treated as
+    /// attention sinks: they always score 1.0 so the cache never evicts them
+    #[serde(default)]
+    pub sink_tokens: usize,
+    /// Seed for the stochastic foraging search; equal seeds and inputs give
+    /// identical `foraging_probability` values
+    #[serde(default)]
+    pub rng_seed: u64,
+}
+
+fn default_history_decay() -> f32 {
+    0.95
+}
+
+/// Weights of the frequency, context, novelty and attention factors in base salience
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SalienceWeights {
+    pub frequency: f32,
+    pub context: f32,
+    pub novelty: f32,
+    pub attention: f32,
+}
+
+impl Default for SalienceWeights {
+    fn default() -> Self {
+        Self {
+            frequency: 0.3,
+            context: 0.3,
+            novelty: 0.2,
+            attention: 0.2,
+        }
+    }
+}
+
+impl SalienceWeights {
+    fn as_array(&self) -> [f32; 4] {
+        [self.frequency, self.context, self.novelty, self.attention]
+    }
+
+    fn from_array([frequency, context, novelty, attention]: [f32; 4]) -> Self {
+        Self { frequency, context, novelty, attention }
+    }
 }
 
 impl Default for SalienceConfig {
@@ -61,6 +118,10 @@ fn default() -> Self {
             phoneme_preservation: true,
             enable_foraging: true,
             adaptive_threshold: true,
+            history_decay: default_history_decay(),
+            salience_weights: SalienceWeights::default(),
+            sink_tokens: 0,
+            rng_seed: 0,
         }
     }
 }
@@ -73,6 +134,9 @@ pub struct SalienceResult {
     pub phoneme_preserved: bool,
     pub foraging_probability: f32,
     pub role_inference: Option<String>,
+    /// Whether this position is an attention sink, see `SalienceConfig::sink_tokens`
+    #[serde(default)]
+    pub is_sink: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +158,27 @@ fn default() -> Self {
     }
 }
 
+/// Inputs at least this long are scored on the rayon pool
+const PARALLEL_MIN_TOKENS: usize = 256;
+
+/// Dopamine movement since the last notification that counts as a state change
+const DOPAMINE_CHANGE_THRESHOLD: f64 = 0.01;
+
+/// Observer invoked when the mesolimbic state changes materially
+pub type StateChangeCallback = Box<dyn Fn(&MesolimbicState) + Send + Sync>;
+
+/// On-disk representation of a `UnifiedSalienceSystem`
+#[derive(Serialize, Deserialize)]
+struct SalienceSnapshot {
+    config: SalienceConfig,
+    state: MesolimbicState,
+    token_history: HashMap<u32, Vec<f32>>,
+    phoneme_patterns: HashMap<u32, Vec<u32>>,
+    role_mappings: HashMap<u32, String>,
+    #[serde(default)]
+    phoneme_lexicon: HashMap<u32, Vec<u32>>,
+}
+
 /// Unified Salience and Mesolimbic System
 pub struct UnifiedSalienceSystem {
     config: SalienceConfig,
@@ -101,6 +186,10 @@ pub struct UnifiedSalienceSystem {
     token_history: HashMap<u32, Vec<f32>>,
     phoneme_patterns: HashMap<u32, Vec<u32>>,
     role_mappings: HashMap<u32, String>,
+    phoneme_lexicon: HashMap<u32, Vec<u32>>, // token id -> phoneme ids
+    embeddings: HashMap<u32, Vec<f32>>, // token id -> embedding vector
+    on_state_change: Option<StateChangeCallback>,
+    notified_dopamine: f64, // Dopamine level at the last state change notification
 }
 
 impl UnifiedSalienceSystem {
@@ -111,33 +200,102 @@ pub fn new(config: SalienceConfig) -> Self {
             token_history: HashMap::new(),
             phoneme_patterns: HashMap::new(),
             role_mappings: HashMap::new(),
+            phoneme_lexicon: HashMap::new(),
+            embeddings: HashMap::new(),
+            on_state_change: None,
+            notified_dopamine: MesolimbicState::default().dopamine_level,
         }
     }
 
-    /// Compute salience scores for a batch of tokens
+    /// Install token embeddings; similarity between two embedded tokens becomes
+    /// their cosine similarity. Embeddings are not persisted by `save_state`.
+    pub fn set_embeddings(&mut self, embeddings: HashMap<u32, Vec<f32>>) {
+        self.embeddings = embeddings;
+    }
+
+    /// Register a callback fired after a `compute_salience` batch that changes
+    /// the attention focus or moves dopamine by at least `DOPAMINE_CHANGE_THRESHOLD`
+    pub fn set_on_state_change(&mut self, callback: impl Fn(&MesolimbicState) + Send + Sync + 'static) {
+        self.on_state_change = Some(Box::new(callback));
+    }
+
+    /// Create a system that takes phoneme patterns from `lexicon`, falling back
+    /// to the ID heuristic only for tokens the lexicon does not cover
+    pub fn with_phoneme_lexicon(config: SalienceConfig, lexicon: HashMap<u32, Vec<u32>>) -> Self {
+        Self {
+            phoneme_lexicon: lexicon,
+            ..Self::new(config)
+        }
+    }
+
+    /// Compute salience scores for a batch of tokens.
+    /// Every token is scored against the state as it was before the batch;
+    /// history, phoneme patterns and roles are updated afterwards in token order.
     pub fn compute_salience(&mut self, tokens: &[u32]) -> Result<Vec<SalienceResult>, SalienceError> {
-        let mut results = Vec::with_capacity(tokens.len());
-        
-        for &token_id in tokens {
-            let result = self.compute_token_salience(token_id)?;
+        self.compute_salience_with(tokens, tokens.len() >= PARALLEL_MIN_TOKENS)
+    }
+
+    fn compute_salience_with(&mut self, tokens: &[u32], parallel: bool) -> Result<Vec<SalienceResult>, SalienceError> {
+        // Scoring phase: read-only against shared state
+        let recent_avg = self.compute_recent_average_salience();
+        let scored: Vec<(SalienceResult, Option<Vec<u32>>)> = if parallel {
+            tokens.par_iter()
+                .map(|&token_id| self.compute_token_salience(token_id, recent_avg))
+                .collect::<Result<_, _>>()?
+        } else {
+            tokens.iter()
+                .map(|&token_id| self.compute_token_salience(token_id, recent_avg))
+                .collect::<Result<_, _>>()?
+        };
+
+        // Mutation phase: applied serially in token order so both scoring paths agree
+        let mut results = Vec::with_capacity(scored.len());
+        for (position, (mut result, new_pattern)) in scored.into_iter().enumerate() {
+            if position < self.config.sink_tokens {
+                result.salience_score = 1.0;
+                result.is_sink = true;
+            }
+            if let Some(pattern) = new_pattern {
+                self.phoneme_patterns.entry(result.token_id).or_insert(pattern);
+            }
+            if let Some(role) = &result.role_inference {
+                self.role_mappings.entry(result.token_id).or_insert_with(|| role.clone());
+            }
+            self.update_token_history(result.token_id, result.salience_score);
             results.push(result);
         }
 
         // Update mesolimbic state based on results
+        let previous_focus = std::mem::take(&mut self.state.attention_focus);
         self.update_mesolimbic_state(&results);
+        self.notify_state_change(&previous_focus);
         
         Ok(results)
     }
 
-    fn compute_token_salience(&mut self, token_id: u32) -> Result<SalienceResult, SalienceError> {
+    fn notify_state_change(&mut self, previous_focus: &[u32]) {
+        let Some(callback) = &self.on_state_change else {
+            return;
+        };
+
+        let focus_changed = previous_focus != self.state.attention_focus.as_slice();
+        let dopamine_moved = (self.state.dopamine_level - self.notified_dopamine).abs() >= DOPAMINE_CHANGE_THRESHOLD;
+        if focus_changed || dopamine_moved {
+            callback(&self.state);
+            self.notified_dopamine = self.state.dopamine_level;
+        }
+    }
+
+    /// Score one token; also returns the phoneme pattern to record if the token had none
+    fn compute_token_salience(&self, token_id: u32, recent_avg: f32) -> Result<(SalienceResult, Option<Vec<u32>>), SalienceError> {
         // Base salience computation
         let base_salience = self.compute_base_salience(token_id);
         
         // Phoneme preservation analysis
-        let phoneme_preserved = if self.config.phoneme_preservation {
+        let (phoneme_preserved, new_pattern) = if self.config.phoneme_preservation {
             self.analyze_phoneme_preservation(token_id)
         } else {
-            true
+            (true, None)
         };
 
         // Foraging probability computation
@@ -155,46 +313,50 @@ fn compute_token_salience(&mut self, token_id: u32) -> Result<SalienceResult, Sa
 
         // Apply adaptive threshold
         let final_salience = if self.config.adaptive_threshold {
-            self.apply_adaptive_threshold(base_salience, token_id)
+            self.apply_adaptive_threshold(base_salience, recent_avg)
         } else {
             base_salience
         };
 
-        // Update token history
-        self.update_token_history(token_id, final_salience);
-
-        Ok(SalienceResult {
+        Ok((SalienceResult {
             token_id,
             salience_score: final_salience,
             confidence,
             phoneme_preserved,
             foraging_probability,
             role_inference,
-        })
+            is_sink: false,
+        }, new_pattern))
     }
 
     fn compute_base_salience(&self, token_id: u32) -> f32 {
-        // Multi-factor salience computation
-        let frequency_factor = self.compute_frequency_factor(token_id);
-        let context_factor = self.compute_context_factor(token_id);
-        let novelty_factor = self.compute_novelty_factor(token_id);
-        let attention_factor = self.compute_attention_factor(token_id);
-
-        // Weighted combination
-        let salience = frequency_factor * 0.3 
-                    + context_factor * 0.3 
-                    + novelty_factor * 0.2 
-                    + attention_factor * 0.2;
+        // Weighted combination of the multi-factor salience terms
+        let weights = self.config.salience_weights.as_array();
+        let salience: f32 = self.base_salience_factors(token_id).iter()
+            .zip(weights)
+            .map(|(factor, weight)| factor * weight)
+            .sum();
 
         salience.clamp(0.0, 1.0)
     }
 
+    /// Frequency, context, novelty and attention factors, in `SalienceWeights` order
+    fn base_salience_factors(&self, token_id: u32) -> [f32; 4] {
+        [
+            self.compute_frequency_factor(token_id),
+            self.compute_context_factor(token_id),
+            self.compute_novelty_factor(token_id),
+            self.compute_attention_factor(token_id),
+        ]
+    }
+
     fn compute_frequency_factor(&self, token_id: u32) -> f32 {
         // Higher frequency = lower base salience (common words less salient)
         let history = self.token_history.get(&token_id);
         match history {
             Some(hist) if !hist.is_empty() => {
-                let avg_occurrence = hist.len() as f32 / 1000.0; // Normalize
+                let occurrences: f32 = self.history_weights().take(hist.len()).sum();
+                let avg_occurrence = occurrences / 1000.0; // Normalize
                 (1.0 - avg_occurrence).max(0.1)
             }
             _ => 0.8 // New tokens are moderately salient
@@ -218,8 +380,8 @@ fn compute_novelty_factor(&self, token_id: u32) -> f32 {
         // Novel tokens (not seen recently) are more salient
         match self.token_history.get(&token_id) {
             Some(history) if !history.is_empty() => {
-                let recent_occurrences = history.iter().rev().take(10).count();
-                (10 - recent_occurrences) as f32 / 10.0
+                let recent_occurrences: f32 = self.history_weights().take(history.len().min(10)).sum();
+                (10.0 - recent_occurrences) / 10.0
             }
             _ => 1.0 // Completely new tokens are highly novel
         }
@@ -241,10 +403,18 @@ fn compute_token_similarity(&self, token1: u32, token2: u32) -> f32 {
             return 1.0;
         }
 
+        // Prefer semantic similarity when both tokens are embedded
+        if let (Some(embedding1), Some(embedding2)) = (
+            self.embeddings.get(&token1),
+            self.embeddings.get(&token2)
+        ) {
+            return cosine_similarity(embedding1, embedding2).clamp(0.0, 1.0);
+        }
+
         // Check phoneme patterns
         if let (Some(pattern1), Some(pattern2)) = (
-            self.phoneme_patterns.get(&token1),
-            self.phoneme_patterns.get(&token2)
+            self.phoneme_pattern(token1),
+            self.phoneme_pattern(token2)
         ) {
             let common_phonemes = pattern1.iter()
                 .filter(|&p| pattern2.contains(p))
@@ -258,18 +428,23 @@ fn compute_token_similarity(&self, token1: u32, token2: u32) -> f32 {
         }
     }
 
-    fn analyze_phoneme_preservation(&mut self, token_id: u32) -> bool {
+    /// Known phoneme pattern for a token, preferring the lexicon over generated patterns
+    fn phoneme_pattern(&self, token_id: u32) -> Option<&Vec<u32>> {
+        self.phoneme_lexicon.get(&token_id)
+            .or_else(|| self.phoneme_patterns.get(&token_id))
+    }
+
+    fn analyze_phoneme_preservation(&self, token_id: u32) -> (bool, Option<Vec<u32>>) {
         // Analyze if token preserves important phonemic information
-        if let Some(pattern) = self.phoneme_patterns.get(&token_id) {
+        if let Some(pattern) = self.phoneme_pattern(token_id) {
             // Check if pattern contains critical phonemes
             let critical_phonemes = [1, 2, 3, 5, 8, 13]; // Example critical phoneme IDs
-            pattern.iter().any(|&p| critical_phonemes.contains(&p))
+            (pattern.iter().any(|&p| critical_phonemes.contains(&p)), None)
         } else {
-            // Generate and store phoneme pattern for new token
+            // Generate a phoneme pattern for the new token; the caller stores it
             let pattern = self.generate_phoneme_pattern(token_id);
             let preserved = pattern.len() > 2; // Simple heuristic
-            self.phoneme_patterns.insert(token_id, pattern);
-            preserved
+            (preserved, Some(pattern))
         }
     }
 
@@ -293,22 +468,23 @@ fn generate_phoneme_pattern(&self, token_id: u32) -> Vec<u32> {
 
     fn compute_foraging_probability(&self, token_id: u32) -> f32 {
         // Two-loop stochastic foraging search
+        let exploration_reward = self.compute_exploration_reward(token_id);
+        let exploitation_reward = self.compute_exploitation_reward(token_id);
+        let exploration_factor = self.state.exploration_factor.clamp(0.0, 1.0);
+        let mut rng = self.foraging_rng(token_id);
         let mut total_probability = 0.0;
         
         // Outer loop
         for _ in 0..self.config.outer_loop_iterations {
             let mut inner_probability = 0.0;
             
-            // Inner loop
+            // Inner loop: explore with probability `exploration_factor`, otherwise exploit
             for _ in 0..self.config.inner_loop_iterations {
-                let exploration_reward = self.compute_exploration_reward(token_id);
-                let exploitation_reward = self.compute_exploitation_reward(token_id);
-                
-                // Balance exploration vs exploitation
-                let probability = exploration_reward * self.state.exploration_factor as f32
-                               + exploitation_reward * (1.0 - self.state.exploration_factor as f32);
-                
-                inner_probability += probability;
+                inner_probability += if rng.gen_bool(exploration_factor) {
+                    exploration_reward
+                } else {
+                    exploitation_reward
+                };
             }
             
             total_probability += inner_probability / self.config.inner_loop_iterations as f32;
@@ -317,6 +493,15 @@ fn compute_foraging_probability(&self, token_id: u32) -> f32 {
         (total_probability / self.config.outer_loop_iterations as f32).clamp(0.0, 1.0)
     }
 
+    /// RNG for one token's foraging search, derived from `rng_seed`, the token and
+    /// how often it has been seen. Per-token streams keep the parallel and serial
+    /// scoring paths identical.
+    fn foraging_rng(&self, token_id: u32) -> StdRng {
+        let visits = self.token_history.get(&token_id).map_or(0, Vec::len) as u64;
+        let stream = (u64::from(token_id) << 32 | visits).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        StdRng::seed_from_u64(self.config.rng_seed ^ stream)
+    }
+
     fn compute_exploration_reward(&self, token_id: u32) -> f32 {
         // Reward for exploring new or rare tokens
         let novelty = self.compute_novelty_factor(token_id);
@@ -327,7 +512,7 @@ fn compute_exploration_reward(&self, token_id: u32) -> f32 {
     fn compute_exploitation_reward(&self, token_id: u32) -> f32 {
         // Reward for exploiting known valuable tokens
         let history_value = self.token_history.get(&token_id)
-            .map(|hist| hist.iter().sum::<f32>() / hist.len() as f32)
+            .map(|hist| self.decayed_mean(hist))
             .unwrap_or(0.5);
         
         let attention_value = if self.state.attention_focus.contains(&token_id) { 0.8 } else { 0.2 };
@@ -335,7 +520,7 @@ fn compute_exploitation_reward(&self, token_id: u32) -> f32 {
         (history_value + attention_value) / 2.0
     }
 
-    fn infer_token_role(&mut self, token_id: u32) -> Option<String> {
+    fn infer_token_role(&self, token_id: u32) -> Option<String> {
         // Infer semantic role of token based on patterns and context
         if let Some(existing_role) = self.role_mappings.get(&token_id) {
             return Some(existing_role.clone());
@@ -352,9 +537,7 @@ fn infer_token_role(&mut self, token_id: u32) -> Option<String> {
             "rare_token"
         };
 
-        let role_string = role.to_string();
-        self.role_mappings.insert(token_id, role_string.clone());
-        Some(role_string)
+        Some(role.to_string())
     }
 
     fn compute_confidence(&self, token_id: u32, salience: f32) -> f32 {
@@ -364,7 +547,7 @@ fn compute_confidence(&self, token_id: u32, salience: f32) -> f32 {
                 if hist.len() < 2 {
                     0.5
                 } else {
-                    let variance = self.compute_variance(hist);
+                    let variance = self.decayed_variance(hist);
                     (1.0 - variance).clamp(0.0, 1.0)
                 }
             })
@@ -376,24 +559,38 @@ fn compute_confidence(&self, token_id: u32, salience: f32) -> f32 {
         (history_consistency + state_confidence + salience_confidence) / 3.0
     }
 
-    fn compute_variance(&self, values: &[f32]) -> f32 {
+    /// Recency weights for history entries, newest first
+    fn history_weights(&self) -> impl Iterator<Item = f32> {
+        let decay = self.config.history_decay.clamp(0.0, 1.0);
+        std::iter::successors(Some(1.0f32), move |w| Some(w * decay))
+    }
+
+    /// Recency-weighted mean of a token's history
+    fn decayed_mean(&self, values: &[f32]) -> f32 {
+        let (weighted, total) = values.iter().rev()
+            .zip(self.history_weights())
+            .fold((0.0, 0.0), |(sum, total), (&x, w)| (sum + w * x, total + w));
+        if total > 0.0 { weighted / total } else { 0.0 }
+    }
+
+    /// Recency-weighted variance of a token's history
+    fn decayed_variance(&self, values: &[f32]) -> f32 {
         if values.len() < 2 {
             return 0.0;
         }
 
-        let mean = values.iter().sum::<f32>() / values.len() as f32;
-        let variance = values.iter()
-            .map(|&x| (x - mean).powi(2))
-            .sum::<f32>() / values.len() as f32;
-        variance
+        let mean = self.decayed_mean(values);
+        let (weighted, total) = values.iter().rev()
+            .zip(self.history_weights())
+            .fold((0.0, 0.0), |(sum, total), (&x, w)| (sum + w * (x - mean).powi(2), total + w));
+        if total > 0.0 { weighted / total } else { 0.0 }
     }
 
-    fn apply_adaptive_threshold(&mut self, salience: f32, token_id: u32) -> f32 {
+    fn apply_adaptive_threshold(&self, salience: f32, recent_avg: f32) -> f32 {
         // Adaptive threshold based on recent performance and context
         let base_threshold = self.config.threshold as f32;
         
         // Adjust threshold based on recent salience distribution
-        let recent_avg = self.compute_recent_average_salience();
         let adaptive_threshold = if recent_avg > base_threshold {
             base_threshold * 1.1 // Raise threshold if recent salience is high
         } else {
@@ -410,7 +607,8 @@ fn apply_adaptive_threshold(&mut self, salience: f32, token_id: u32) -> f32 {
 
     fn compute_recent_average_salience(&self) -> f32 {
         let recent_values: Vec<f32> = self.token_history.values()
-            .filter_map(|hist| hist.last().copied())
+            .filter(|hist| !hist.is_empty())
+            .map(|hist| self.decayed_mean(hist))
             .collect();
         
         if recent_values.is_empty() {
@@ -462,9 +660,36 @@ pub fn get_state(&self) -> &MesolimbicState {
         &self.state
     }
 
+    /// Get the current configuration, including any trained weights
+    pub fn get_config(&self) -> &SalienceConfig {
+        &self.config
+    }
+
+    /// Independent copy of this system, including its learned history, for
+    /// exploring alternatives without disturbing the original. The state change
+    /// callback is not carried over.
+    pub fn fork(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            token_history: self.token_history.clone(),
+            phoneme_patterns: self.phoneme_patterns.clone(),
+            role_mappings: self.role_mappings.clone(),
+            phoneme_lexicon: self.phoneme_lexicon.clone(),
+            embeddings: self.embeddings.clone(),
+            notified_dopamine: self.notified_dopamine,
+            ..Self::new(self.config.clone())
+        }
+    }
+
+    /// Fork this system and run the fork with `config`
+    pub fn with_config(&self, config: SalienceConfig) -> Self {
+        Self { config, ..self.fork() }
+    }
+
     /// Reset the system state
     pub fn reset(&mut self) {
         self.state = MesolimbicState::default();
+        self.notified_dopamine = self.state.dopamine_level;
         self.token_history.clear();
         self.phoneme_patterns.clear();
         self.role_mappings.clear();
@@ -474,6 +699,94 @@ pub fn reset(&mut self) {
     pub fn update_config(&mut self, config: SalienceConfig) {
         self.config = config;
     }
+
+    /// Fit `salience_weights` to `(token, target salience)` examples by gradient descent
+    ///
+    /// Factors are computed once against the current state, then each epoch takes
+    /// one full-batch step on the mean squared error of the (unclamped) blend.
+    /// Returns the loss after every epoch.
+    pub fn train(&mut self, examples: &[(u32, f32)], epochs: usize, learning_rate: f32) -> Result<Vec<f32>, SalienceError> {
+        if examples.is_empty() {
+            return Err(SalienceError::ConfigError("training dataset is empty".to_string()));
+        }
+        if !(learning_rate > 0.0 && learning_rate.is_finite()) {
+            return Err(SalienceError::ConfigError(format!("learning rate must be positive (got {})", learning_rate)));
+        }
+
+        let features: Vec<[f32; 4]> = examples.iter()
+            .map(|&(token_id, _)| self.base_salience_factors(token_id))
+            .collect();
+        let predict = |weights: &[f32; 4], factors: &[f32; 4]| -> f32 {
+            factors.iter().zip(weights).map(|(f, w)| f * w).sum()
+        };
+
+        let count = examples.len() as f32;
+        let mut weights = self.config.salience_weights.as_array();
+        let mut losses = Vec::with_capacity(epochs);
+        for _ in 0..epochs {
+            let mut gradient = [0.0f32; 4];
+            for (factors, &(_, target)) in features.iter().zip(examples) {
+                let error = predict(&weights, factors) - target;
+                for (g, f) in gradient.iter_mut().zip(factors) {
+                    *g += 2.0 * error * f / count;
+                }
+            }
+            for (w, g) in weights.iter_mut().zip(gradient) {
+                *w -= learning_rate * g;
+            }
+
+            let loss = features.iter().zip(examples)
+                .map(|(factors, &(_, target))| (predict(&weights, factors) - target).powi(2))
+                .sum::<f32>() / count;
+            losses.push(loss);
+        }
+
+        self.config.salience_weights = SalienceWeights::from_array(weights);
+        Ok(losses)
+    }
+
+    /// Write the config, mesolimbic state and learned token maps to `path` as JSON
+    pub fn save_state(&self, path: &Path) -> Result<(), SalienceError> {
+        let snapshot = SalienceSnapshot {
+            config: self.config.clone(),
+            state: self.state.clone(),
+            token_history: self.token_history.clone(),
+            phoneme_patterns: self.phoneme_patterns.clone(),
+            role_mappings: self.role_mappings.clone(),
+            phoneme_lexicon: self.phoneme_lexicon.clone(),
+        };
+
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, &snapshot)?;
+        Ok(())
+    }
+
+    /// Rebuild a system from a file written by `save_state`
+    pub fn load_state(path: &Path) -> Result<Self, SalienceError> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let snapshot: SalienceSnapshot = serde_json::from_reader(file)?;
+
+        Ok(Self {
+            notified_dopamine: snapshot.state.dopamine_level,
+            state: snapshot.state,
+            token_history: snapshot.token_history,
+            phoneme_patterns: snapshot.phoneme_patterns,
+            role_mappings: snapshot.role_mappings,
+            phoneme_lexicon: snapshot.phoneme_lexicon,
+            ..Self::new(snapshot.config)
+        })
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
 }
 
 /// Factory function to create salience system instances
@@ -481,8 +794,241 @@ pub fn create_salience_system(config: SalienceConfig) -> UnifiedSalienceSystem {
     UnifiedSalienceSystem::new(config)
 }
 
+/// Load a phoneme lexicon from a JSON object mapping token ids to phoneme ids,
+/// e.g. `{"100": [1, 4, 7]}`
+pub fn load_phoneme_lexicon(path: &Path) -> Result<HashMap<u32, Vec<u32>>, SalienceError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Load a training dataset from a JSON object mapping token ids to target
+/// salience, e.g. `{"100": 0.8}`, sorted by token id
+pub fn load_salience_dataset(path: &Path) -> Result<Vec<(u32, f32)>, SalienceError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let targets: HashMap<u32, f32> = serde_json::from_reader(file)?;
+    let mut examples: Vec<(u32, f32)> = targets.into_iter().collect();
+    examples.sort_by_key(|&(token_id, _)| token_id);
+    Ok(examples)
+}
+
 /// Convenience function for quick salience computation
 pub fn compute_token_salience(tokens: &[u32]) -> Result<Vec<SalienceResult>, SalienceError> {
     let mut system = UnifiedSalienceSystem::new(SalienceConfig::default());
     system.compute_salience(tokens)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("salience.json");
+
+        let mut system = UnifiedSalienceSystem::new(SalienceConfig {
+            outer_loop_iterations: 2,
+            inner_loop_iterations: 2,
+            ..Default::default()
+        });
+        system.compute_salience(&[5, 42, 420, 5, 9000]).unwrap();
+        system.compute_salience(&[42, 7]).unwrap();
+        system.save_state(&path).unwrap();
+
+        let restored = UnifiedSalienceSystem::load_state(&path).unwrap();
+        assert_eq!(restored.token_history, system.token_history);
+        assert_eq!(restored.phoneme_patterns, system.phoneme_patterns);
+        assert_eq!(restored.role_mappings, system.role_mappings);
+        assert_eq!(restored.get_state().dopamine_level, system.get_state().dopamine_level);
+        assert_eq!(restored.get_state().attention_focus, system.get_state().attention_focus);
+    }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let config = SalienceConfig {
+            outer_loop_iterations: 4,
+            inner_loop_iterations: 4,
+            ..Default::default()
+        };
+        let tokens: Vec<u32> = (0..2_000u32).map(|i| (i * 7919) % 12_000).collect();
+
+        let mut serial = UnifiedSalienceSystem::new(config.clone());
+        let mut parallel = UnifiedSalienceSystem::new(config);
+        for chunk in tokens.chunks(500) {
+            let expected = serial.compute_salience_with(chunk, false).unwrap();
+            let actual = parallel.compute_salience_with(chunk, true).unwrap();
+            assert_eq!(
+                serde_json::to_string(&expected).unwrap(),
+                serde_json::to_string(&actual).unwrap()
+            );
+        }
+        assert_eq!(serial.token_history, parallel.token_history);
+        assert_eq!(serial.get_state().dopamine_level, parallel.get_state().dopamine_level);
+    }
+
+    #[test]
+    fn test_phoneme_lexicon_similarity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lexicon.json");
+        std::fs::write(&path, r#"{"100": [1, 4, 7], "200": [1, 4, 9], "300": [20, 21]}"#).unwrap();
+
+        let lexicon = load_phoneme_lexicon(&path).unwrap();
+        let system = UnifiedSalienceSystem::with_phoneme_lexicon(SalienceConfig::default(), lexicon);
+
+        // Two of three phonemes shared: 2 * 2 / 6
+        assert!((system.compute_token_similarity(100, 200) - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(system.compute_token_similarity(100, 300), 0.0);
+
+        // Lexicon tokens need no generated pattern; unknown tokens fall back to the heuristic
+        assert_eq!(system.analyze_phoneme_preservation(100), (true, None));
+        assert!(system.analyze_phoneme_preservation(12345).1.is_some());
+    }
+
+    #[test]
+    fn test_history_decay_tracks_recent_burst() {
+        let confidence = |history_decay: f32, history: &[f32]| {
+            let mut system = UnifiedSalienceSystem::new(SalienceConfig {
+                history_decay,
+                ..Default::default()
+            });
+            for &salience in history {
+                system.update_token_history(7, salience);
+            }
+            system.compute_confidence(7, 0.9)
+        };
+
+        let burst: Vec<f32> = std::iter::repeat(0.1).take(80)
+            .chain(std::iter::repeat(0.9).take(20))
+            .collect();
+        let steady = vec![0.9; 100];
+
+        // With decay the old low entries barely count, so confidence matches a steady high history
+        let decayed = confidence(0.8, &burst);
+        assert!((decayed - confidence(0.8, &steady)).abs() < 0.01, "{}", decayed);
+
+        // Without decay the old entries still drag consistency down
+        let undecayed = confidence(1.0, &burst);
+        assert!(decayed - undecayed > 0.02, "{} vs {}", decayed, undecayed);
+    }
+
+    #[test]
+    fn test_state_change_callback() {
+        let observed: Arc<std::sync::Mutex<Vec<Vec<u32>>>> = Arc::default();
+        let mut system = UnifiedSalienceSystem::new(SalienceConfig {
+            outer_loop_iterations: 2,
+            inner_loop_iterations: 2,
+            ..Default::default()
+        });
+        let sink = Arc::clone(&observed);
+        system.set_on_state_change(move |state| {
+            sink.lock().unwrap().push(state.attention_focus.clone());
+        });
+
+        // Unseen tokens score above the threshold and enter the focus set
+        system.compute_salience(&[500, 501, 502]).unwrap();
+        let calls = observed.lock().unwrap().clone();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0], system.get_state().attention_focus);
+        assert!(!calls[0].is_empty());
+    }
+
+    #[test]
+    fn test_embedding_similarity() {
+        let mut system = UnifiedSalienceSystem::new(SalienceConfig::default());
+        system.set_embeddings(HashMap::from([
+            (10, vec![1.0, 0.0, 0.2]),
+            (11, vec![0.9, 0.1, 0.25]),
+            (50_000, vec![-0.1, 1.0, 0.0]),
+        ]));
+
+        // Similarity comes from the embeddings rather than token-id distance
+        let near = system.compute_token_similarity(10, 11);
+        let far = system.compute_token_similarity(10, 50_000);
+        assert!(near > 0.95, "{}", near);
+        assert!(far < 0.1, "{}", far);
+        assert!(near > far);
+    }
+
+    #[test]
+    fn test_train_reduces_loss() {
+        let mut system = UnifiedSalienceSystem::new(SalienceConfig::default());
+        // Give tokens different histories and a shared attention focus so their factors differ
+        system.compute_salience(&[10, 11, 10, 500, 10, 11]).unwrap();
+
+        let examples = [(10, 0.2), (11, 0.4), (500, 0.9), (2000, 0.95), (12, 0.3)];
+        let losses = system.train(&examples, 200, 0.1).unwrap();
+
+        assert_eq!(losses.len(), 200);
+        assert!(losses.windows(2).all(|pair| pair[1] <= pair[0]), "{:?}", losses);
+        assert!(losses[199] < losses[0] * 0.5, "{} -> {}", losses[0], losses[199]);
+        assert_ne!(system.config.salience_weights, SalienceWeights::default());
+
+        // Learned weights travel with the saved state
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("salience.json");
+        system.save_state(&path).unwrap();
+        let restored = UnifiedSalienceSystem::load_state(&path).unwrap();
+        assert_eq!(restored.config.salience_weights, system.config.salience_weights);
+
+        assert!(system.train(&[], 10, 0.1).is_err());
+    }
+
+    #[test]
+    fn test_attention_sinks_get_max_salience() {
+        let mut system = UnifiedSalienceSystem::new(SalienceConfig {
+            sink_tokens: 2,
+            ..Default::default()
+        });
+        // Warm up history so the leading tokens would otherwise score below 1.0
+        system.compute_salience(&[1, 2, 1, 2]).unwrap();
+
+        let results = system.compute_salience(&[1, 2, 3, 4]).unwrap();
+        for result in &results[..2] {
+            assert_eq!(result.salience_score, 1.0);
+            assert!(result.is_sink);
+        }
+        assert!(results[2..].iter().all(|result| !result.is_sink));
+    }
+
+    #[test]
+    fn test_fork_leaves_original_untouched() {
+        let mut original = UnifiedSalienceSystem::new(SalienceConfig::default());
+        original.compute_salience(&[10, 11, 10, 12]).unwrap();
+        let history = original.token_history.clone();
+        let state = original.get_state().clone();
+
+        let mut fork = original.with_config(SalienceConfig {
+            adaptive_threshold: false,
+            ..Default::default()
+        });
+        assert_eq!(fork.token_history, history);
+        assert!(!fork.get_config().adaptive_threshold);
+
+        fork.compute_salience(&[10, 13, 14, 10]).unwrap();
+        assert_ne!(fork.token_history, history);
+        assert_eq!(original.token_history, history);
+        assert_eq!(original.get_state().attention_focus, state.attention_focus);
+        assert_eq!(original.get_state().dopamine_level, state.dopamine_level);
+        assert!(original.get_config().adaptive_threshold);
+    }
+
+    #[test]
+    fn test_foraging_is_reproducible_for_a_seed() {
+        let foraging = |rng_seed| {
+            let mut system = UnifiedSalienceSystem::new(SalienceConfig {
+                rng_seed,
+                outer_loop_iterations: 5,
+                inner_loop_iterations: 4,
+                ..Default::default()
+            });
+            system.compute_salience(&[1, 2, 3, 1, 2]).unwrap();
+            system.compute_salience(&(0..32).collect::<Vec<_>>()).unwrap()
+                .iter()
+                .map(|result| result.foraging_probability)
+                .collect::<Vec<f32>>()
+        };
+
+        assert_eq!(foraging(42), foraging(42));
+        assert_ne!(foraging(42), foraging(43));
+    }
+}
@@ -17,11 +17,14 @@
 //! This module consolidates common types from multiple crates
 
 use serde::{Serialize, Deserialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
 // use std::collections::HashMap; // Removed to fix unused import warning
 
 // Re-export core types
-pub use zeta_kv_cache::{KVCacheConfig, KVCacheError, PrecisionLevel as KVPrecisionLevel};
-pub use zeta_quantization::{QuantizationConfig, QuantizationError, PrecisionLevel, QuantizationResult};
+pub use zeta_kv_cache::{KVCacheConfig, KVCacheError, WindowConfig, PrecisionLevel as KVPrecisionLevel};
+pub use zeta_quantization::{QuantizationConfig, QuantizationError, PrecisionLevel, QuantizationResult, UnsupportedPrecision};
 pub use zeta_salience::{SalienceConfig, SalienceError, SalienceResult, MesolimbicState};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +42,9 @@ pub struct RuntimeConfig {
     pub enable_gpu: bool,
     pub batch_size: usize,
     pub timeout_seconds: u64,
+    /// Hugging Face `tokenizer.json` used to turn CLI input into token ids
+    #[serde(default)]
+    pub tokenizer_path: Option<PathBuf>,
 }
 
 impl Default for RuntimeConfig {
@@ -49,6 +55,7 @@ fn default() -> Self {
             enable_gpu: false,
             batch_size: 32,
             timeout_seconds: 300,
+            tokenizer_path: None,
         }
     }
 }
@@ -64,6 +71,75 @@ fn default() -> Self {
     }
 }
 
+impl ZetaConfig {
+    /// Check that every field is in a range the runtime can work with
+    ///
+    /// Deserialization only checks types, so this catches values like a zero
+    /// `block_size` before they surface as a panic deep inside the cache.
+    pub fn validate(&self) -> Result<()> {
+        fn invalid(field: &str, requirement: &str, got: impl std::fmt::Display) -> Result<()> {
+            Err(ZetaError::Config(format!("{} must be {} (got {})", field, requirement, got)))
+        }
+
+        let runtime = &self.runtime;
+        if runtime.worker_threads == 0 {
+            return invalid("runtime.worker_threads", "at least 1", runtime.worker_threads);
+        }
+        if runtime.batch_size == 0 {
+            return invalid("runtime.batch_size", "at least 1", runtime.batch_size);
+        }
+        if runtime.max_memory_mb == 0 {
+            return invalid("runtime.max_memory_mb", "at least 1", runtime.max_memory_mb);
+        }
+
+        let kv_cache = &self.kv_cache;
+        if kv_cache.block_size == 0 {
+            return invalid("kv_cache.block_size", "at least 1", kv_cache.block_size);
+        }
+        if !(0.0..=1.0).contains(&kv_cache.salience_threshold) {
+            return invalid("kv_cache.salience_threshold", "in [0, 1]", kv_cache.salience_threshold);
+        }
+        if !(kv_cache.lfu_decay_factor > 0.0 && kv_cache.lfu_decay_factor <= 1.0) {
+            return invalid("kv_cache.lfu_decay_factor", "in (0, 1]", kv_cache.lfu_decay_factor);
+        }
+        if let Some(window) = &kv_cache.window {
+            if window.window_size == 0 {
+                return invalid("kv_cache.window.window_size", "at least 1", window.window_size);
+            }
+        }
+
+        let quantization = &self.quantization;
+        if quantization.block_size == 0 {
+            return invalid("quantization.block_size", "at least 1", quantization.block_size);
+        }
+        if !(0.0..=1.0).contains(&quantization.salience_threshold) {
+            return invalid("quantization.salience_threshold", "in [0, 1]", quantization.salience_threshold);
+        }
+
+        let salience = &self.salience;
+        if !(0.0..=1.0).contains(&salience.threshold) {
+            return invalid("salience.threshold", "in [0, 1]", salience.threshold);
+        }
+        if salience.outer_loop_iterations == 0 {
+            return invalid("salience.outer_loop_iterations", "at least 1", salience.outer_loop_iterations);
+        }
+        if salience.inner_loop_iterations == 0 {
+            return invalid("salience.inner_loop_iterations", "at least 1", salience.inner_loop_iterations);
+        }
+        if !(salience.learning_rate > 0.0 && salience.learning_rate.is_finite()) {
+            return invalid("salience.learning_rate", "a positive number", salience.learning_rate);
+        }
+        if !(0.0..=1.0).contains(&salience.discount_factor) {
+            return invalid("salience.discount_factor", "in [0, 1]", salience.discount_factor);
+        }
+        if !(salience.history_decay > 0.0 && salience.history_decay <= 1.0) {
+            return invalid("salience.history_decay", "in (0, 1]", salience.history_decay);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingStats {
     pub tokens_processed: usize,
@@ -82,6 +158,42 @@ pub struct ModelMetadata {
     pub parameters: u64,
     pub precision: PrecisionLevel,
     pub created_at: String,
+    /// Hex SHA-256 of the model file; empty when the model wasn't registered from disk
+    #[serde(default)]
+    pub sha256: String,
+}
+
+impl ModelMetadata {
+    /// Recompute the checksum of `path` and compare it with the recorded `sha256`
+    pub fn verify_integrity(&self, path: &Path) -> Result<()> {
+        if self.sha256.is_empty() {
+            return Err(ZetaError::Integrity(format!("no checksum recorded for model {}", self.name)));
+        }
+        let actual = sha256_file(path)?;
+        if !actual.eq_ignore_ascii_case(&self.sha256) {
+            return Err(ZetaError::Integrity(format!(
+                "{} does not match model {}: expected sha256 {}, got {}",
+                path.display(), self.name, self.sha256, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Hex SHA-256 digest of a file's contents, read in chunks
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let read_error = |e: std::io::Error| ZetaError::Runtime(format!("Failed to read {}: {}", path.display(), e));
+    let mut file = std::fs::File::open(path).map_err(read_error)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let read = file.read(&mut buffer).map_err(read_error)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
 }
 
 use thiserror::Error as ThisError;
@@ -100,4 +212,117 @@ pub enum ZetaError {
     Quantization(String),
     #[error("Salience error: {0}")]
     Salience(String),
+    #[error("Integrity error: {0}")]
+    Integrity(String),
+}
+
+impl From<KVCacheError> for ZetaError {
+    fn from(err: KVCacheError) -> Self {
+        ZetaError::KVCache(err.to_string())
+    }
+}
+
+impl From<QuantizationError> for ZetaError {
+    fn from(err: QuantizationError) -> Self {
+        ZetaError::Quantization(err.to_string())
+    }
+}
+
+impl From<SalienceError> for ZetaError {
+    fn from(err: SalienceError) -> Self {
+        ZetaError::Salience(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_error(config: &ZetaConfig) -> String {
+        match config.validate() {
+            Err(ZetaError::Config(message)) => message,
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        ZetaConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_worker_threads() {
+        let mut config = ZetaConfig::default();
+        config.runtime.worker_threads = 0;
+        assert_eq!(config_error(&config), "runtime.worker_threads must be at least 1 (got 0)");
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_salience_threshold() {
+        let mut config = ZetaConfig::default();
+        config.kv_cache.salience_threshold = -0.5;
+        assert_eq!(config_error(&config), "kv_cache.salience_threshold must be in [0, 1] (got -0.5)");
+
+        let mut config = ZetaConfig::default();
+        config.kv_cache.salience_threshold = 2.0;
+        assert_eq!(config_error(&config), "kv_cache.salience_threshold must be in [0, 1] (got 2)");
+
+        let mut config = ZetaConfig::default();
+        config.quantization.salience_threshold = -1.0;
+        assert!(config_error(&config).starts_with("quantization.salience_threshold"));
+
+        let mut config = ZetaConfig::default();
+        config.quantization.salience_threshold = 1.5;
+        assert!(config_error(&config).starts_with("quantization.salience_threshold"));
+
+        let mut config = ZetaConfig::default();
+        config.salience.threshold = 1.1;
+        assert!(config_error(&config).starts_with("salience.threshold"));
+
+        let mut config = ZetaConfig::default();
+        config.salience.threshold = -0.1;
+        assert!(config_error(&config).starts_with("salience.threshold"));
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_block_size() {
+        let mut config = ZetaConfig::default();
+        config.kv_cache.block_size = 0;
+        assert_eq!(config_error(&config), "kv_cache.block_size must be at least 1 (got 0)");
+
+        let mut config = ZetaConfig::default();
+        config.quantization.block_size = 0;
+        assert_eq!(config_error(&config), "quantization.block_size must be at least 1 (got 0)");
+
+        let mut config = ZetaConfig::default();
+        config.kv_cache.window = Some(WindowConfig { window_size: 0 });
+        assert_eq!(config_error(&config), "kv_cache.window.window_size must be at least 1 (got 0)");
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_factors() {
+        let mut config = ZetaConfig::default();
+        config.runtime.batch_size = 0;
+        assert!(config_error(&config).starts_with("runtime.batch_size"));
+
+        let mut config = ZetaConfig::default();
+        config.kv_cache.lfu_decay_factor = 1.5;
+        assert!(config_error(&config).starts_with("kv_cache.lfu_decay_factor"));
+
+        let mut config = ZetaConfig::default();
+        config.salience.discount_factor = 2.0;
+        assert!(config_error(&config).starts_with("salience.discount_factor"));
+
+        let mut config = ZetaConfig::default();
+        config.salience.history_decay = 0.0;
+        assert!(config_error(&config).starts_with("salience.history_decay"));
+
+        let mut config = ZetaConfig::default();
+        config.salience.outer_loop_iterations = 0;
+        assert_eq!(config_error(&config), "salience.outer_loop_iterations must be at least 1 (got 0)");
+
+        let mut config = ZetaConfig::default();
+        config.salience.inner_loop_iterations = 0;
+        assert!(config_error(&config).starts_with("salience.inner_loop_iterations"));
+    }
 }
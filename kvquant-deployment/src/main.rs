@@ -30,6 +30,7 @@ fn default() -> Self {
                 max_cache_items: 50000,
                 salience_threshold: 0.7,
                 enable_debug_logging: true,
+                auth: Default::default(),
             },
             scheduler_window: 100,
             mesolimbic_iterations: 50,
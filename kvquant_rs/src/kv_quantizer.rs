@@ -427,6 +427,7 @@ fn test_quantize_dequantize_int8() {
             precision: PrecisionLevel::Int8,
             enable_debug_logging: false,
             max_cache_items: 1000,
+            auth: Default::default(),
         };
         
         let quantizer = KVQuantizer::new(config);
@@ -33,6 +33,25 @@ pub struct KVQuantConfig {
     pub enable_debug_logging: bool,
     /// Maximum number of items in cache
     pub max_cache_items: usize,
+    /// Caller authentication for the sidecar gRPC service
+    #[serde(default)]
+    pub auth: AuthConfig,
+}
+
+/// Authentication settings for the sidecar gRPC service
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AuthConfig {
+    /// Token callers must send as `authorization: Bearer <token>`; `None` disables auth
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("bearer_token", &self.bearer_token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
 }
 
 impl Default for KVQuantConfig {
@@ -44,6 +63,7 @@ fn default() -> Self {
             precision: PrecisionLevel::Int8,
             enable_debug_logging: false,
             max_cache_items: 1000,
+            auth: AuthConfig::default(),
         }
     }
 }
@@ -61,6 +81,32 @@ pub enum PrecisionLevel {
     Bit1,
 }
 
+impl From<PrecisionLevel> for zeta_quantization::PrecisionLevel {
+    fn from(level: PrecisionLevel) -> Self {
+        match level {
+            PrecisionLevel::Int8 => Self::Int8,
+            PrecisionLevel::Int4 => Self::Int4,
+            PrecisionLevel::Int2 => Self::Int2,
+            PrecisionLevel::Bit1 => Self::Int1,
+        }
+    }
+}
+
+impl TryFrom<zeta_quantization::PrecisionLevel> for PrecisionLevel {
+    type Error = zeta_quantization::UnsupportedPrecision;
+
+    fn try_from(level: zeta_quantization::PrecisionLevel) -> Result<Self, Self::Error> {
+        use zeta_quantization::PrecisionLevel as Canonical;
+        match level {
+            Canonical::Int8 => Ok(Self::Int8),
+            Canonical::Int4 => Ok(Self::Int4),
+            Canonical::Int2 => Ok(Self::Int2),
+            Canonical::Int1 => Ok(Self::Bit1),
+            other => Err(zeta_quantization::UnsupportedPrecision(other)),
+        }
+    }
+}
+
 /// Trait for quantization data that provides access to precision information
 pub trait QuantizationDataTrait {
     /// Get the precision level used for quantization
@@ -113,3 +159,22 @@ pub enum QuantizationError {
     Other(String),
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zeta_quantization::{PrecisionLevel as Canonical, UnsupportedPrecision};
+
+    #[test]
+    fn test_precision_round_trips_through_canonical() {
+        for level in [PrecisionLevel::Int8, PrecisionLevel::Int4, PrecisionLevel::Int2, PrecisionLevel::Bit1] {
+            let canonical = Canonical::from(level);
+            assert_eq!(PrecisionLevel::try_from(canonical), Ok(level));
+        }
+        assert_eq!(Canonical::from(PrecisionLevel::Bit1), Canonical::Int1);
+
+        for level in [Canonical::NF4, Canonical::FP16, Canonical::FP32] {
+            assert_eq!(PrecisionLevel::try_from(level.clone()), Err(UnsupportedPrecision(level)));
+        }
+    }
+}
+
@@ -13,12 +13,22 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
-use std::sync::Arc;
+#[cfg(feature = "tls")]
+use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use dashmap::DashMap;
 use tonic::{transport::Server, Request, Response, Status};
+use tonic::service::{interceptor::InterceptedService, Interceptor};
 use log::{info, error, debug};
 use serde::Serialize;
 use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_stream::{Stream, StreamExt};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use zeta_salience::{SalienceConfig, UnifiedSalienceSystem};
 
 
 // Include the generated protobuf code and re-export the service traits and types
@@ -70,7 +80,7 @@ pub enum KVQuantError {
 pub use crate::block::{DataBlock, BlockState};
 pub use crate::spot::SpotManager;
 pub use crate::kv_cache::LogStructuredKVCache;
-pub use crate::kvquant_config::{KVQuantConfig, PrecisionLevel, QuantizationResult, QuantizationData};
+pub use crate::kvquant_config::{AuthConfig, KVQuantConfig, PrecisionLevel, QuantizationResult, QuantizationData};
 pub use crate::role_inferer::{RoleInferer, RoleInferenceResult};
 pub use crate::mesolimbic_system::{MesolimbicSystem, SalienceResult};
 pub use crate::kv_quantizer::KVQuantizer;
@@ -119,10 +129,93 @@ pub struct KVQuantService {
     config: KVQuantConfig,
     /// In-memory cache for key-value storage
     cache: DashMap<String, Vec<u8>>,
+    /// Recency order of cached keys, for LRU eviction. Taken before any `cache`
+    /// shard lock, and held across eviction and insertion so writers at
+    /// capacity don't each evict for the same free slot.
+    lru: Mutex<LruOrder>,
     /// Metrics for monitoring
     metrics: ServiceMetrics,
+    /// Publishes cache inserts and evictions to `WatchCache` subscribers
+    events: broadcast::Sender<CacheEvent>,
+    /// Salience system whose state `GetMesolimbicState` reports
+    salience: Arc<RwLock<UnifiedSalienceSystem>>,
 }
 
+/// The sidecar gRPC service wrapped in its bearer-token check
+pub type AuthenticatedSidecarServer = InterceptedService<SidecarServiceServer<KVQuantService>, BearerAuth>;
+
+/// Rejects calls whose `authorization` header does not carry the configured bearer token
+#[derive(Clone)]
+pub struct BearerAuth {
+    expected: Option<String>,
+}
+
+impl BearerAuth {
+    /// Builds the interceptor from `config`; without a token every call is allowed
+    pub fn new(config: &AuthConfig) -> Self {
+        Self { expected: config.bearer_token.as_ref().map(|token| format!("Bearer {}", token)) }
+    }
+}
+
+impl Interceptor for BearerAuth {
+    fn call(&mut self, request: Request<()>) -> std::result::Result<Request<()>, Status> {
+        let Some(expected) = &self.expected else {
+            return Ok(request);
+        };
+        let provided = request.metadata()
+            .get("authorization")
+            .map(|value| value.as_bytes())
+            .unwrap_or_default();
+
+        if constant_time_eq(provided, expected.as_bytes()) {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("Missing or invalid bearer token"))
+        }
+    }
+}
+
+/// Compares secrets without exiting early on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Cached keys ordered by logical time of their last read or write
+#[derive(Debug, Default)]
+struct LruOrder {
+    by_tick: BTreeMap<u64, String>,
+    ticks: HashMap<String, u64>,
+    clock: u64,
+}
+
+impl LruOrder {
+    /// Mark `key` as the most recently used
+    fn touch(&mut self, key: &str) {
+        let tick = self.clock;
+        self.clock += 1;
+        if let Some(previous) = self.ticks.insert(key.to_string(), tick) {
+            self.by_tick.remove(&previous);
+        }
+        self.by_tick.insert(tick, key.to_string());
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(tick) = self.ticks.remove(key) {
+            self.by_tick.remove(&tick);
+        }
+    }
+
+    /// Remove and return the least recently used key
+    fn pop_oldest(&mut self) -> Option<String> {
+        let (_, key) = self.by_tick.pop_first()?;
+        self.ticks.remove(&key);
+        Some(key)
+    }
+}
+
+/// Number of cache events buffered per `WatchCache` subscriber before it lags
+const CACHE_EVENT_BUFFER: usize = 1024;
+
 /// Service metrics for monitoring
 #[derive(Debug, Default)]
 struct ServiceMetrics {
@@ -146,12 +239,14 @@ pub struct ServiceMetricsSnapshot {
 
 #[tonic::async_trait]
 impl SidecarService for KVQuantService {
+    type WatchCacheStream = Pin<Box<dyn Stream<Item = std::result::Result<CacheEvent, Status>> + Send>>;
+
     async fn get_cached_data(
         &self,
         request: Request<CacheRequest>,
     ) -> std::result::Result<Response<CacheResponse>, Status> {
         let inner_result = (|| -> Result<Response<CacheResponse>> {
-            self.metrics.total_requests.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.metrics.total_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
             
             let req = request.into_inner();
             let cache_key = format!("{}:{}", req.vector_id, req.layer_id);
@@ -160,22 +255,25 @@ async fn get_cached_data(
                 debug!("Looking up cache key: {}", cache_key);
             }
             
-            match self.cache.get(&cache_key) {
+            // Release the shard before touching; the LRU lock is always taken first
+            let cached = self.cache.get(&cache_key).map(|data| data.value().clone());
+            match cached {
                 Some(data) => {
-                    self.metrics.cache_hits.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    self.metrics.cache_hits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    self.touch(&cache_key);
                     
                     if self.config.enable_debug_logging {
                         debug!("Cache hit for key: {} ({} bytes)", cache_key, data.len());
                     }
                     
                     let response = CacheResponse {
-                        data: data.value().clone(),
+                        data,
                         status: "OK".to_string(),
                     };
                     Ok(Response::new(response))
                 }
                 None => {
-                    self.metrics.cache_misses.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    self.metrics.cache_misses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
                     
                     if self.config.enable_debug_logging {
                         debug!("Cache miss for key: {}", cache_key);
@@ -197,26 +295,36 @@ async fn update_cache(
         request: Request<CacheUpdate>,
     ) -> std::result::Result<Response<UpdateResponse>, Status> {
         let inner_result = (|| -> Result<Response<UpdateResponse>> {
-            self.metrics.total_requests.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.metrics.total_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
             let req = request.into_inner();
 
-            // Check if we need to evict old entries to make space
-            if self.cache.len() >= self.config.max_cache_items {
-                // Simple eviction strategy: remove the first item
-                if let Some(entry) = self.cache.iter().next() {
-                    let key = entry.key().clone();
-                    self.cache.remove(&key);
-                    if self.config.enable_debug_logging {
-                        debug!("Evicted key from cache: {}", key);
+            if self.config.enable_debug_logging {
+                debug!("Updating cache for vector_id: {} ({} bytes)", req.vector_id, req.data.len());
+            }
+
+            // Make space by evicting the least recently used entries; overwrites need none
+            let mut evicted = Vec::new();
+            {
+                let mut lru = self.lock_lru();
+                while !self.cache.contains_key(&req.vector_id) && self.cache.len() >= self.config.max_cache_items {
+                    let Some(key) = lru.pop_oldest() else {
+                        break;
+                    };
+                    if self.cache.remove(&key).is_some() {
+                        evicted.push(key);
                     }
                 }
+                lru.touch(&req.vector_id);
+                self.cache.insert(req.vector_id.clone(), req.data);
             }
 
-            if self.config.enable_debug_logging {
-                debug!("Updating cache for vector_id: {} ({} bytes)", req.vector_id, req.data.len());
+            for key in evicted {
+                if self.config.enable_debug_logging {
+                    debug!("Evicted key from cache: {}", key);
+                }
+                self.publish(key, CacheOp::Evict);
             }
-
-            self.cache.insert(req.vector_id.clone(), req.data);
+            self.publish(req.vector_id, CacheOp::Insert);
 
             let response = UpdateResponse {
                 status: "OK".to_string(),
@@ -225,6 +333,74 @@ async fn update_cache(
         })();
         inner_result.map_err(|e| Status::internal(e.to_string()))
     }
+
+    async fn delete_cache(
+        &self,
+        request: Request<CacheDelete>,
+    ) -> std::result::Result<Response<DeleteResponse>, Status> {
+        self.metrics.total_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let req = request.into_inner();
+        let cache_key = format!("{}:{}", req.vector_id, req.layer_id);
+
+        let deleted = {
+            let mut lru = self.lock_lru();
+            lru.remove(&cache_key);
+            self.cache.remove(&cache_key).is_some()
+        };
+        if deleted {
+            if self.config.enable_debug_logging {
+                debug!("Deleted key from cache: {}", cache_key);
+            }
+            self.publish(cache_key, CacheOp::Delete);
+        }
+
+        Ok(Response::new(DeleteResponse {
+            deleted,
+            status: if deleted { "OK" } else { "Not Found" }.to_string(),
+        }))
+    }
+
+    async fn get_metrics(
+        &self,
+        _request: Request<MetricsRequest>,
+    ) -> std::result::Result<Response<MetricsResponse>, Status> {
+        let snapshot = self.metrics_snapshot();
+        Ok(Response::new(MetricsResponse {
+            total_requests: snapshot.total_requests,
+            cache_hits: snapshot.cache_hits,
+            cache_misses: snapshot.cache_misses,
+            cache_size: snapshot.cache_size,
+        }))
+    }
+
+    async fn get_mesolimbic_state(
+        &self,
+        _request: Request<MesolimbicStateRequest>,
+    ) -> std::result::Result<Response<MesolimbicState>, Status> {
+        let system = self.salience.read()
+            .map_err(|_| Status::internal("Salience system lock poisoned"))?;
+        let state = system.get_state();
+        Ok(Response::new(MesolimbicState {
+            dopamine_level: state.dopamine_level,
+            attention_focus: state.attention_focus.clone(),
+            reward_prediction: state.reward_prediction,
+            exploration_factor: state.exploration_factor,
+        }))
+    }
+
+    async fn watch_cache(
+        &self,
+        _request: Request<WatchRequest>,
+    ) -> std::result::Result<Response<Self::WatchCacheStream>, Status> {
+        // tonic streams must yield Status errors, however large
+        #[allow(clippy::result_large_err)]
+        let events = BroadcastStream::new(self.events.subscribe()).map(|event| {
+            event.map_err(|BroadcastStreamRecvError::Lagged(missed)| {
+                Status::data_loss(format!("Watcher lagged behind and missed {} cache events", missed))
+            })
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
 }
 
 impl KVQuantService {
@@ -237,32 +413,185 @@ pub fn new(config: Option<KVQuantConfig>) -> Self {
         Self {
             config: config.clone(),
             cache: DashMap::with_capacity(config.max_cache_items.min(1000)),
+            lru: Mutex::new(LruOrder::default()),
+            events: broadcast::channel(CACHE_EVENT_BUFFER).0,
             metrics: ServiceMetrics::default(),
+            salience: Arc::new(RwLock::new(UnifiedSalienceSystem::new(SalienceConfig::default()))),
         }
     }
 
-    /// Runs the KVQuantService gRPC server
-    pub async fn run_service(addr: &str) -> Result<()> {
-        let service = KVQuantService::new(None);
-        let addr: SocketAddr = addr.parse()
-            .map_err(|e| KVQuantError::Config(format!("Invalid address: {}", e)))?;
+    /// Report the state of `system`, shared with whatever computes salience in this process
+    pub fn with_salience_system(mut self, system: Arc<RwLock<UnifiedSalienceSystem>>) -> Self {
+        self.salience = system;
+        self
+    }
+
+    /// The salience system whose state `GetMesolimbicState` reports
+    pub fn salience_system(&self) -> Arc<RwLock<UnifiedSalienceSystem>> {
+        self.salience.clone()
+    }
+
+    /// Creates the gRPC service for `config`, rejecting callers without its bearer token
+    ///
+    /// The service gets its own salience system, so `GetMesolimbicState` reports
+    /// its initial state; use `into_authenticated_server` on a service built with
+    /// `with_salience_system` to report one that is fed elsewhere.
+    pub fn authenticated_server(config: KVQuantConfig) -> AuthenticatedSidecarServer {
+        KVQuantService::new(Some(config)).into_authenticated_server()
+    }
+
+    /// Wraps this service for gRPC, rejecting callers without its configured bearer token
+    pub fn into_authenticated_server(self) -> AuthenticatedSidecarServer {
+        let auth = BearerAuth::new(&self.config.auth);
+        SidecarServiceServer::with_interceptor(self, auth)
+    }
+
+    /// Runs the KVQuantService gRPC server with `config`, including its auth settings
+    ///
+    /// Like `authenticated_server`, this reports a salience system nothing feeds;
+    /// embedders that compute salience should call `serve_with_shutdown` instead.
+    pub async fn run_service(addr: &str, config: KVQuantConfig) -> Result<()> {
+        Self::run_service_with_shutdown(addr, config, std::future::pending()).await
+    }
+
+    /// Runs the KVQuantService gRPC server until `shutdown` resolves
+    ///
+    /// Once it resolves the server stops accepting connections and returns after
+    /// in-flight RPCs have finished. Open `WatchCache` streams count as in flight,
+    /// so their clients must disconnect before shutdown completes.
+    pub async fn run_service_with_shutdown(
+        addr: &str,
+        config: KVQuantConfig,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
+        KVQuantService::new(Some(config)).serve_with_shutdown(addr, shutdown).await
+    }
+
+    /// Serves this service, with its salience system, until `shutdown` resolves
+    ///
+    /// Shutdown drains in-flight RPCs the same way as `run_service_with_shutdown`.
+    pub async fn serve_with_shutdown(
+        self,
+        addr: &str,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
+        let service = self.into_authenticated_server();
+        let addr = parse_addr(addr)?;
         
         info!("Starting KVQuantService on {}", addr);
         
         Server::builder()
-            .add_service(SidecarServiceServer::new(service))
-            .serve(addr)
+            .add_service(service)
+            .serve_with_shutdown(addr, async {
+                shutdown.await;
+                info!("KVQuantService draining in-flight requests");
+            })
             .await
             .map_err(KVQuantError::Transport)?;
         
         info!("KVQuantService shutdown complete");
         Ok(())
     }
+
+    /// Runs the KVQuantService gRPC server over TLS, using a PEM certificate chain and private key
+    #[cfg(feature = "tls")]
+    pub async fn run_service_tls(
+        addr: &str,
+        config: KVQuantConfig,
+        cert_path: &Path,
+        key_path: &Path,
+    ) -> Result<()> {
+        Self::run_service_tls_with_shutdown(addr, config, cert_path, key_path, std::future::pending()).await
+    }
+
+    /// Runs the KVQuantService gRPC server over TLS until `shutdown` resolves
+    ///
+    /// Shutdown drains in-flight RPCs the same way as `run_service_with_shutdown`.
+    #[cfg(feature = "tls")]
+    pub async fn run_service_tls_with_shutdown(
+        addr: &str,
+        config: KVQuantConfig,
+        cert_path: &Path,
+        key_path: &Path,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
+        KVQuantService::new(Some(config))
+            .serve_tls_with_shutdown(addr, cert_path, key_path, shutdown)
+            .await
+    }
+
+    /// Serves this service over TLS, with its salience system, until `shutdown` resolves
+    #[cfg(feature = "tls")]
+    pub async fn serve_tls_with_shutdown(
+        self,
+        addr: &str,
+        cert_path: &Path,
+        key_path: &Path,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
+        use tonic::transport::{Identity, ServerTlsConfig};
+
+        let service = self.into_authenticated_server();
+        let addr = parse_addr(addr)?;
+        let cert = tokio::fs::read(cert_path).await?;
+        let key = tokio::fs::read(key_path).await?;
+
+        info!("Starting KVQuantService with TLS on {}", addr);
+
+        Server::builder()
+            .tls_config(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))?
+            .add_service(service)
+            .serve_with_shutdown(addr, async {
+                shutdown.await;
+                info!("KVQuantService draining in-flight requests");
+            })
+            .await
+            .map_err(KVQuantError::Transport)?;
+
+        info!("KVQuantService shutdown complete");
+        Ok(())
+    }
     
     /// Returns the current cache size
     pub fn cache_size(&self) -> usize {
         self.cache.len()
     }
+
+    /// Records an access to `key` for LRU ordering, unless it was evicted meanwhile
+    fn touch(&self, key: &str) {
+        let mut lru = self.lock_lru();
+        if self.cache.contains_key(key) {
+            lru.touch(key);
+        }
+    }
+
+    fn lock_lru(&self) -> MutexGuard<'_, LruOrder> {
+        // The order holds plain data, so a panic elsewhere can't leave it inconsistent
+        self.lru.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Notifies `WatchCache` subscribers, if any, of a cache change
+    fn publish(&self, vector_id: String, op: CacheOp) {
+        let _ = self.events.send(CacheEvent { vector_id, op: op as i32 });
+    }
+
+    /// Returns the current request counters and cache size
+    pub fn metrics_snapshot(&self) -> ServiceMetricsSnapshot {
+        use std::sync::atomic::Ordering;
+
+        // Requests bump total_requests before their hit or miss counter, so reading
+        // hits and misses first keeps cache_hits + cache_misses <= total_requests
+        let cache_hits = self.metrics.cache_hits.load(Ordering::SeqCst);
+        let cache_misses = self.metrics.cache_misses.load(Ordering::SeqCst);
+        let total_requests = self.metrics.total_requests.load(Ordering::SeqCst);
+
+        ServiceMetricsSnapshot {
+            total_requests,
+            cache_hits,
+            cache_misses,
+            cache_size: self.cache_size() as u64,
+        }
+    }
 }
 
 
@@ -271,6 +600,11 @@ pub fn cache_size(&self) -> usize {
 
 
 
+fn parse_addr(addr: &str) -> Result<SocketAddr> {
+    addr.parse()
+        .map_err(|e| KVQuantError::Config(format!("Invalid address: {}", e)))
+}
+
 /// Configuration for the KVQuant system
 pub fn initialize_kv_cache(config: KVQuantConfig) -> kv_cache::LogStructuredKVCache {
     log::info!("Initializing kvquant-rs with block size: {}, spot capacity: {}", config.block_size, config.spot_capacity);
@@ -0,0 +1,440 @@
+//! Tests for the KVQuant sidecar service
+
+use std::time::Duration;
+
+use kvquant_rs::{
+    AuthConfig,
+    CacheDelete,
+    CacheEvent,
+    CacheOp,
+    CacheRequest,
+    CacheUpdate,
+    KVQuantConfig,
+    KVQuantService,
+    MesolimbicStateRequest,
+    MetricsRequest,
+    SidecarService,
+    SidecarServiceClient,
+    WatchRequest,
+};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use tonic::Request;
+
+/// Serve a fresh `KVQuantService` on an ephemeral port and connect a client to it
+async fn spawn_sidecar() -> SidecarServiceClient<Channel> {
+    spawn_sidecar_with(KVQuantConfig::default()).await
+}
+
+async fn spawn_sidecar_with(config: KVQuantConfig) -> SidecarServiceClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(KVQuantService::authenticated_server(config))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    SidecarServiceClient::connect(endpoint).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_get_metrics_counts_requests() {
+    let mut client = spawn_sidecar().await;
+
+    let metrics = client.get_metrics(MetricsRequest {}).await.unwrap().into_inner();
+    assert_eq!(metrics.total_requests, 0);
+    assert_eq!(metrics.cache_size, 0);
+
+    client.update_cache(CacheUpdate {
+        vector_id: "v1:0".to_string(),
+        data: vec![7; 16],
+    }).await.unwrap();
+
+    for layer_id in ["0", "0", "1"] {
+        client.get_cached_data(CacheRequest {
+            vector_id: "v1".to_string(),
+            layer_id: layer_id.to_string(),
+        }).await.unwrap();
+    }
+
+    let metrics = client.get_metrics(MetricsRequest {}).await.unwrap().into_inner();
+    assert_eq!(metrics.total_requests, 4);
+    assert_eq!(metrics.cache_hits, 2);
+    assert_eq!(metrics.cache_misses, 1);
+    assert_eq!(metrics.cache_size, 1);
+}
+
+async fn put(service: &KVQuantService, key: &str) {
+    service.update_cache(Request::new(CacheUpdate {
+        vector_id: format!("{}:0", key),
+        data: key.as_bytes().to_vec(),
+    })).await.unwrap();
+}
+
+async fn lookup(service: &KVQuantService, key: &str) -> String {
+    service.get_cached_data(Request::new(CacheRequest {
+        vector_id: key.to_string(),
+        layer_id: "0".to_string(),
+    })).await.unwrap().into_inner().status
+}
+
+#[tokio::test]
+async fn test_update_cache_evicts_least_recently_used() {
+    let service = KVQuantService::new(Some(KVQuantConfig {
+        max_cache_items: 3,
+        ..KVQuantConfig::default()
+    }));
+
+    for key in ["a", "b", "c"] {
+        put(&service, key).await;
+    }
+    // Re-touch the oldest key so "b" becomes the eviction victim
+    assert_eq!(lookup(&service, "a").await, "OK");
+    put(&service, "d").await;
+
+    assert_eq!(service.cache_size(), 3);
+    assert_eq!(lookup(&service, "a").await, "OK");
+    assert_eq!(lookup(&service, "b").await, "Not Found");
+    assert_eq!(lookup(&service, "c").await, "OK");
+    assert_eq!(lookup(&service, "d").await, "OK");
+
+    // Overwriting a cached key at capacity evicts nothing
+    put(&service, "c").await;
+    assert_eq!(service.cache_size(), 3);
+    assert_eq!(lookup(&service, "a").await, "OK");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_updates_at_capacity_evict_one_each() {
+    let service = std::sync::Arc::new(KVQuantService::new(Some(KVQuantConfig {
+        max_cache_items: 8,
+        ..KVQuantConfig::default()
+    })));
+    for i in 0..8 {
+        put(&service, &format!("seed{}", i)).await;
+    }
+
+    let writers: Vec<_> = (0..64)
+        .map(|i| {
+            let service = std::sync::Arc::clone(&service);
+            tokio::spawn(async move { put(&service, &format!("new{}", i)).await })
+        })
+        .collect();
+    for writer in writers {
+        writer.await.unwrap();
+    }
+
+    // Every insert evicted exactly one entry, so the cache is still full
+    assert_eq!(service.cache_size(), 8);
+    assert_eq!(lookup(&service, "seed0").await, "Not Found");
+}
+
+#[tokio::test]
+async fn test_watch_cache_streams_inserts_and_evictions() {
+    let mut client = spawn_sidecar_with(KVQuantConfig {
+        max_cache_items: 1,
+        ..KVQuantConfig::default()
+    }).await;
+    let mut events = client.watch_cache(WatchRequest {}).await.unwrap().into_inner();
+
+    for key in ["a:0", "b:0"] {
+        client.update_cache(CacheUpdate {
+            vector_id: key.to_string(),
+            data: vec![1],
+        }).await.unwrap();
+    }
+
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let event: CacheEvent = tokio::time::timeout(Duration::from_secs(5), events.message())
+            .await
+            .expect("timed out waiting for cache event")
+            .unwrap()
+            .unwrap();
+        received.push((event.vector_id.clone(), event.op()));
+    }
+
+    assert_eq!(received, vec![
+        ("a:0".to_string(), CacheOp::Insert),
+        ("a:0".to_string(), CacheOp::Evict),
+        ("b:0".to_string(), CacheOp::Insert),
+    ]);
+}
+
+#[tokio::test]
+async fn test_delete_cache_removes_entry() {
+    let mut client = spawn_sidecar().await;
+    client.update_cache(CacheUpdate {
+        vector_id: "v1:2".to_string(),
+        data: vec![9; 4],
+    }).await.unwrap();
+
+    let delete = CacheDelete { vector_id: "v1".to_string(), layer_id: "2".to_string() };
+    let response = client.delete_cache(delete.clone()).await.unwrap().into_inner();
+    assert!(response.deleted);
+    assert_eq!(response.status, "OK");
+
+    let lookup = client.get_cached_data(CacheRequest {
+        vector_id: "v1".to_string(),
+        layer_id: "2".to_string(),
+    }).await.unwrap().into_inner();
+    assert_eq!(lookup.status, "Not Found");
+
+    let response = client.delete_cache(delete).await.unwrap().into_inner();
+    assert!(!response.deleted);
+    assert_eq!(response.status, "Not Found");
+
+    let metrics = client.get_metrics(MetricsRequest {}).await.unwrap().into_inner();
+    assert_eq!(metrics.cache_size, 0);
+    assert_eq!(metrics.total_requests, 4);
+}
+
+#[tokio::test]
+async fn test_get_mesolimbic_state_reports_live_salience() {
+    let service = KVQuantService::new(None);
+    let salience = service.salience_system();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(kvquant_rs::SidecarServiceServer::new(service))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let mut client = SidecarServiceClient::connect(endpoint).await.unwrap();
+
+    let initial = client.get_mesolimbic_state(MesolimbicStateRequest {}).await.unwrap().into_inner();
+    assert_eq!(initial.dopamine_level, 0.5);
+    assert!(initial.attention_focus.is_empty());
+
+    // Novel tokens score above 0.5, so each batch nudges dopamine up
+    let expected = {
+        let mut system = salience.write().unwrap();
+        for _ in 0..5 {
+            system.compute_salience(&[1, 2, 3, 4]).unwrap();
+        }
+        system.get_state().clone()
+    };
+
+    let live = client.get_mesolimbic_state(MesolimbicStateRequest {}).await.unwrap().into_inner();
+    assert_ne!(live.dopamine_level, initial.dopamine_level);
+    assert_eq!(live.dopamine_level, expected.dopamine_level);
+    assert_eq!(live.attention_focus, expected.attention_focus);
+    assert_eq!(live.reward_prediction, expected.reward_prediction);
+    assert_eq!(live.exploration_factor, expected.exploration_factor);
+}
+
+#[tokio::test]
+async fn test_run_service_with_shutdown_drains_and_exits() {
+    // Reserve a free port, then hand it to the server
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        KVQuantService::run_service_with_shutdown(&addr.to_string(), KVQuantConfig::default(), async {
+            let _ = shutdown_rx.await;
+        }).await
+    });
+
+    let endpoint = format!("http://{}", addr);
+    let mut client = None;
+    for _ in 0..50 {
+        match SidecarServiceClient::connect(endpoint.clone()).await {
+            Ok(connected) => {
+                client = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut client = client.expect("sidecar never came up");
+
+    // Trigger shutdown while the update is being sent; it must still complete
+    let (response, _) = tokio::join!(
+        client.update_cache(CacheUpdate {
+            vector_id: "draining:0".to_string(),
+            data: vec![1, 2, 3],
+        }),
+        async { shutdown_tx.send(()).unwrap() },
+    );
+    assert_eq!(response.unwrap().into_inner().status, "OK");
+
+    // The idle client connection must not hold the server open
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server did not stop after shutdown")
+        .unwrap()
+        .unwrap();
+    drop(client);
+    assert!(SidecarServiceClient::connect(endpoint).await.is_err());
+}
+
+#[cfg(feature = "tls")]
+#[tokio::test]
+async fn test_run_service_tls_accepts_trusted_client_and_shuts_down() {
+    use tonic::transport::{Certificate, ClientTlsConfig, Endpoint};
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("sidecar.pem");
+    let key_path = dir.path().join("sidecar.key");
+    let cert_pem = cert.serialize_pem().unwrap();
+    std::fs::write(&cert_path, &cert_pem).unwrap();
+    std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+
+    // Reserve a free port, then hand it to the server
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        let config = KVQuantConfig::default();
+        KVQuantService::run_service_tls_with_shutdown(&addr.to_string(), config, &cert_path, &key_path, async {
+            let _ = shutdown_rx.await;
+        }).await
+    });
+
+    let endpoint = Endpoint::from_shared(format!("https://localhost:{}", addr.port()))
+        .unwrap()
+        .tls_config(ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(&cert_pem))
+            .domain_name("localhost"))
+        .unwrap();
+
+    let mut channel = None;
+    for _ in 0..50 {
+        match endpoint.connect().await {
+            Ok(connected) => {
+                channel = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut client = SidecarServiceClient::new(channel.expect("TLS sidecar never came up"));
+
+    let response = client.update_cache(CacheUpdate {
+        vector_id: "secure:0".to_string(),
+        data: vec![1, 2, 3],
+    }).await.unwrap().into_inner();
+    assert_eq!(response.status, "OK");
+
+    drop(client);
+    shutdown_tx.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("TLS server did not stop after shutdown")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_bearer_token_required_when_configured() {
+    let mut client = spawn_sidecar_with(KVQuantConfig {
+        auth: AuthConfig { bearer_token: Some("s3cret".to_string()) },
+        ..KVQuantConfig::default()
+    }).await;
+
+    let with_token = |token: Option<&str>| {
+        let mut request = Request::new(MetricsRequest {});
+        if let Some(token) = token {
+            request.metadata_mut().insert("authorization", token.parse().unwrap());
+        }
+        request
+    };
+
+    assert!(client.get_metrics(with_token(Some("Bearer s3cret"))).await.is_ok());
+
+    for token in [None, Some("Bearer wrong"), Some("s3cret")] {
+        let status = client.get_metrics(with_token(token)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated, "token {:?}", token);
+    }
+}
+
+#[tokio::test]
+async fn test_run_service_enforces_configured_bearer_token() {
+    // Reserve a free port, then hand it to the server
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let config = KVQuantConfig {
+        auth: AuthConfig { bearer_token: Some("s3cret".to_string()) },
+        ..KVQuantConfig::default()
+    };
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        KVQuantService::run_service_with_shutdown(&addr.to_string(), config, async {
+            let _ = shutdown_rx.await;
+        }).await
+    });
+
+    let endpoint = format!("http://{}", addr);
+    let mut client = None;
+    for _ in 0..50 {
+        match SidecarServiceClient::connect(endpoint.clone()).await {
+            Ok(connected) => {
+                client = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut client = client.expect("sidecar never came up");
+
+    let status = client.get_metrics(MetricsRequest {}).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    let mut request = Request::new(MetricsRequest {});
+    request.metadata_mut().insert("authorization", "Bearer s3cret".parse().unwrap());
+    assert!(client.get_metrics(request).await.is_ok());
+
+    drop(client);
+    shutdown_tx.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server did not stop after shutdown")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_serve_with_shutdown_reports_shared_salience_system() {
+    let salience = KVQuantService::new(None).salience_system();
+    let service = KVQuantService::new(None).with_salience_system(salience.clone());
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        service.serve_with_shutdown(&addr.to_string(), async {
+            let _ = shutdown_rx.await;
+        }).await
+    });
+
+    let endpoint = format!("http://{}", addr);
+    let mut client = None;
+    for _ in 0..50 {
+        match SidecarServiceClient::connect(endpoint.clone()).await {
+            Ok(connected) => {
+                client = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut client = client.expect("sidecar never came up");
+
+    // Salience computed by the embedder shows up through the running server
+    let expected = {
+        let mut system = salience.write().unwrap();
+        system.compute_salience(&[5, 6, 7]).unwrap();
+        system.get_state().clone()
+    };
+    let live = client.get_mesolimbic_state(MesolimbicStateRequest {}).await.unwrap().into_inner();
+    assert_ne!(live.dopamine_level, 0.5);
+    assert_eq!(live.dopamine_level, expected.dopamine_level);
+    assert_eq!(live.attention_focus, expected.attention_focus);
+
+    drop(client);
+    shutdown_tx.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server did not stop after shutdown")
+        .unwrap()
+        .unwrap();
+}
@@ -13,6 +13,9 @@
 // limitations under the License.
 
 pub mod kv_cache;
+pub mod tokenizer;
+#[cfg(feature = "grpc")]
+pub mod sidecar;
 mod metrics;
 mod utils;
 
@@ -28,6 +31,11 @@
 use tokio::io::{AsyncReadExt, BufReader};
 use std::io::{Error as IoError, ErrorKind};
 use std::path::PathBuf;
+use thiserror::Error;
+use tokenizer::{ByteTokenizer, Tokenizer};
+
+/// Maximum number of tokens a single `infer` call generates
+pub const MAX_NEW_TOKENS: usize = 16;
 
 pub fn get_default_inference_config() -> InferenceConfig {
     InferenceConfig {
@@ -56,21 +64,47 @@ pub struct InferenceOutput {
     pub latency_ms: f64,
 }
 
+#[derive(Error, Debug)]
+pub enum InferenceError {
+    #[error("Neuron matrix not initialized")]
+    NeuronMatrixNotInitialized,
+    #[error("Neuron matrix lock poisoned: {0}")]
+    LockPoisoned(String),
+    #[error("d_model must be at least 1")]
+    ZeroDModel,
+}
+
 pub struct InferenceEngine {
     d_model: usize,
     weights: Vec<u8>,
     neuron_matrix: Option<Arc<RwLock<Array2<f16>>>>,
     num_used: usize,
+    tokenizer: Arc<dyn Tokenizer>,
 }
 
 impl InferenceEngine {
-    pub async fn new(d_model: usize) -> Self {
-        InferenceEngine {
+    /// Fails for a `d_model` of 0, which leaves no hidden state to score tokens with
+    pub async fn new(d_model: usize) -> Result<Self, InferenceError> {
+        if d_model == 0 {
+            return Err(InferenceError::ZeroDModel);
+        }
+        Ok(InferenceEngine {
             d_model,
             weights: Vec::new(),
             neuron_matrix: None,
             num_used: 0,
-        }
+            tokenizer: Arc::new(ByteTokenizer),
+        })
+    }
+
+    /// Use `tokenizer` to encode prompts and decode generated tokens
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    pub fn tokenizer(&self) -> &dyn Tokenizer {
+        self.tokenizer.as_ref()
     }
 
     pub async fn load_from_flash(&mut self, path: &str, is_enterprise: bool) -> Result<(), IoError> {
@@ -95,33 +129,73 @@ pub async fn infer(
         input: &str,
         routing_plan: &NSRoutingPlan,
         weights: &[u8],
-    ) -> InferenceOutput {
+    ) -> Result<InferenceOutput, InferenceError> {
         let start = Instant::now();
-        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let mut tokens = self.tokenizer.encode(input);
         let tokens_processed = tokens.len();
 
-        let matrix_lock = self.neuron_matrix.as_ref().unwrap();
-        let matrix = matrix_lock.read().unwrap();
+        let matrix_lock = self
+            .neuron_matrix
+            .as_ref()
+            .ok_or(InferenceError::NeuronMatrixNotInitialized)?;
+        let matrix = matrix_lock
+            .read()
+            .map_err(|e| InferenceError::LockPoisoned(e.to_string()))?;
         let up_project = matrix.slice(s![..self.num_used, ..self.d_model]);
-        let down_project = matrix.slice(s![..self.num_used, self.d_model..]).reversed_axes();
-
-        let attention_scores: Vec<f16> = (0..self.num_used)
-            .into_iter()
-            .map(|i| {
-                (0..self.d_model)
-                    .map(|d| up_project[[i, d]] * down_project[[i, d]])
-                    .fold(f16::from_f32(0.0), |acc, val| acc + val)
-            })
-            .collect();
-
-        let text = format!("Processed: {}", input);
+        let down_project = matrix.slice(s![..self.num_used, self.d_model..]);
+
+        // Greedy decoding: feed each generated token back into the context
+        let mut generated = Vec::with_capacity(MAX_NEW_TOKENS);
+        for _ in 0..MAX_NEW_TOKENS {
+            let logits = self.logits(&tokens, up_project, down_project);
+            let Some(next) = argmax(&logits) else { break };
+            tokens.push(next);
+            generated.push(next);
+        }
+
+        let text = self.tokenizer.decode(&generated);
         let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
 
-        InferenceOutput {
+        Ok(InferenceOutput {
             text,
             tokens_processed,
             latency_ms,
+        })
+    }
+
+    /// Score every vocabulary entry as the next token after `tokens`
+    ///
+    /// The context is embedded as a bag of tokens, passed through the active
+    /// neurons (ReLU of the up projection, scattered back through the down
+    /// projection) with a residual connection, and read out per token id.
+    fn logits(&self, tokens: &[u32], up_project: ArrayView2<f16>, down_project: ArrayView2<f16>) -> Vec<f32> {
+        let mut hidden = vec![0.0f32; self.d_model];
+        for &token in tokens {
+            hidden[token as usize % self.d_model] += 1.0;
+        }
+        if !tokens.is_empty() {
+            let scale = 1.0 / tokens.len() as f32;
+            hidden.iter_mut().for_each(|h| *h *= scale);
         }
+
+        let mut output = hidden.clone();
+        for (up, down) in up_project.outer_iter().zip(down_project.outer_iter()) {
+            let activation = up
+                .iter()
+                .zip(&hidden)
+                .map(|(w, h)| w.to_f32() * h)
+                .sum::<f32>()
+                .max(0.0);
+            if activation > 0.0 {
+                for (o, w) in output.iter_mut().zip(down.iter()) {
+                    *o += activation * w.to_f32();
+                }
+            }
+        }
+
+        (0..self.tokenizer.vocab_size())
+            .map(|id| output[id % self.d_model])
+            .collect()
     }
 
     pub async fn update_neuron_matrix(&mut self, new_neurons: Vec<(usize, Array2<f16>, f32)>) {
@@ -155,6 +229,18 @@ pub async fn update_neuron_matrix(&mut self, new_neurons: Vec<(usize, Array2<f16
     }
 }
 
+/// Index of the highest score, preferring the lowest index on ties
+fn argmax(values: &[f32]) -> Option<u32> {
+    values
+        .iter()
+        .enumerate()
+        .fold(None, |best: Option<(usize, f32)>, (i, &v)| match best {
+            Some((_, b)) if v.total_cmp(&b).is_le() => best,
+            _ => Some((i, v)),
+        })
+        .map(|(i, _)| i as u32)
+}
+
 pub mod mesolimbic_system {
     use serde::{Deserialize, Serialize};
 
@@ -252,28 +338,6 @@ pub enum PrecisionLevel {
     }
 }
 
-#[cfg(feature = "grpc")]
-mod pb {
-    pub mod sidecar_service_client {
-        use tonic::transport::Channel;
-
-        #[derive(Clone)]
-        pub struct SidecarServiceClient<T> {
-            inner: T,
-        }
-
-        impl SidecarServiceClient<Channel> {
-            pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-            where
-                D: Into<String>,
-            {
-                let inner = Channel::from_shared(dst.into())?.connect().await?;
-                Ok(SidecarServiceClient { inner })
-            }
-        }
-    }
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tableau {
     pub data: Vec<u8>,
@@ -284,3 +348,57 @@ fn default() -> Self {
         Tableau { data: vec![] }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_routing_plan() -> NSRoutingPlan {
+        NSRoutingPlan {
+            model_config: ns_router_rs::ModelConfig { precision: vec![] },
+            kv_cache_config: ns_router_rs::KVCacheConfig {
+                sparsity: 0.0,
+                priority_tokens: vec![],
+            },
+        }
+    }
+
+    async fn test_engine(d_model: usize, neurons: usize) -> InferenceEngine {
+        let mut engine = InferenceEngine::new(d_model).await.unwrap();
+        let matrix = Array2::from_shape_fn((neurons, 2 * d_model), |(i, j)| {
+            f16::from_f32(((i * 7 + j * 3) % 5) as f32 * 0.1 - 0.2)
+        });
+        engine.neuron_matrix = Some(Arc::new(RwLock::new(matrix)));
+        engine.num_used = neurons;
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_infer_generates_from_tokenizer_and_scores() {
+        let engine = test_engine(256, 4).await;
+        let plan = test_routing_plan();
+
+        let first = engine.infer("hello world", &plan, &[]).await.unwrap();
+        let second = engine.infer("zzz yyy", &plan, &[]).await.unwrap();
+
+        assert_eq!(first.tokens_processed, engine.tokenizer().encode("hello world").len());
+        assert_eq!(second.tokens_processed, engine.tokenizer().encode("zzz yyy").len());
+        assert!(!first.text.is_empty());
+        assert_ne!(first.text, second.text);
+        assert!(!first.text.starts_with("Processed:"));
+    }
+
+    #[tokio::test]
+    async fn test_infer_without_neuron_matrix_returns_error() {
+        let mut engine = InferenceEngine::new(16).await.unwrap();
+        engine.load_weights(vec![0u8; 64]).await;
+
+        let result = engine.infer("hello", &test_routing_plan(), &[]).await;
+        assert!(matches!(result, Err(InferenceError::NeuronMatrixNotInitialized)));
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_zero_d_model() {
+        assert!(matches!(InferenceEngine::new(0).await, Err(InferenceError::ZeroDModel)));
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2025 ZETA RETICULA INC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sidecar client that reconnects after transient transport failures
+
+use std::future::Future;
+use std::time::Duration;
+
+use kvquant_rs::{
+    CacheDelete, CacheRequest, CacheResponse, CacheUpdate, DeleteResponse, MetricsRequest,
+    MetricsResponse, SidecarServiceClient, UpdateResponse,
+};
+use thiserror::Error;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Response, Status};
+
+#[derive(Error, Debug)]
+pub enum SidecarClientError {
+    #[error("Invalid sidecar endpoint: {0}")]
+    InvalidEndpoint(String),
+    #[error("Sidecar unreachable after {attempts} attempts: {last_error}")]
+    Unavailable { attempts: u32, last_error: String },
+    #[error("Sidecar request failed: {0}")]
+    Rpc(Box<Status>),
+}
+
+impl From<Status> for SidecarClientError {
+    fn from(status: Status) -> Self {
+        SidecarClientError::Rpc(Box::new(status))
+    }
+}
+
+/// Exponential backoff between reconnection attempts
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// Delay before the first retry; doubled for each retry after that
+    pub initial: Duration,
+    /// Upper bound on any single delay
+    pub max: Duration,
+    /// Total attempts, including the first, before giving up
+    pub max_attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(5),
+            max_attempts: 6,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before retry number `retry` (0-based)
+    fn delay(&self, retry: u32) -> Duration {
+        self.initial
+            .checked_mul(1u32.checked_shl(retry).unwrap_or(u32::MAX))
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+/// Wrapper around `SidecarServiceClient` that survives dropped connections
+///
+/// The connection is made lazily. When an RPC fails at the transport level the
+/// connection is discarded. Idempotent reads (`get_cached_data`, `get_metrics`)
+/// then reconnect with exponential backoff and retry. Writes are sent once,
+/// because a lost response doesn't mean the write wasn't applied; the next call
+/// reconnects.
+pub struct ReconnectingSidecarClient {
+    endpoint: Endpoint,
+    client: Option<SidecarServiceClient<Channel>>,
+    backoff: Backoff,
+}
+
+impl ReconnectingSidecarClient {
+    /// Create a client for `dst`, e.g. `http://localhost:50051`
+    pub fn new(dst: impl Into<String>) -> Result<Self, SidecarClientError> {
+        let endpoint = Endpoint::from_shared(dst.into())
+            .map_err(|e| SidecarClientError::InvalidEndpoint(e.to_string()))?;
+        Ok(Self {
+            endpoint,
+            client: None,
+            backoff: Backoff::default(),
+        })
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub async fn get_cached_data(&mut self, request: CacheRequest) -> Result<CacheResponse, SidecarClientError> {
+        self.call_with_retry(|mut client| {
+            let request = request.clone();
+            async move { client.get_cached_data(request).await }
+        })
+        .await
+    }
+
+    pub async fn get_metrics(&mut self) -> Result<MetricsResponse, SidecarClientError> {
+        self.call_with_retry(|mut client| async move { client.get_metrics(MetricsRequest {}).await })
+            .await
+    }
+
+    pub async fn update_cache(&mut self, update: CacheUpdate) -> Result<UpdateResponse, SidecarClientError> {
+        self.call_once(|mut client| async move { client.update_cache(update).await })
+            .await
+    }
+
+    pub async fn delete_cache(&mut self, delete: CacheDelete) -> Result<DeleteResponse, SidecarClientError> {
+        self.call_once(|mut client| async move { client.delete_cache(delete).await })
+            .await
+    }
+
+    /// The current client, connecting first if there is none
+    async fn connected(&mut self) -> Result<SidecarServiceClient<Channel>, tonic::transport::Error> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let client = SidecarServiceClient::new(self.endpoint.connect().await?);
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+
+    async fn call_with_retry<T, F, Fut>(&mut self, mut call: F) -> Result<T, SidecarClientError>
+    where
+        F: FnMut(SidecarServiceClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let last_error = match self.connected().await {
+                Ok(client) => match call(client).await {
+                    Ok(response) => return Ok(response.into_inner()),
+                    Err(status) if is_transport_failure(&status) => {
+                        self.client = None;
+                        status.to_string()
+                    }
+                    Err(status) => return Err(status.into()),
+                },
+                Err(e) => e.to_string(),
+            };
+
+            if attempts >= self.backoff.max_attempts {
+                return Err(SidecarClientError::Unavailable { attempts, last_error });
+            }
+            let delay = self.backoff.delay(attempts - 1);
+            tracing::warn!("Sidecar call failed ({}), reconnecting in {:?}", last_error, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn call_once<T, F, Fut>(&mut self, call: F) -> Result<T, SidecarClientError>
+    where
+        F: FnOnce(SidecarServiceClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        let client = self.connected().await.map_err(|e| SidecarClientError::Unavailable {
+            attempts: 1,
+            last_error: e.to_string(),
+        })?;
+        match call(client).await {
+            Ok(response) => Ok(response.into_inner()),
+            Err(status) => {
+                if is_transport_failure(&status) {
+                    self.client = None;
+                }
+                Err(status.into())
+            }
+        }
+    }
+}
+
+/// Whether `status` means the connection failed rather than the server rejecting the call
+fn is_transport_failure(status: &Status) -> bool {
+    match status.code() {
+        Code::Unavailable => true,
+        // tonic reports a connection torn down mid-call as Unknown with a transport source
+        Code::Unknown => std::error::Error::source(status)
+            .is_some_and(|source| source.is::<tonic::transport::Error>()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use kvquant_rs::{KVQuantService, SidecarService, SidecarServiceServer};
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::Server;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(50),
+            max_attempts: 10,
+        };
+        let delays: Vec<u128> = (0..5).map(|retry| backoff.delay(retry).as_millis()).collect();
+        assert_eq!(delays, vec![10, 20, 40, 50, 50]);
+        assert_eq!(backoff.delay(40), Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_dropped_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let accepted = dropped.clone();
+        tokio::spawn(async move {
+            // Hang up on the first connection once the request is in flight, then serve normally
+            let (first, _) = listener.accept().await.unwrap();
+            accepted.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(first);
+
+            let service = KVQuantService::new(None);
+            service.update_cache(tonic::Request::new(CacheUpdate {
+                vector_id: "v1:0".to_string(),
+                data: vec![4, 2],
+            })).await.unwrap();
+            Server::builder()
+                .add_service(SidecarServiceServer::new(service))
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+
+        let mut client = ReconnectingSidecarClient::new(endpoint).unwrap().with_backoff(Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(100),
+            max_attempts: 5,
+        });
+        let response = client.get_cached_data(CacheRequest {
+            vector_id: "v1".to_string(),
+            layer_id: "0".to_string(),
+        }).await.unwrap();
+
+        assert_eq!(response.status, "OK");
+        assert_eq!(response.data, vec![4, 2]);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        // Reserve a port and close it so every connection is refused
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut client = ReconnectingSidecarClient::new(format!("http://{}", addr)).unwrap().with_backoff(Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(5),
+            max_attempts: 3,
+        });
+
+        let result = client.get_metrics().await;
+        assert!(matches!(result, Err(SidecarClientError::Unavailable { attempts: 3, .. })), "{:?}", result);
+    }
+}
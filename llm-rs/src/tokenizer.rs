@@ -0,0 +1,62 @@
+// Copyright 2025 ZETA RETICULA INC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tokenizers used by the inference engine
+
+/// Converts between text and token ids
+pub trait Tokenizer: Send + Sync {
+    /// Split `text` into token ids
+    fn encode(&self, text: &str) -> Vec<u32>;
+
+    /// Turn token ids back into text
+    fn decode(&self, ids: &[u32]) -> String;
+
+    /// Number of distinct token ids this tokenizer can produce
+    fn vocab_size(&self) -> usize;
+}
+
+/// Byte-level tokenizer: every UTF-8 byte is one token
+///
+/// Needs no vocabulary file, so it is the default for engines that
+/// haven't been given a model-specific tokenizer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteTokenizer;
+
+impl Tokenizer for ByteTokenizer {
+    fn encode(&self, text: &str) -> Vec<u32> {
+        text.bytes().map(u32::from).collect()
+    }
+
+    fn decode(&self, ids: &[u32]) -> String {
+        let bytes: Vec<u8> = ids.iter().filter_map(|&id| u8::try_from(id).ok()).collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn vocab_size(&self) -> usize {
+        256
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_tokenizer_round_trip() {
+        let tokenizer = ByteTokenizer;
+        let ids = tokenizer.encode("héllo");
+        assert_eq!(ids.len(), "héllo".len());
+        assert_eq!(tokenizer.decode(&ids), "héllo");
+    }
+}
@@ -15,12 +15,18 @@
 //! Master service for Zeta Reticula's distributed AI system
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::pin::Pin;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
 use uuid::Uuid;
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tonic::{transport::Server, Request, Response, Status};
 
@@ -28,6 +34,10 @@ pub mod proto {
     tonic::include_proto!("master");
 }
 
+mod persistence;
+
+use persistence::RegistryPersistence;
+
 use proto::{
     master_service_server::{MasterService as MasterServiceTrait, MasterServiceServer},
     *,
@@ -43,13 +53,67 @@ pub enum MasterServiceError {
     RpcError(#[from] Status),
     #[error("Transport error: {0}")]
     TransportError(#[from] tonic::transport::Error),
+    #[error("Persistence error: {0}")]
+    PersistenceError(String),
+}
+
+/// Metadata key holding a node's available GPU memory in megabytes
+pub const GPU_MEMORY_KEY: &str = "gpu_memory_mb";
+/// Metadata key holding a comma-separated list of models loaded on a node
+pub const MODEL_LOADED_KEY: &str = "model_loaded";
+/// Metadata or heartbeat metric key holding a node's current load; lower is less busy
+pub const LOAD_KEY: &str = "load";
+
+/// Node events buffered per subscriber before it is considered lagged
+const NODE_EVENT_BUFFER: usize = 1024;
+
+/// Resource constraints a node must satisfy to be selected
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeRequirements {
+    /// Minimum GPU memory the node must advertise
+    pub min_gpu_memory_mb: Option<u64>,
+    /// Model the node must already have loaded
+    pub model_loaded: Option<String>,
+}
+
+impl NodeRequirements {
+    fn is_satisfied_by(&self, metadata: &HashMap<String, String>) -> bool {
+        if let Some(min_memory) = self.min_gpu_memory_mb {
+            let has_memory = metadata.get(GPU_MEMORY_KEY)
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .is_some_and(|memory| memory >= min_memory);
+            if !has_memory {
+                return false;
+            }
+        }
+        if let Some(model) = &self.model_loaded {
+            let loaded = metadata.get(MODEL_LOADED_KEY)
+                .is_some_and(|models| models.split(',').any(|m| m.trim() == model));
+            if !loaded {
+                return false;
+            }
+        }
+        true
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Prefers the heartbeat-reported load over the static metadata value; nodes that
+/// report neither sort after every node that does
+fn node_load(node: &NodeInfo) -> f64 {
+    node.metrics.get(LOAD_KEY)
+        .copied()
+        .or_else(|| node.metadata.get(LOAD_KEY).and_then(|v| v.trim().parse::<f64>().ok()))
+        .filter(|load| !load.is_nan())
+        .unwrap_or(f64::INFINITY)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct NodeInfo {
     id: String,
     last_seen: SystemTime,
     metadata: HashMap<String, String>,
+    #[serde(default)]
+    metrics: HashMap<String, f64>,
 }
 
 /// Main master service implementation
@@ -57,6 +121,8 @@ struct NodeInfo {
 pub struct MasterService {
     nodes: Arc<RwLock<HashMap<String, NodeInfo>>>,
     shutdown_tx: Option<mpsc::Sender<()>>,
+    persistence: Option<Arc<RegistryPersistence>>,
+    events: broadcast::Sender<NodeEvent>,
 }
 
 impl Default for MasterService {
@@ -71,9 +137,45 @@ pub fn new() -> Self {
         MasterService {
             nodes: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: None,
+            persistence: None,
+            events: broadcast::channel(NODE_EVENT_BUFFER).0,
         }
     }
 
+    /// Create a master service whose node registry is persisted to `path`
+    ///
+    /// Nodes from a previous run are restored with a fresh `last_seen`, giving each
+    /// one a full timeout window to resume heartbeating before it is cleaned up.
+    pub fn with_persistence(path: impl AsRef<Path>) -> Result<Self, MasterServiceError> {
+        let path = path.as_ref();
+        let mut nodes = RegistryPersistence::load(path)?;
+        let now = SystemTime::now();
+        for node in nodes.values_mut() {
+            node.last_seen = now;
+        }
+
+        let nodes = Arc::new(RwLock::new(nodes));
+        let persistence = RegistryPersistence::new(path, nodes.clone());
+        Ok(MasterService {
+            nodes,
+            shutdown_tx: None,
+            persistence: Some(Arc::new(persistence)),
+            events: broadcast::channel(NODE_EVENT_BUFFER).0,
+        })
+    }
+
+    /// Schedule a write of the registry, if persistence is enabled
+    fn persist(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.mark_dirty();
+        }
+    }
+
+    /// Notify subscribers of a membership change; having none is not an error
+    fn publish(&self, node_id: &str, kind: NodeEventKind) {
+        let _ = self.events.send(NodeEvent { kind: kind as i32, node_id: node_id.to_string() });
+    }
+
     /// Register a node with the master service
     pub fn register_node(&self, id: &str, metadata: HashMap<String, String>) -> Result<(), MasterServiceError> {
         let mut nodes = self.nodes.write().map_err(|e| {
@@ -84,9 +186,28 @@ pub fn register_node(&self, id: &str, metadata: HashMap<String, String>) -> Resu
             id: id.to_string(),
             last_seen: SystemTime::now(),
             metadata,
+            metrics: HashMap::new(),
         };
         
         nodes.insert(id.to_string(), node);
+        drop(nodes);
+        self.persist();
+        self.publish(id, NodeEventKind::Joined);
+        Ok(())
+    }
+
+    /// Record a heartbeat, merging `metrics` into the node's latest readings
+    pub fn heartbeat_node(&self, id: &str, metrics: HashMap<String, f64>) -> Result<(), MasterServiceError> {
+        let mut nodes = self.nodes.write().map_err(|e| {
+            MasterServiceError::ServiceError(format!("Failed to acquire write lock: {}", e))
+        })?;
+
+        let node = nodes.get_mut(id)
+            .ok_or_else(|| MasterServiceError::NodeNotFound(id.to_string()))?;
+        node.last_seen = SystemTime::now();
+        node.metrics.extend(metrics);
+        drop(nodes);
+        self.persist();
         Ok(())
     }
 
@@ -96,7 +217,12 @@ pub fn remove_node(&self, id: &str) -> Result<(), MasterServiceError> {
             MasterServiceError::ServiceError(format!("Failed to acquire write lock: {}", e))
         })?;
         
-        nodes.remove(id);
+        let removed = nodes.remove(id).is_some();
+        drop(nodes);
+        if removed {
+            self.persist();
+            self.publish(id, NodeEventKind::Left);
+        }
         Ok(())
     }
 
@@ -109,6 +235,21 @@ pub fn get_nodes(&self) -> Result<Vec<NodeInfo>, MasterServiceError> {
         Ok(nodes.values().cloned().collect())
     }
 
+    /// Select the least-loaded node that satisfies `requirements`
+    ///
+    /// Ties are broken by node ID so the choice is deterministic.
+    pub fn select_node(&self, requirements: &NodeRequirements) -> Option<String> {
+        let nodes = self.nodes.read().ok()?;
+
+        nodes.values()
+            .filter(|node| requirements.is_satisfied_by(&node.metadata))
+            .map(|node| (node_load(node), &node.id))
+            .min_by(|(a_load, a_id), (b_load, b_id)| {
+                a_load.total_cmp(b_load).then_with(|| a_id.cmp(b_id))
+            })
+            .map(|(_, id)| id.clone())
+    }
+
     /// Clean up stale nodes that haven't sent a heartbeat in the specified duration
     pub async fn cleanup_stale_nodes(&self, max_age_seconds: u64) -> Result<usize, MasterServiceError> {
         let mut nodes = self.nodes.write().map_err(|e| {
@@ -117,13 +258,24 @@ pub async fn cleanup_stale_nodes(&self, max_age_seconds: u64) -> Result<usize, M
         
         let now = SystemTime::now();
         let max_age = Duration::from_secs(max_age_seconds);
-        let initial_count = nodes.len();
+        let mut stale = Vec::new();
         
-        nodes.retain(|_, node| {
-            now.duration_since(node.last_seen).map_or(false, |age| age <= max_age)
+        nodes.retain(|id, node| {
+            let fresh = now.duration_since(node.last_seen).map_or(false, |age| age <= max_age);
+            if !fresh {
+                stale.push(id.clone());
+            }
+            fresh
         });
         
-        Ok(initial_count - nodes.len())
+        drop(nodes);
+        if !stale.is_empty() {
+            self.persist();
+        }
+        for id in &stale {
+            self.publish(id, NodeEventKind::Stale);
+        }
+        Ok(stale.len())
     }
 
     /// Start the master service server
@@ -173,6 +325,8 @@ pub async fn start_server(
 
 #[tonic::async_trait]
 impl MasterServiceTrait for MasterService {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<NodeEvent, Status>> + Send>>;
+
     async fn register(
         &self,
         request: Request<RegisterRequest>,
@@ -195,17 +349,13 @@ async fn heartbeat(
         request: Request<HeartbeatRequest>,
     ) -> Result<Response<HeartbeatResponse>, Status> {
         let req = request.into_inner();
-        let node_id = req.node_id;
-        
-        let mut nodes = self.nodes.write().map_err(|_| {
-            Status::internal("Failed to acquire write lock")
-        })?;
         
-        if let Some(node) = nodes.get_mut(&node_id) {
-            node.last_seen = SystemTime::now();
-            Ok(Response::new(HeartbeatResponse { success: true }))
-        } else {
-            Err(Status::not_found(format!("Node {} not found", node_id)))
+        match self.heartbeat_node(&req.node_id, req.metrics) {
+            Ok(()) => Ok(Response::new(HeartbeatResponse { success: true })),
+            Err(MasterServiceError::NodeNotFound(node_id)) => {
+                Err(Status::not_found(format!("Node {} not found", node_id)))
+            }
+            Err(e) => Err(Status::internal(e.to_string())),
         }
     }
     
@@ -224,11 +374,42 @@ async fn get_nodes(
                     .map(|d| d.as_secs() as i64)
                     .unwrap_or(-1),
                 metadata: node.metadata.clone(),
+                metrics: node.metrics.clone(),
             })
             .collect();
         
         Ok(Response::new(GetNodesResponse { nodes: nodes_proto }))
     }
+
+    async fn select_node(
+        &self,
+        request: Request<SelectNodeRequest>,
+    ) -> Result<Response<SelectNodeResponse>, Status> {
+        let req = request.into_inner();
+        let requirements = NodeRequirements {
+            min_gpu_memory_mb: Some(req.min_gpu_memory_mb).filter(|&mb| mb > 0),
+            model_loaded: Some(req.model_loaded).filter(|model| !model.is_empty()),
+        };
+
+        match MasterService::select_node(self, &requirements) {
+            Some(node_id) => Ok(Response::new(SelectNodeResponse { node_id })),
+            None => Err(Status::not_found("No node satisfies the requirements")),
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        _request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        // tonic streams must yield Status errors, however large
+        #[allow(clippy::result_large_err)]
+        let events = BroadcastStream::new(self.events.subscribe()).map(|event| {
+            event.map_err(|BroadcastStreamRecvError::Lagged(missed)| {
+                Status::data_loss(format!("Subscriber lagged behind and missed {} node events", missed))
+            })
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
 }
 
 #[cfg(test)]
@@ -276,4 +457,154 @@ async fn test_cleanup_stale_nodes() {
         let nodes = service.get_nodes().unwrap();
         assert!(nodes.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_select_node_by_requirements() {
+        let service = MasterService::new();
+        let metadata = |memory: &str, models: &str, load: &str| {
+            HashMap::from([
+                (GPU_MEMORY_KEY.to_string(), memory.to_string()),
+                (MODEL_LOADED_KEY.to_string(), models.to_string()),
+                (LOAD_KEY.to_string(), load.to_string()),
+            ])
+        };
+        service.register_node("small", metadata("8192", "llama-7b", "0.1")).unwrap();
+        service.register_node("large", metadata("81920", "llama-7b,llama-70b", "0.7")).unwrap();
+
+        // Both nodes qualify, so the less loaded one wins
+        let any = NodeRequirements::default();
+        assert_eq!(service.select_node(&any).as_deref(), Some("small"));
+
+        let big_model = NodeRequirements {
+            min_gpu_memory_mb: Some(40_000),
+            model_loaded: Some("llama-70b".to_string()),
+        };
+        assert_eq!(service.select_node(&big_model).as_deref(), Some("large"));
+
+        let unknown_model = NodeRequirements {
+            model_loaded: Some("mistral-7b".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.select_node(&unknown_model), None);
+
+        let response = MasterServiceTrait::select_node(&service, Request::new(SelectNodeRequest {
+            min_gpu_memory_mb: 16_384,
+            model_loaded: String::new(),
+        })).await.unwrap();
+        assert_eq!(response.into_inner().node_id, "large");
+    }
+
+    #[tokio::test]
+    async fn test_registry_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+
+        {
+            let service = MasterService::with_persistence(&path).unwrap();
+            let mut metadata = HashMap::new();
+            metadata.insert("role".to_string(), "worker".to_string());
+            service.register_node("worker-1", metadata).unwrap();
+            service.register_node("worker-2", HashMap::new()).unwrap();
+            service.register_node("worker-3", HashMap::new()).unwrap();
+            service.remove_node("worker-3").unwrap();
+        }
+
+        let service = MasterService::with_persistence(&path).unwrap();
+        let mut nodes = service.get_nodes().unwrap();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        let ids: Vec<_> = nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, ["worker-1", "worker-2"]);
+        assert_eq!(nodes[0].metadata.get("role").unwrap(), "worker");
+    }
+
+    #[tokio::test]
+    async fn test_registry_writes_are_debounced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        let service = MasterService::with_persistence(&path).unwrap();
+
+        service.register_node("worker-1", HashMap::new()).unwrap();
+        assert!(!path.exists());
+
+        tokio::time::sleep(persistence::PERSIST_DEBOUNCE * 3).await;
+        assert!(path.exists());
+        assert_eq!(MasterService::with_persistence(&path).unwrap().get_nodes().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_join_and_stale_events() {
+        let service = MasterService::new();
+        let mut events = MasterServiceTrait::subscribe(&service, Request::new(SubscribeRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        service.register_node("flaky-node", HashMap::new()).unwrap();
+        {
+            let mut nodes = service.nodes.write().unwrap();
+            if let Some(node) = nodes.get_mut("flaky-node") {
+                node.last_seen = SystemTime::now() - Duration::from_secs(120);
+            }
+        }
+        assert_eq!(service.cleanup_stale_nodes(60).await.unwrap(), 1);
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+                .await
+                .expect("timed out waiting for node event")
+                .unwrap()
+                .unwrap();
+            received.push((event.kind(), event.node_id));
+        }
+        assert_eq!(received, vec![
+            (NodeEventKind::Joined, "flaky-node".to_string()),
+            (NodeEventKind::Stale, "flaky-node".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_metrics_appear_in_node_listing() {
+        let service = MasterService::new();
+        service.register_node("gpu-node", HashMap::new()).unwrap();
+
+        let heartbeat = |metrics: &[(&str, f64)]| HeartbeatRequest {
+            node_id: "gpu-node".to_string(),
+            metrics: metrics.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        };
+        MasterServiceTrait::heartbeat(&service, Request::new(heartbeat(&[("gpu_util", 0.8)])))
+            .await
+            .unwrap();
+        MasterServiceTrait::heartbeat(&service, Request::new(heartbeat(&[("queue_depth", 3.0)])))
+            .await
+            .unwrap();
+
+        let listing = MasterServiceTrait::get_nodes(&service, Request::new(GetNodesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(listing.nodes.len(), 1);
+        let metrics = &listing.nodes[0].metrics;
+        assert_eq!(metrics.get("gpu_util"), Some(&0.8));
+        assert_eq!(metrics.get("queue_depth"), Some(&3.0));
+
+        let status = MasterServiceTrait::heartbeat(&service, Request::new(HeartbeatRequest {
+            node_id: "unknown".to_string(),
+            metrics: HashMap::new(),
+        })).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_load_overrides_metadata_load() {
+        let service = MasterService::new();
+        for (id, load) in [("a", "0.2"), ("b", "0.5")] {
+            let metadata = HashMap::from([(LOAD_KEY.to_string(), load.to_string())]);
+            service.register_node(id, metadata).unwrap();
+        }
+        assert_eq!(service.select_node(&NodeRequirements::default()).as_deref(), Some("a"));
+
+        service.heartbeat_node("a", HashMap::from([(LOAD_KEY.to_string(), 0.9)])).unwrap();
+        assert_eq!(service.select_node(&NodeRequirements::default()).as_deref(), Some("b"));
+    }
 }
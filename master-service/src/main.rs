@@ -15,6 +15,7 @@
 use master_service::MasterService;
 use std::error::Error;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::time::Duration;
 use log::{info, error, warn};
 use std::process;
@@ -28,6 +29,7 @@ struct Config {
     bind_addr: SocketAddr,
     log_level: String,
     node_timeout_seconds: u64,
+    registry_path: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -36,6 +38,7 @@ fn default() -> Self {
             bind_addr: "0.0.0.0:8080".parse().expect("Invalid default bind address"),
             log_level: "info".to_string(),
             node_timeout_seconds: 300, // 5 minutes
+            registry_path: None,
         }
     }
 }
@@ -56,6 +59,10 @@ fn from_env() -> Result<Self, Box<dyn Error>> {
         if let Ok(timeout) = std::env::var("NODE_TIMEOUT_SECONDS") {
             config.node_timeout_seconds = timeout.parse()?;
         }
+
+        if let Ok(path) = std::env::var("REGISTRY_PATH") {
+            config.registry_path = Some(PathBuf::from(path));
+        }
         
         Ok(config)
     }
@@ -140,7 +147,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
     info!("Starting master service with config: {:?}", config);
 
     // Create the master service
-    let service = MasterService::new();
+    let service = match &config.registry_path {
+        Some(path) => match MasterService::with_persistence(path) {
+            Ok(service) => service,
+            Err(e) => {
+                error!("Failed to load node registry: {}", e);
+                process::exit(1);
+            }
+        },
+        None => MasterService::new(),
+    };
 
     // Start the cleanup task
     let cleanup_interval = config.node_timeout_seconds / 6; // Clean up more frequently than the timeout
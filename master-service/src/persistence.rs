@@ -0,0 +1,151 @@
+// Copyright 2025 ZETA RETICULA INC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk persistence for the master node registry
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::{MasterServiceError, NodeInfo};
+
+/// How long to wait after a change before writing, so bursts of heartbeats coalesce
+pub(crate) const PERSIST_DEBOUNCE: Duration = Duration::from_millis(500);
+
+type NodeMap = Arc<RwLock<HashMap<String, NodeInfo>>>;
+
+/// JSON snapshot of the node registry
+struct RegistryFile {
+    path: PathBuf,
+    nodes: NodeMap,
+    dirty: AtomicBool,
+    write_lock: Mutex<()>,
+}
+
+impl RegistryFile {
+    /// Write the registry if it changed since the last successful write
+    fn flush(&self) -> Result<(), MasterServiceError> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let result = self.write_snapshot();
+        if result.is_err() {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+
+    fn write_snapshot(&self) -> Result<(), MasterServiceError> {
+        let _guard = self.write_lock.lock().map_err(|e| {
+            MasterServiceError::PersistenceError(format!("Failed to acquire write lock: {}", e))
+        })?;
+        let snapshot: Vec<NodeInfo> = {
+            let nodes = self.nodes.read().map_err(|e| {
+                MasterServiceError::ServiceError(format!("Failed to acquire read lock: {}", e))
+            })?;
+            nodes.values().cloned().collect()
+        };
+        let json = serde_json::to_vec_pretty(&snapshot)
+            .map_err(|e| MasterServiceError::PersistenceError(e.to_string()))?;
+
+        // Write then rename so a crash mid-write never leaves a truncated registry
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, json)
+            .and_then(|_| std::fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                MasterServiceError::PersistenceError(format!("{}: {}", self.path.display(), e))
+            })
+    }
+}
+
+/// Persists registry changes to disk
+///
+/// Inside a Tokio runtime writes are debounced by a background task; otherwise every
+/// change is written through. Any pending change is flushed when the last
+/// `MasterService` clone is dropped.
+pub(crate) struct RegistryPersistence {
+    file: Arc<RegistryFile>,
+    notify: Arc<Notify>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl RegistryPersistence {
+    pub(crate) fn new(path: &Path, nodes: NodeMap) -> Self {
+        let file = Arc::new(RegistryFile {
+            path: path.to_path_buf(),
+            nodes,
+            dirty: AtomicBool::new(false),
+            write_lock: Mutex::new(()),
+        });
+        let notify = Arc::new(Notify::new());
+
+        let writer = tokio::runtime::Handle::try_current().ok().map(|handle| {
+            let file = file.clone();
+            let notify = notify.clone();
+            handle.spawn(async move {
+                loop {
+                    notify.notified().await;
+                    tokio::time::sleep(PERSIST_DEBOUNCE).await;
+                    if let Err(e) = file.flush() {
+                        log::error!("Failed to persist node registry: {}", e);
+                    }
+                }
+            })
+        });
+
+        Self { file, notify, writer }
+    }
+
+    /// Load a previously persisted registry, or an empty one if `path` doesn't exist yet
+    pub(crate) fn load(path: &Path) -> Result<HashMap<String, NodeInfo>, MasterServiceError> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => {
+                return Err(MasterServiceError::PersistenceError(format!("{}: {}", path.display(), e)))
+            }
+        };
+        let nodes: Vec<NodeInfo> = serde_json::from_slice(&bytes).map_err(|e| {
+            MasterServiceError::PersistenceError(format!("{}: {}", path.display(), e))
+        })?;
+
+        Ok(nodes.into_iter().map(|node| (node.id.clone(), node)).collect())
+    }
+
+    /// Record that the registry changed and schedule a write
+    pub(crate) fn mark_dirty(&self) {
+        self.file.dirty.store(true, Ordering::SeqCst);
+        if self.writer.is_some() {
+            self.notify.notify_one();
+        } else if let Err(e) = self.file.flush() {
+            log::error!("Failed to persist node registry: {}", e);
+        }
+    }
+}
+
+impl Drop for RegistryPersistence {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            writer.abort();
+        }
+        if let Err(e) = self.file.flush() {
+            log::error!("Failed to persist node registry: {}", e);
+        }
+    }
+}
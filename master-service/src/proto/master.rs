@@ -28,6 +28,9 @@ pub struct HeartbeatRequest {
     /// The ID of the node sending the heartbeat
     #[prost(string, tag = "1")]
     pub node_id: ::prost::alloc::string::String,
+    /// Load metrics to merge into the node's latest readings
+    #[prost(map = "string, double", tag = "2")]
+    pub metrics: ::std::collections::HashMap<::prost::alloc::string::String, f64>,
 }
 /// The response message for heartbeats.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -67,6 +70,74 @@ pub struct NodeInfo {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    /// Latest load metrics reported via heartbeat
+    #[prost(map = "string, double", tag = "4")]
+    pub metrics: ::std::collections::HashMap<::prost::alloc::string::String, f64>,
+}
+/// The request message for node selection. Zero or empty fields impose no constraint.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelectNodeRequest {
+    /// Minimum `gpu_memory_mb` the node must advertise
+    #[prost(uint64, tag = "1")]
+    pub min_gpu_memory_mb: u64,
+    /// Model the node must list in its `model_loaded` metadata
+    #[prost(string, tag = "2")]
+    pub model_loaded: ::prost::alloc::string::String,
+}
+/// The response message containing the selected node.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelectNodeResponse {
+    /// The ID of the chosen node
+    #[prost(string, tag = "1")]
+    pub node_id: ::prost::alloc::string::String,
+}
+/// The request message for subscribing to node events.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeRequest {}
+/// A change in node membership.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeEvent {
+    #[prost(enumeration = "NodeEventKind", tag = "1")]
+    pub kind: i32,
+    #[prost(string, tag = "2")]
+    pub node_id: ::prost::alloc::string::String,
+}
+/// The kind of membership change a node event describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NodeEventKind {
+    /// The node registered
+    Joined = 0,
+    /// The node was removed
+    Left = 1,
+    /// The node stopped heartbeating and was cleaned up
+    Stale = 2,
+}
+impl NodeEventKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NodeEventKind::Joined => "NODE_EVENT_KIND_JOINED",
+            NodeEventKind::Left => "NODE_EVENT_KIND_LEFT",
+            NodeEventKind::Stale => "NODE_EVENT_KIND_STALE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NODE_EVENT_KIND_JOINED" => Some(Self::Joined),
+            "NODE_EVENT_KIND_LEFT" => Some(Self::Left),
+            "NODE_EVENT_KIND_STALE" => Some(Self::Stale),
+            _ => None,
+        }
+    }
 }
 /// Generated client implementations.
 pub mod master_service_client {
@@ -232,6 +303,58 @@ pub async fn get_nodes(
                 .insert(GrpcMethod::new("master.MasterService", "GetNodes"));
             self.inner.unary(req, path, codec).await
         }
+        /// Pick the least-loaded node that satisfies the given resource requirements
+        pub async fn select_node(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SelectNodeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SelectNodeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/master.MasterService/SelectNode",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("master.MasterService", "SelectNode"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Stream node membership changes as they happen
+        pub async fn subscribe(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubscribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::NodeEvent>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/master.MasterService/Subscribe",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("master.MasterService", "Subscribe"));
+            self.inner.server_streaming(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -265,6 +388,25 @@ async fn get_nodes(
             tonic::Response<super::GetNodesResponse>,
             tonic::Status,
         >;
+        /// Pick the least-loaded node that satisfies the given resource requirements
+        async fn select_node(
+            &self,
+            request: tonic::Request<super::SelectNodeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SelectNodeResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the Subscribe method.
+        type SubscribeStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::NodeEvent, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// Stream node membership changes as they happen
+        async fn subscribe(
+            &self,
+            request: tonic::Request<super::SubscribeRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SubscribeStream>, tonic::Status>;
     }
     /// The master service definition.
     #[derive(Debug)]
@@ -484,6 +626,99 @@ fn call(
                     };
                     Box::pin(fut)
                 }
+                "/master.MasterService/SelectNode" => {
+                    #[allow(non_camel_case_types)]
+                    struct SelectNodeSvc<T: MasterService>(pub Arc<T>);
+                    impl<
+                        T: MasterService,
+                    > tonic::server::UnaryService<super::SelectNodeRequest>
+                    for SelectNodeSvc<T> {
+                        type Response = super::SelectNodeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SelectNodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as MasterService>::select_node(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SelectNodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/master.MasterService/Subscribe" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeSvc<T: MasterService>(pub Arc<T>);
+                    impl<
+                        T: MasterService,
+                    > tonic::server::ServerStreamingService<super::SubscribeRequest>
+                    for SubscribeSvc<T> {
+                        type Response = super::NodeEvent;
+                        type ResponseStream = T::SubscribeStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as MasterService>::subscribe(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SubscribeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
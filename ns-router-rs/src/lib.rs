@@ -39,7 +39,7 @@
 pub use context::{NSContextAnalysis, NSContextAnalyzer};
 pub use router::{NSRouter, TokenFeatures};
 pub use salience::SalienceAnalyzer;
-pub use strategy::{ExecutionStrategy, ModelConfig, KVCacheConfig};
+pub use strategy::{ExecutionStrategy, ModelConfig, KVCacheConfig, RoutingOptions};
 
 /// Neurosymbolic Routing Plan
 /// 
@@ -58,6 +58,36 @@ pub struct NSRoutingPlan {
     
     /// Symbolic rules to apply during inference
     pub symbolic_rules: Vec<String>,
+    
+    /// Models to try, in order, if the primary model fails or is overloaded
+    #[serde(default)]
+    pub fallbacks: Vec<ModelConfig>,
+}
+
+impl NSRoutingPlan {
+    /// The primary model followed by each fallback, in the order they should be tried
+    pub fn model_chain(&self) -> impl Iterator<Item = &ModelConfig> {
+        std::iter::once(&self.model_config).chain(self.fallbacks.iter())
+    }
+    
+    /// Run `attempt` against each model in the chain until one succeeds
+    /// 
+    /// Returns the first success, or the error from the last model tried.
+    pub async fn run_with_fallbacks<T, E, F, Fut>(&self, mut attempt: F) -> Result<T, E>
+    where
+        F: FnMut(&ModelConfig) -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        let mut models = self.model_chain().peekable();
+        loop {
+            let model = models.next().expect("model chain always has a primary model");
+            match attempt(model).await {
+                Ok(output) => return Ok(output),
+                Err(e) if models.peek().is_none() => return Err(e),
+                Err(_) => log::warn!("Model of size {} failed, trying next fallback", model.size),
+            }
+        }
+    }
 }
 
 /// Initialize a new NSRouter instance
@@ -80,10 +110,40 @@ pub fn initialize_ns_router() -> NSRouter {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_router_initialization() {
+    #[tokio::test]
+    async fn test_router_initialization() {
         let router = initialize_ns_router();
-        assert!(router.route_inference("test input", "user1").is_ok());
+        assert!(router.route_inference("test input", "user1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_with_fallbacks_tries_next_model_on_error() {
+        let model = |precision| ModelConfig { size: 100, precision: vec![precision] };
+        let plan = NSRoutingPlan {
+            model_config: model(PrecisionLevel::Bit4),
+            execution_strategy: "local".to_string(),
+            kv_cache_config: KVCacheConfig { sparsity: 0.5, priority_tokens: vec![] },
+            symbolic_rules: vec![],
+            fallbacks: vec![model(PrecisionLevel::Bit8), model(PrecisionLevel::Bit16)],
+        };
+
+        let mut tried = Vec::new();
+        let result: Result<PrecisionLevel, String> = plan.run_with_fallbacks(|model| {
+            let precision = model.precision[0];
+            tried.push(precision);
+            async move {
+                match precision {
+                    PrecisionLevel::Bit4 => Err("overloaded".to_string()),
+                    other => Ok(other),
+                }
+            }
+        }).await;
+
+        assert_eq!(result, Ok(PrecisionLevel::Bit8));
+        assert_eq!(tried, vec![PrecisionLevel::Bit4, PrecisionLevel::Bit8]);
+
+        let result: Result<(), &str> = plan.run_with_fallbacks(|_| async { Err("down") }).await;
+        assert_eq!(result, Err("down"));
     }
 }
 
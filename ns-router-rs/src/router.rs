@@ -57,7 +57,7 @@ pub enum RouterError {
 use crate::{
     NSRoutingPlan,
     context::{NSContextAnalyzer, NSContextAnalysis},
-    strategy::NSStrategySelector,
+    strategy::{self, NSStrategySelector, RoutingOptions},
     symbolic::{SymbolicReasoner, SymbolicError},
 };
 
@@ -249,13 +249,27 @@ pub fn with_config(config: RouterConfig) -> Self {
     /// - Symbolic reasoning fails
     /// - Strategy selection fails
     pub async fn route_inference(&self, input: &str, user_id: &str) -> RouterResult<NSRoutingPlan> {
+        self.route_inference_with_options(input, user_id, &RoutingOptions::default()).await
+    }
+    
+    /// Route an inference request, honouring the caller's latency SLA and quality budget
+    /// 
+    /// When `options` sets an SLA or budget, the plan's execution strategy is a
+    /// cost/latency tier (`low_latency`, `balanced` or `high_quality`) and the model
+    /// is sized to match; otherwise this behaves like [`NSRouter::route_inference`].
+    pub async fn route_inference_with_options(
+        &self,
+        input: &str,
+        user_id: &str,
+        options: &RoutingOptions,
+    ) -> RouterResult<NSRoutingPlan> {
         // Input validation
         if input.trim().is_empty() {
             return Err(RouterError::EmptyInput);
         }
         
         // Check cache first
-        let cache_key = format!("{}:{}", user_id, input);
+        let cache_key = format!("{}:{:?}:{}", user_id, options, input);
         if let Some(cached_plan) = {
             let cache = self.decision_cache.read().await;
             cache.peek(&cache_key).cloned()
@@ -324,19 +338,31 @@ pub async fn route_inference(&self, input: &str, user_id: &str) -> RouterResult<
             .collect();
         
         // Select execution strategy with salience information
-        let (strategy_model_config, execution_strategy, strategy_kv_cache_config, symbolic_rules) = 
+        let (mut strategy_model_config, mut execution_strategy, mut strategy_kv_cache_config, symbolic_rules) = 
             self.strategy_selector.select_strategy(&context);
         
+        // Trade cost against latency and quality when the caller asked for it
+        if let Some(tier) = self.strategy_selector.select_tier(&context, options) {
+            strategy_model_config = self.strategy_selector.model_for_tier(tier, strategy_model_config);
+            execution_strategy = tier;
+        }
+        
+        // Let the KV cache keep the tokens the salience analysis found important
+        if self.config.enable_salience && !salience_results.is_empty() {
+            strategy_kv_cache_config = salience_kv_cache_config(&salience_results);
+        }
+        
+        // Step up through precision tiers if the primary model fails
+        let confidence = mean_role_confidence(&salience_results);
+        let fallbacks = precision_fallbacks(&strategy_model_config, confidence);
+        
         // Create routing plan with time directionality and salience information
         let plan = NSRoutingPlan {
             model_config: strategy_model_config,
-            execution_strategy: match execution_strategy {
-                crate::strategy::ExecutionStrategy::Local => "local".to_string(),
-                crate::strategy::ExecutionStrategy::Distributed => "distributed".to_string(),
-                crate::strategy::ExecutionStrategy::Federated => "federated".to_string(),
-            },
+            execution_strategy: execution_strategy.as_str().to_string(),
             kv_cache_config: strategy_kv_cache_config,
             symbolic_rules: context.symbolic_constraints,
+            fallbacks,
         };
         
         // Log the routing decision with time directionality
@@ -445,6 +471,81 @@ fn calculate_cache_size(&self, context: &NSContextAnalysis) -> u32 {
 
 
 
+/// Derive the KV cache configuration from per-token salience
+///
+/// Tokens scoring at or above the input's average salience become priority tokens, and
+/// sparsity falls as average salience rises, so salient inputs keep more of their cache.
+pub fn salience_kv_cache_config(salience_results: &[SalienceResult]) -> strategy::KVCacheConfig {
+    if salience_results.is_empty() {
+        return strategy::KVCacheConfig { sparsity: 1.0, priority_tokens: Vec::new() };
+    }
+
+    let avg_salience = salience_results.iter().map(|r| r.salience_score).sum::<f32>()
+        / salience_results.len() as f32;
+    let priority_tokens = salience_results
+        .iter()
+        .filter(|r| r.salience_score >= avg_salience)
+        .map(|r| r.token_id)
+        .collect();
+
+    strategy::KVCacheConfig {
+        sparsity: (1.0 - avg_salience).clamp(0.0, 1.0),
+        priority_tokens,
+    }
+}
+
+/// Precision tiers from cheapest to most accurate, used to build fallback chains
+const PRECISION_TIERS: [PrecisionLevel; 4] = [
+    PrecisionLevel::Bit4,
+    PrecisionLevel::Bit8,
+    PrecisionLevel::Bit16,
+    PrecisionLevel::Bit32,
+];
+
+/// Inputs whose mean role confidence falls below this get every higher precision tier as a fallback
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Position of a precision level in `PRECISION_TIERS`
+fn precision_tier(precision: &PrecisionLevel) -> usize {
+    match precision {
+        PrecisionLevel::Bit4 => 0,
+        PrecisionLevel::Bit8 => 1,
+        PrecisionLevel::Bit16 | PrecisionLevel::FP16 => 2,
+        PrecisionLevel::Bit32 => 3,
+        PrecisionLevel::Custom(bits) => match bits {
+            0..=4 => 0,
+            5..=8 => 1,
+            9..=16 => 2,
+            _ => 3,
+        },
+    }
+}
+
+/// Mean role confidence across the analysed tokens, or 0.0 for no tokens
+fn mean_role_confidence(salience_results: &[SalienceResult]) -> f32 {
+    if salience_results.is_empty() {
+        return 0.0;
+    }
+    salience_results.iter().map(|r| r.role_confidence).sum::<f32>() / salience_results.len() as f32
+}
+
+/// Build the fallback chain for a primary model from the precision tiers above it
+///
+/// Confident inputs fall back one tier; low-confidence inputs get every higher tier,
+/// so a failing low-precision run can escalate all the way to full precision.
+pub fn precision_fallbacks(primary: &strategy::ModelConfig, confidence: f32) -> Vec<strategy::ModelConfig> {
+    let Some(current) = primary.precision.iter().map(precision_tier).max() else {
+        return Vec::new();
+    };
+    let steps = if confidence < LOW_CONFIDENCE_THRESHOLD { PRECISION_TIERS.len() } else { 1 };
+
+    PRECISION_TIERS[current + 1..]
+        .iter()
+        .take(steps)
+        .map(|&precision| strategy::ModelConfig { size: primary.size, precision: vec![precision] })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolicRule {
     pub name: String,
@@ -465,14 +566,13 @@ pub struct SymbolicRules {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
-    use tokio::test;
 
     #[test]
     fn test_router_initialization() {
         let router = NSRouter::new();
-        // Verify the router was created with default components
-        assert!(router.context_analyzer.is_some());
-        assert!(router.strategy_selector.is_some());
+        // Verify the router was created with the default configuration
+        assert!(router.config.enable_salience);
+        assert!(router.config.enable_symbolic);
     }
 
     #[tokio::test]
@@ -486,7 +586,7 @@ async fn test_route_inference_success() {
         let plan = result.unwrap();
         
         // Verify the routing plan contains expected values
-        assert!(!plan.model_config.model_name.is_empty(), "Model name should not be empty");
+        assert!(plan.model_config.size > 0, "Model size should be set");
         assert!(!plan.execution_strategy.is_empty(), "Execution strategy should be set");
     }
 
@@ -538,6 +638,101 @@ async fn test_error_handling() {
         let result = router.route_inference(&long_input, "user123").await;
         assert!(result.is_ok(), "Should handle long input gracefully");
     }
+
+    fn salience(token_id: u32, salience_score: f32) -> SalienceResult {
+        SalienceResult {
+            token_id,
+            salience_score,
+            role: "subject".to_string(),
+            role_confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_salience_kv_cache_config() {
+        let results = [salience(0, 0.1), salience(1, 0.9), salience(2, 0.2), salience(3, 0.8)];
+        let config = salience_kv_cache_config(&results);
+
+        assert_eq!(config.priority_tokens, vec![1, 3]);
+        assert!((config.sparsity - 0.5).abs() < 1e-6);
+
+        // Higher average salience means a denser cache
+        let salient = salience_kv_cache_config(&[salience(0, 0.9), salience(1, 0.95)]);
+        assert!(salient.sparsity < config.sparsity);
+    }
+
+    #[tokio::test]
+    async fn test_route_inference_prioritizes_salient_tokens() {
+        let router = NSRouter::new();
+        let input = "Never deploy untested code to production servers";
+
+        let plan = router.route_inference(input, "user123").await.unwrap();
+        let config = &plan.kv_cache_config;
+
+        let token_count = input.split_whitespace().count() as u32;
+        assert!(!config.priority_tokens.is_empty());
+        assert!(config.priority_tokens.iter().all(|&id| id < token_count));
+        assert!((0.0..=1.0).contains(&config.sparsity));
+    }
+
+    #[tokio::test]
+    async fn test_route_inference_with_options_selects_tier() {
+        let router = NSRouter::new();
+        let input = "Summarize the quarterly report";
+
+        let tight_sla = RoutingOptions { sla_ms: Some(50), budget: None };
+        let plan = router.route_inference_with_options(input, "user123", &tight_sla).await.unwrap();
+        assert_eq!(plan.execution_strategy, "low_latency");
+
+        let high_budget = RoutingOptions { sla_ms: None, budget: Some(0.9) };
+        let plan = router.route_inference_with_options(input, "user123", &high_budget).await.unwrap();
+        assert_eq!(plan.execution_strategy, "high_quality");
+        assert_eq!(plan.model_config.precision, vec![PrecisionLevel::Bit32]);
+
+        // No SLA or budget keeps the placement strategy
+        let plan = router.route_inference(input, "user123").await.unwrap();
+        assert_eq!(plan.execution_strategy, "local");
+    }
+
+    fn fallback_precisions(fallbacks: &[strategy::ModelConfig]) -> Vec<PrecisionLevel> {
+        fallbacks.iter().flat_map(|model| model.precision.clone()).collect()
+    }
+
+    #[test]
+    fn test_precision_fallbacks_follow_confidence() {
+        let primary = strategy::ModelConfig { size: 100_000_000, precision: vec![PrecisionLevel::Bit4] };
+
+        let low_confidence = precision_fallbacks(&primary, 0.2);
+        assert_eq!(
+            fallback_precisions(&low_confidence),
+            vec![PrecisionLevel::Bit8, PrecisionLevel::Bit16, PrecisionLevel::Bit32],
+        );
+        assert!(low_confidence.iter().all(|model| model.size == primary.size));
+
+        let confident = precision_fallbacks(&primary, 0.9);
+        assert_eq!(fallback_precisions(&confident), vec![PrecisionLevel::Bit8]);
+
+        // Nothing sits above full precision
+        let full = strategy::ModelConfig { size: 1, precision: vec![PrecisionLevel::Bit32] };
+        assert!(precision_fallbacks(&full, 0.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_low_precision_plan_has_higher_precision_fallback() {
+        let router = NSRouter::new();
+        let options = RoutingOptions { sla_ms: Some(50), budget: None };
+
+        let plan = router
+            .route_inference_with_options("Summarize the quarterly report", "user123", &options)
+            .await
+            .unwrap();
+
+        assert_eq!(plan.model_config.precision, vec![PrecisionLevel::Bit8]);
+        let primary_tier = precision_tier(&plan.model_config.precision[0]);
+        assert!(plan.fallbacks.iter()
+            .flat_map(|model| &model.precision)
+            .any(|precision| precision_tier(precision) > primary_tier));
+    }
 }
 
 
@@ -55,6 +55,48 @@ pub enum ExecutionStrategy {
     
     /// Distribute inference across multiple nodes
     Distributed,
+    
+    /// Favour response time over output quality
+    LowLatency,
+    
+    /// Favour output quality over cost and response time
+    HighQuality,
+    
+    /// Trade cost, latency and quality off evenly
+    Balanced,
+}
+
+impl ExecutionStrategy {
+    /// Name used for the strategy in routing plans
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionStrategy::Local => "local",
+            ExecutionStrategy::Federated => "federated",
+            ExecutionStrategy::Distributed => "distributed",
+            ExecutionStrategy::LowLatency => "low_latency",
+            ExecutionStrategy::HighQuality => "high_quality",
+            ExecutionStrategy::Balanced => "balanced",
+        }
+    }
+}
+
+/// SLAs at or below this many milliseconds are always routed `LowLatency`
+pub const LOW_LATENCY_SLA_MS: u32 = 250;
+
+/// Budgets at or above this are routed `HighQuality` unless the SLA is tight
+pub const HIGH_QUALITY_BUDGET: f32 = 0.8;
+
+/// Budgets at or below this are routed `LowLatency`
+pub const LOW_COST_BUDGET: f32 = 0.2;
+
+/// Per-request requirements that steer the cost/latency/quality trade-off
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct RoutingOptions {
+    /// Target response time in milliseconds
+    pub sla_ms: Option<u32>,
+    
+    /// Quality budget from 0.0 (cheapest) to 1.0 (best quality regardless of cost)
+    pub budget: Option<f32>,
 }
 
 /// Selects the optimal execution strategy based on context analysis
@@ -130,6 +172,60 @@ pub fn select_strategy(
     }
 }
 
+impl NSStrategySelector {
+    /// Pick a cost/latency tier for the request
+    /// 
+    /// Returns `None` when the request states no SLA or budget, leaving the
+    /// placement strategy from `select_strategy` in charge. A tight SLA wins over
+    /// any budget; otherwise an extreme budget decides, and a middling one defers
+    /// to the complexity of the input.
+    pub fn select_tier(
+        &self,
+        context: &NSContextAnalysis,
+        options: &RoutingOptions,
+    ) -> Option<ExecutionStrategy> {
+        if options.sla_ms.is_none() && options.budget.is_none() {
+            return None;
+        }
+        if options.sla_ms.is_some_and(|sla| sla <= LOW_LATENCY_SLA_MS) {
+            return Some(ExecutionStrategy::LowLatency);
+        }
+        match options.budget {
+            Some(budget) if budget >= HIGH_QUALITY_BUDGET => return Some(ExecutionStrategy::HighQuality),
+            Some(budget) if budget <= LOW_COST_BUDGET => return Some(ExecutionStrategy::LowLatency),
+            _ => {}
+        }
+        
+        let tier = if context.complexity >= 0.7 {
+            ExecutionStrategy::HighQuality
+        } else if context.complexity <= 0.3 {
+            ExecutionStrategy::LowLatency
+        } else {
+            ExecutionStrategy::Balanced
+        };
+        Some(tier)
+    }
+    
+    /// Adjust the model choice to match a cost/latency tier
+    pub fn model_for_tier(&self, tier: ExecutionStrategy, mut model_config: ModelConfig) -> ModelConfig {
+        match tier {
+            ExecutionStrategy::LowLatency => {
+                model_config.size = model_config.size.min(100_000_000);
+                model_config.precision = vec![PrecisionLevel::Bit8];
+            }
+            ExecutionStrategy::HighQuality => {
+                model_config.size = model_config.size.max(1_000_000_000);
+                model_config.precision = vec![PrecisionLevel::Bit32];
+            }
+            ExecutionStrategy::Balanced => {
+                model_config.precision = vec![PrecisionLevel::Bit16];
+            }
+            ExecutionStrategy::Local | ExecutionStrategy::Federated | ExecutionStrategy::Distributed => {}
+        }
+        model_config
+    }
+}
+
 impl Default for NSStrategySelector {
     fn default() -> Self {
         Self::new()
@@ -139,38 +235,56 @@ fn default() -> Self {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::TokenFeatures;
 
     #[test]
     fn test_strategy_selection() {
         let selector = NSStrategySelector::new();
-        let context = NSContextAnalysis {
-            input: "test input".to_string(),
-            token_features: vec![
-                TokenFeatures {
-                    token_id: 1,
-                    frequency: 0.5,
-                    sentiment_score: 0.0,
-                    context_relevance: 1.0,
-                    role: "subject".to_string(),
-                },
-                TokenFeatures {
-                    token_id: 2,
-                    frequency: 0.5,
-                    sentiment_score: 0.0,
-                    context_relevance: 1.0,
-                    role: "modifier".to_string(),
-                },
-            ],
-            token_count: 2,
-            salience_profile: vec![],
-            theory_complexity: 0.0,
-            symbolic_constraints: vec![],
-        };
+        // Two tokens: one subject-like high-salience token and one modifier
+        let context = NSContextAnalysis::new().with_token_salience(vec![0.8, 0.3]);
 
         let (model_config, strategy, _, _) = selector.select_strategy(&context);
         assert!(model_config.size > 0);
         assert!(!model_config.precision.is_empty());
         assert!(matches!(strategy, ExecutionStrategy::Local));
     }
+
+    #[test]
+    fn test_tight_sla_selects_low_latency() {
+        let selector = NSStrategySelector::new();
+        let context = NSContextAnalysis::new().with_complexity(0.9);
+        
+        // A tight SLA overrides both a generous budget and a complex input
+        let options = RoutingOptions { sla_ms: Some(100), budget: Some(1.0) };
+        assert_eq!(selector.select_tier(&context, &options), Some(ExecutionStrategy::LowLatency));
+        
+        let model = selector.model_for_tier(ExecutionStrategy::LowLatency, ModelConfig {
+            size: 1_000_000_000,
+            precision: vec![PrecisionLevel::Bit32],
+        });
+        assert_eq!(model.size, 100_000_000);
+        assert_eq!(model.precision, vec![PrecisionLevel::Bit8]);
+    }
+
+    #[test]
+    fn test_high_budget_selects_high_quality() {
+        let selector = NSStrategySelector::new();
+        let context = NSContextAnalysis::new().with_complexity(0.1);
+        
+        let options = RoutingOptions { sla_ms: Some(5_000), budget: Some(0.95) };
+        assert_eq!(selector.select_tier(&context, &options), Some(ExecutionStrategy::HighQuality));
+    }
+
+    #[test]
+    fn test_middling_budget_follows_complexity() {
+        let selector = NSStrategySelector::new();
+        let options = RoutingOptions { sla_ms: None, budget: Some(0.5) };
+        
+        let tier = |complexity| selector.select_tier(&NSContextAnalysis::new().with_complexity(complexity), &options);
+        assert_eq!(tier(0.1), Some(ExecutionStrategy::LowLatency));
+        assert_eq!(tier(0.5), Some(ExecutionStrategy::Balanced));
+        assert_eq!(tier(0.9), Some(ExecutionStrategy::HighQuality));
+        
+        // Without an SLA or budget the placement strategy is left alone
+        assert_eq!(selector.select_tier(&NSContextAnalysis::new(), &RoutingOptions::default()), None);
+    }
 }
\ No newline at end of file
@@ -164,7 +164,7 @@ fn test_salience_filtering() {
             .collect::<Vec<_>>();
             
         let salience = vec![QuantizationResult {
-            salience_score: 0.5,
+            original: 0.5,
             ..Default::default()
         }];
         
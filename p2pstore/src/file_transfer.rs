@@ -0,0 +1,168 @@
+// Copyright 2025 ZETA RETICULA
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File-backed transfer engine
+
+use std::path::PathBuf;
+
+use crate::{KVCache, Segment, TransferEngine, TransferEngineError};
+
+/// Transfer engine that keeps each segment's KV caches in one JSON file under `root`
+#[derive(Debug, Clone)]
+pub struct FileTransferEngine {
+    root: PathBuf,
+    segment: Segment,
+}
+
+impl FileTransferEngine {
+    pub fn new(root: impl Into<PathBuf>, segment: Segment) -> Self {
+        FileTransferEngine {
+            root: root.into(),
+            segment,
+        }
+    }
+
+    /// File holding the caches saved for this engine's segment
+    pub fn segment_path(&self) -> PathBuf {
+        self.root.join(format!("{}.json", self.segment.id))
+    }
+
+    async fn read_segment(&self) -> Result<Vec<KVCache>, TransferEngineError> {
+        let path = self.segment_path();
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| TransferEngineError::Io(format!("{}: {}", path.display(), e)))?;
+        let caches: Vec<KVCache> = serde_json::from_slice(&bytes)
+            .map_err(|e| TransferEngineError::Io(format!("{}: {}", path.display(), e)))?;
+
+        for (i, cache) in caches.iter().enumerate() {
+            let actual = cache.compute_descriptor_checksum();
+            if actual != cache.descriptor_checksum {
+                return Err(TransferEngineError::Io(format!(
+                    "descriptor checksum mismatch for KV cache {} in segment {}: expected {:016x}, got {:016x}",
+                    i, self.segment.id, cache.descriptor_checksum, actual
+                )));
+            }
+        }
+        Ok(caches)
+    }
+}
+
+impl TransferEngine for FileTransferEngine {
+    /// Push the saved copy of `cache`, matched by its buffer descriptors, onto `hbm`
+    async fn async_load(&self, cache: &KVCache, hbm: &mut Vec<KVCache>) -> Result<(), TransferEngineError> {
+        let saved = self
+            .read_segment()
+            .await?
+            .into_iter()
+            .find(|saved| saved.buffers == cache.buffers)
+            .ok_or_else(|| {
+                TransferEngineError::Io(format!("KV cache not found in segment {}", self.segment.id))
+            })?;
+        hbm.push(saved);
+        Ok(())
+    }
+
+    /// Replace everything saved for the segment with `cache`, stamping each entry's descriptor checksum
+    async fn async_save(&self, mut cache: Vec<KVCache>) -> Result<(), TransferEngineError> {
+        for entry in &mut cache {
+            entry.descriptor_checksum = entry.compute_descriptor_checksum();
+        }
+        tokio::fs::create_dir_all(&self.root).await?;
+        let json = serde_json::to_vec(&cache).map_err(|e| TransferEngineError::Io(e.to_string()))?;
+
+        // Write then rename so a reader never sees a partially written segment
+        let path = self.segment_path();
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, json).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AllocatedBufferDescriptor;
+    use tempfile::tempdir;
+
+    fn test_segment() -> Segment {
+        Segment {
+            id: "segment-0".to_string(),
+            name: "layer0".to_string(),
+            client_id: "client-a".to_string(),
+        }
+    }
+
+    fn test_cache(base: u64) -> KVCache {
+        let mut cache = KVCache::new(vec![
+            AllocatedBufferDescriptor { buffer_address_: base, size_: 4096 },
+            AllocatedBufferDescriptor { buffer_address_: base + 4096, size_: 1024 },
+        ]);
+        cache.positional_encoding = Some(vec![0, 1, 2]);
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_round_trip() {
+        let dir = tempdir().unwrap();
+        let engine = FileTransferEngine::new(dir.path(), test_segment());
+        let first = test_cache(0x1000);
+        let second = test_cache(0x9000);
+        engine.async_save(vec![first.clone(), second.clone()]).await.unwrap();
+
+        let mut hbm = Vec::new();
+        engine.async_load(&KVCache::new(second.buffers.clone()), &mut hbm).await.unwrap();
+
+        assert_eq!(hbm.len(), 1);
+        assert_eq!(hbm[0].buffers, second.buffers);
+        assert_eq!(hbm[0].positional_encoding, Some(vec![0, 1, 2]));
+        assert_eq!(hbm[0].descriptor_checksum, second.compute_descriptor_checksum());
+    }
+
+    #[tokio::test]
+    async fn test_load_detects_corrupted_segment() {
+        let dir = tempdir().unwrap();
+        let engine = FileTransferEngine::new(dir.path(), test_segment());
+        let cache = test_cache(0x1000);
+        engine.async_save(vec![cache.clone()]).await.unwrap();
+
+        // Flip one byte of a saved buffer size: 4096 -> 4097
+        let path = engine.segment_path();
+        let mut bytes = std::fs::read(&path).unwrap();
+        let offset = bytes.windows(4).position(|w| w == b"4096").unwrap() + 3;
+        bytes[offset] = b'7';
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut hbm = Vec::new();
+        let result = engine.async_load(&cache, &mut hbm).await;
+        match result {
+            Err(TransferEngineError::Io(msg)) => assert!(msg.contains("checksum mismatch"), "{}", msg),
+            other => panic!("expected checksum error, got {:?}", other),
+        }
+        assert!(hbm.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_unknown_cache_fails() {
+        let dir = tempdir().unwrap();
+        let engine = FileTransferEngine::new(dir.path(), test_segment());
+        engine.async_save(vec![test_cache(0x1000)]).await.unwrap();
+
+        let mut hbm = Vec::new();
+        let result = engine.async_load(&test_cache(0x5000), &mut hbm).await;
+        assert!(matches!(result, Err(TransferEngineError::Io(_))));
+        assert!(hbm.is_empty());
+    }
+}
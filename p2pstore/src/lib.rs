@@ -14,6 +14,11 @@
 
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
+use xxhash_rust::xxh3::Xxh3;
+
+mod file_transfer;
+
+pub use file_transfer::FileTransferEngine;
 
 // Privacy policy placeholder - module not available
 #[derive(Debug, Clone, Default)]
@@ -23,7 +28,7 @@ pub struct PrivacyPolicy {
 
 
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct AllocatedBufferDescriptor {
     pub buffer_address_: u64,
     pub size_: u64,
@@ -33,6 +38,10 @@ pub struct AllocatedBufferDescriptor {
 pub struct KVCache {
     pub buffers: Vec<AllocatedBufferDescriptor>,
     pub positional_encoding: Option<Vec<i32>>,
+    /// xxh3 digest of the buffer descriptors and positions, set when the cache
+    /// is saved. It guards this metadata only; buffer contents are not covered.
+    #[serde(default, alias = "checksum")]
+    pub descriptor_checksum: u64,
 }
 
 impl KVCache {
@@ -40,7 +49,25 @@ pub fn new(buffers: Vec<AllocatedBufferDescriptor>) -> Self {
         KVCache {
             buffers,
             positional_encoding: None,
+            descriptor_checksum: 0,
+        }
+    }
+
+    /// Digest of the buffer descriptors and positional encoding, which is
+    /// everything the cache holds besides the stored digest itself
+    pub fn compute_descriptor_checksum(&self) -> u64 {
+        let mut hasher = Xxh3::new();
+        for buffer in &self.buffers {
+            hasher.update(&buffer.buffer_address_.to_le_bytes());
+            hasher.update(&buffer.size_.to_le_bytes());
+        }
+        if let Some(positions) = &self.positional_encoding {
+            hasher.update(&[1]);
+            for position in positions {
+                hasher.update(&position.to_le_bytes());
+            }
         }
+        hasher.digest()
     }
 }
 
@@ -64,4 +91,8 @@ pub enum TransferEngineError {
     Overflow(String),
 }
 
-
+impl From<std::io::Error> for TransferEngineError {
+    fn from(e: std::io::Error) -> Self {
+        TransferEngineError::Io(e.to_string())
+    }
+}
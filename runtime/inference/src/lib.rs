@@ -20,14 +20,24 @@
 //! - ns-router-rs/src/inference.rs
 //! - llm-rs inference components
 
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use zeta_shared::{ZetaConfig, ProcessingStats, ModelMetadata, Result, ZetaError};
-use zeta_kv_cache::UnifiedKVCache;
+use zeta_kv_cache::{StoreOutcome, UnifiedKVCache};
 use zeta_quantization::UnifiedQuantizer;
-use zeta_salience::UnifiedSalienceSystem;
+use zeta_salience::{SalienceResult, UnifiedSalienceSystem};
 use serde::{Serialize, Deserialize};
 use tracing::{info, debug, error};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use futures::{Stream, StreamExt, TryStreamExt};
+
+mod model_files;
+
+pub use model_files::{load_model_tensors, load_model_weights};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InferenceRequest {
@@ -39,6 +49,12 @@ pub struct InferenceRequest {
     pub top_p: Option<f32>,
     pub use_cache: bool,
     pub compute_salience: bool,
+    /// Seed for token sampling; unseeded requests sample from OS entropy
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Generation stops after this token is produced
+    #[serde(default)]
+    pub eos_token_id: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +73,112 @@ pub struct CacheStats {
     pub misses: usize,
     pub hit_rate: f32,
     pub memory_usage_mb: usize,
+    /// Values written to the cache by this request
+    #[serde(default)]
+    pub stored: usize,
+    /// Values dropped by the cache for falling below its salience threshold
+    #[serde(default)]
+    pub skipped: usize,
+}
+
+/// One generated token from `stream_inference`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamChunk {
+    /// Position of the token within the generated continuation
+    pub index: usize,
+    pub token_id: u32,
+    pub salience: f32,
+    /// Milliseconds since the request started
+    pub elapsed_ms: u64,
+}
+
+/// Prompt-side results shared by the batch and streaming paths
+struct PreparedInference {
+    model_metadata: ModelMetadata,
+    salience_scores: Vec<f32>,
+    output_data: Vec<f32>,
+    cache_hits: usize,
+    cache_misses: usize,
+    cache_stored: usize,
+    cache_skipped: usize,
+}
+
+/// Sampling state carried across generation steps
+struct TokenGenerator {
+    logits: Vec<f32>,
+    rng: StdRng,
+    temperature: f32,
+    top_p: Option<f32>,
+    eos_token_id: Option<u32>,
+    use_cache: bool,
+    compute_salience: bool,
+    remaining: usize,
+    produced: usize,
+    cache_stored: usize,
+    cache_skipped: usize,
+}
+
+impl TokenGenerator {
+    fn new(request: &InferenceRequest, logits: Vec<f32>) -> Self {
+        Self {
+            logits,
+            rng: match request.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            },
+            temperature: request.temperature.unwrap_or(1.0),
+            top_p: request.top_p,
+            eos_token_id: request.eos_token_id,
+            use_cache: request.use_cache,
+            compute_salience: request.compute_salience,
+            remaining: request.max_tokens.unwrap_or(1),
+            produced: 0,
+            cache_stored: 0,
+            cache_skipped: 0,
+        }
+    }
+}
+
+enum StreamState {
+    Pending(InferenceRequest),
+    Generating(Box<TokenGenerator>),
+}
+
+/// Fixed-point scale for fractional values kept in atomic accumulators
+const METRIC_SCALE: f64 = 1_000_000.0;
+
+/// Cumulative counters behind `get_processing_stats`
+#[derive(Default)]
+struct EngineMetrics {
+    processing_time_us: AtomicU64,
+    quantized_requests: AtomicU64,
+    compression_ratio_sum: AtomicU64, // scaled by METRIC_SCALE
+    salience_samples: AtomicU64,
+    salience_sum: AtomicU64, // scaled by METRIC_SCALE
+}
+
+impl EngineMetrics {
+    fn record_time(&self, elapsed: Duration) {
+        self.processing_time_us.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn record_quantization(&self, compression_ratio: f32) {
+        self.quantized_requests.fetch_add(1, Ordering::Relaxed);
+        self.compression_ratio_sum.fetch_add((compression_ratio as f64 * METRIC_SCALE) as u64, Ordering::Relaxed);
+    }
+
+    fn record_salience(&self, scores: &[f32]) {
+        let sum: f64 = scores.iter().map(|&score| score.max(0.0) as f64).sum();
+        self.salience_samples.fetch_add(scores.len() as u64, Ordering::Relaxed);
+        self.salience_sum.fetch_add((sum * METRIC_SCALE) as u64, Ordering::Relaxed);
+    }
+
+    fn average(sum: &AtomicU64, count: &AtomicU64) -> f32 {
+        match count.load(Ordering::Relaxed) {
+            0 => 0.0,
+            n => (sum.load(Ordering::Relaxed) as f64 / METRIC_SCALE / n as f64) as f32,
+        }
+    }
 }
 
 /// Unified Inference Engine
@@ -66,6 +188,7 @@ pub struct UnifiedInferenceEngine {
     quantizer: Arc<RwLock<UnifiedQuantizer>>,
     salience_system: Arc<RwLock<UnifiedSalienceSystem>>,
     models: Arc<RwLock<std::collections::HashMap<String, ModelMetadata>>>,
+    metrics: EngineMetrics,
 }
 
 impl UnifiedInferenceEngine {
@@ -83,6 +206,7 @@ pub async fn new(config: ZetaConfig) -> Result<Self> {
             quantizer,
             salience_system,
             models,
+            metrics: EngineMetrics::default(),
         })
     }
 
@@ -93,10 +217,105 @@ pub async fn register_model(&self, metadata: ModelMetadata) -> Result<()> {
         Ok(())
     }
 
+    /// Register a model from a `.safetensors`, `.gguf` or Hugging Face `config.json`
+    /// file, reading only its headers plus a SHA-256 pass over the file for
+    /// `ModelMetadata::verify_integrity`. The model is registered under its file stem.
+    pub async fn register_model_from_path(&self, path: &Path) -> Result<ModelMetadata> {
+        let owned = path.to_path_buf();
+        let metadata = tokio::task::spawn_blocking(move || model_files::read_model_metadata(&owned))
+            .await
+            .map_err(|e| ZetaError::Runtime(format!("Model loader task failed: {}", e)))??;
+
+        self.register_model(metadata.clone()).await?;
+        Ok(metadata)
+    }
+
+    /// Run one inference request, failing with `ZetaError::Runtime` if it takes
+    /// longer than `runtime.timeout_seconds` (0 disables the limit)
     pub async fn process_inference(&self, request: InferenceRequest) -> Result<InferenceResponse> {
+        let timeout_seconds = self.config.runtime.timeout_seconds;
+        if timeout_seconds == 0 {
+            return self.run_inference(request).await;
+        }
+
+        // Dropping the timed-out future releases any lock guards it holds
+        tokio::time::timeout(Duration::from_secs(timeout_seconds), self.run_inference(request))
+            .await
+            .map_err(|_| ZetaError::Runtime("inference timed out".to_string()))?
+    }
+
+    async fn run_inference(&self, request: InferenceRequest) -> Result<InferenceResponse> {
         let start_time = std::time::Instant::now();
         debug!("Processing inference request for model: {}", request.model_id);
 
+        let prepared = self.prepare_inference(&request).await?;
+
+        // Step 6: Generate up to max_tokens, treating the output as logits over the vocabulary
+        let mut generator = TokenGenerator::new(&request, prepared.output_data.clone());
+        let mut output_tokens = Vec::with_capacity(generator.remaining);
+        while let Some((token, _)) = self.generate_step(&mut generator).await? {
+            output_tokens.push(token);
+        }
+
+        let processing_time = start_time.elapsed().as_millis() as u64;
+        let cache_stats = self.kv_cache.get_stats();
+        let (cache_hits, cache_misses) = (prepared.cache_hits, prepared.cache_misses);
+
+        let response = InferenceResponse {
+            output_tokens,
+            output_data: prepared.output_data,
+            salience_scores: prepared.salience_scores,
+            cache_stats: CacheStats {
+                hits: cache_hits,
+                misses: cache_misses,
+                hit_rate: if cache_hits + cache_misses > 0 {
+                    cache_hits as f32 / (cache_hits + cache_misses) as f32
+                } else {
+                    0.0
+                },
+                memory_usage_mb: cache_stats.memory_usage_bytes / (1024 * 1024),
+                stored: prepared.cache_stored + generator.cache_stored,
+                skipped: prepared.cache_skipped + generator.cache_skipped,
+            },
+            processing_time_ms: processing_time,
+            model_metadata: prepared.model_metadata,
+        };
+
+        self.metrics.record_time(start_time.elapsed());
+        info!("Inference completed in {}ms", processing_time);
+        Ok(response)
+    }
+
+    /// Stream generated tokens as they are sampled. Runs the same salience,
+    /// cache and quantization pipeline as `process_inference`.
+    pub fn stream_inference(&self, request: InferenceRequest) -> impl Stream<Item = Result<StreamChunk>> + '_ {
+        let start_time = std::time::Instant::now();
+
+        futures::stream::try_unfold(StreamState::Pending(request), move |state| async move {
+            let mut generator = match state {
+                StreamState::Pending(request) => {
+                    debug!("Streaming inference request for model: {}", request.model_id);
+                    let prepared = self.prepare_inference(&request).await?;
+                    Box::new(TokenGenerator::new(&request, prepared.output_data))
+                }
+                StreamState::Generating(generator) => generator,
+            };
+
+            let index = generator.produced;
+            Ok(self.generate_step(&mut generator).await?.map(|(token_id, salience)| {
+                let chunk = StreamChunk {
+                    index,
+                    token_id,
+                    salience,
+                    elapsed_ms: start_time.elapsed().as_millis() as u64,
+                };
+                (chunk, StreamState::Generating(generator))
+            }))
+        })
+    }
+
+    /// Salience, cache lookup, quantization and cache update for the prompt
+    async fn prepare_inference(&self, request: &InferenceRequest) -> Result<PreparedInference> {
         // Get model metadata
         let model_metadata = {
             let models = self.models.read().await;
@@ -107,9 +326,10 @@ pub async fn process_inference(&self, request: InferenceRequest) -> Result<Infer
 
         // Step 1: Compute salience if requested
         let salience_scores = if request.compute_salience {
-            let mut salience_system = self.salience_system.write().await;
-            let results = salience_system.compute_salience(&request.input_tokens)?;
-            results.into_iter().map(|r| r.salience_score).collect()
+            let results = self.score_salience(request.input_tokens.clone()).await?;
+            let scores: Vec<f32> = results.into_iter().map(|r| r.salience_score).collect();
+            self.metrics.record_salience(&scores);
+            scores
         } else {
             vec![1.0; request.input_tokens.len()] // Default high salience
         };
@@ -120,8 +340,9 @@ pub async fn process_inference(&self, request: InferenceRequest) -> Result<Infer
         let mut cached_results = Vec::new();
 
         if request.use_cache {
-            for (i, &token) in request.input_tokens.iter().enumerate() {
-                match self.kv_cache.retrieve(token).await? {
+            let cached = self.kv_cache.retrieve_many(&request.input_tokens).await?;
+            for (i, value) in cached.into_iter().enumerate() {
+                match value {
                     Some(cached_value) => {
                         cached_results.push((i, cached_value));
                         cache_hits += 1;
@@ -134,30 +355,37 @@ pub async fn process_inference(&self, request: InferenceRequest) -> Result<Infer
         }
 
         // Step 3: Process uncached tokens through quantization
-        let mut output_data = vec![0.0; request.input_data.len()];
-        let quantizer = self.quantizer.read().await;
-        
         // Set salience weights for quantization
         let salience_weights: std::collections::HashMap<usize, f32> = request.input_tokens.iter()
             .enumerate()
             .map(|(i, _)| (i, salience_scores.get(i).copied().unwrap_or(1.0)))
             .collect();
 
-        let mut quantizer_mut = self.quantizer.write().await;
-        quantizer_mut.set_salience_weights(salience_weights);
-        drop(quantizer_mut);
-
-        let quantization_result = quantizer.quantize(&request.input_data)?;
+        // Quantize with a per-request copy so the shared quantizer is only locked
+        // long enough to clone it, and concurrent requests quantize in parallel
+        let mut quantizer = self.quantizer.read().await.clone();
+        quantizer.set_salience_weights(salience_weights);
+        let input_data = request.input_data.clone();
+        let quantization_result = run_blocking(move || quantizer.quantize(&input_data)).await??;
+        self.metrics.record_quantization(quantization_result.compression_ratio);
         
         // Dequantize for output
-        let dequantized = quantizer.dequantize(&quantization_result.quantized_data, &quantization_result.parameters);
-        output_data = dequantized;
+        let mut output_data = quantization_result.dequantized();
 
         // Step 4: Update cache with new results
+        let mut cache_stored = 0;
+        let mut cache_skipped = 0;
         if request.use_cache {
-            for (i, (&token, &value)) in request.input_tokens.iter().zip(output_data.iter()).enumerate() {
-                let salience = salience_scores.get(i).copied().unwrap_or(1.0);
-                self.kv_cache.store(token, value, salience).await?;
+            let items: Vec<(u32, f32, f32)> = request.input_tokens.iter()
+                .zip(output_data.iter())
+                .enumerate()
+                .map(|(i, (&token, &value))| (token, value, salience_scores.get(i).copied().unwrap_or(1.0)))
+                .collect();
+            for outcome in self.kv_cache.store_many(&items).await? {
+                match outcome {
+                    StoreOutcome::Stored => cache_stored += 1,
+                    StoreOutcome::Skipped => cache_skipped += 1,
+                }
             }
         }
 
@@ -168,77 +396,96 @@ pub async fn process_inference(&self, request: InferenceRequest) -> Result<Infer
             }
         }
 
-        // Generate output tokens (simplified transformation)
-        let output_tokens: Vec<u32> = request.input_tokens.iter()
-            .enumerate()
-            .map(|(i, &token)| {
-                let transform = (output_data.get(i).copied().unwrap_or(0.0) * 1000.0) as u32;
-                token.wrapping_add(transform % 100)
-            })
-            .collect();
+        Ok(PreparedInference {
+            model_metadata,
+            salience_scores,
+            output_data,
+            cache_hits,
+            cache_misses,
+            cache_stored,
+            cache_skipped,
+        })
+    }
 
-        let processing_time = start_time.elapsed().as_millis() as u64;
-        let cache_stats = self.kv_cache.get_stats();
+    /// Sample the next token, score its salience and write it to the cache.
+    /// Returns `None` once the generator is exhausted.
+    async fn generate_step(&self, generator: &mut TokenGenerator) -> Result<Option<(u32, f32)>> {
+        if generator.remaining == 0 || generator.logits.is_empty() {
+            return Ok(None);
+        }
 
-        let response = InferenceResponse {
-            output_tokens,
-            output_data,
-            salience_scores,
-            cache_stats: CacheStats {
-                hits: cache_hits,
-                misses: cache_misses,
-                hit_rate: if cache_hits + cache_misses > 0 {
-                    cache_hits as f32 / (cache_hits + cache_misses) as f32
-                } else {
-                    0.0
-                },
-                memory_usage_mb: cache_stats.memory_usage_bytes / (1024 * 1024),
-            },
-            processing_time_ms: processing_time,
-            model_metadata,
+        let token = sample_token_with_rng(&generator.logits, generator.temperature, generator.top_p, &mut generator.rng);
+        generator.remaining -= 1;
+        generator.produced += 1;
+
+        let salience = if generator.compute_salience {
+            self.score_salience(vec![token]).await?
+                .first()
+                .map_or(1.0, |result| result.salience_score)
+        } else {
+            1.0
         };
 
-        info!("Inference completed in {}ms", processing_time);
-        Ok(response)
+        if generator.use_cache {
+            match self.kv_cache.store(token, generator.logits[token as usize], salience).await? {
+                StoreOutcome::Stored => generator.cache_stored += 1,
+                StoreOutcome::Skipped => generator.cache_skipped += 1,
+            }
+        }
+
+        if generator.eos_token_id == Some(token) {
+            generator.remaining = 0;
+        } else {
+            // No transformer forward pass here: feeding the token back only
+            // penalizes it so the next step favors a different continuation
+            penalize_repeat(&mut generator.logits, token as usize);
+        }
+
+        Ok(Some((token, salience)))
+    }
+
+    /// Run the salience system over `tokens` on the blocking pool. The system is
+    /// stateful, so calls still take turns, but other requests keep making progress.
+    async fn score_salience(&self, tokens: Vec<u32>) -> Result<Vec<SalienceResult>> {
+        let mut salience_system = Arc::clone(&self.salience_system).write_owned().await;
+        Ok(run_blocking(move || salience_system.compute_salience(&tokens)).await??)
     }
 
     pub async fn batch_inference(&self, requests: Vec<InferenceRequest>) -> Result<Vec<InferenceResponse>> {
         info!("Processing batch of {} inference requests", requests.len());
-        
-        let mut responses = Vec::with_capacity(requests.len());
-        
-        // Process requests concurrently
-        let futures: Vec<_> = requests.into_iter()
+
+        // Process requests concurrently, at most worker_threads in flight;
+        // `buffered` yields responses in input order
+        let max_in_flight = self.config.runtime.worker_threads.max(1);
+        futures::stream::iter(requests)
             .map(|req| self.process_inference(req))
-            .collect();
-            
-        for future in futures {
-            responses.push(future.await?);
-        }
-        
-        Ok(responses)
+            .buffered(max_in_flight)
+            .try_collect()
+            .await
     }
 
     pub async fn get_processing_stats(&self) -> ProcessingStats {
         let cache_stats = self.kv_cache.get_stats();
-        let salience_state = {
-            let salience_system = self.salience_system.read().await;
-            salience_system.get_state().clone()
-        };
 
         ProcessingStats {
             tokens_processed: cache_stats.total_items,
             cache_hits: cache_stats.valid_blocks,
             cache_misses: cache_stats.total_blocks - cache_stats.valid_blocks,
-            quantization_ratio: 4.0, // Placeholder - would track actual ratio
-            avg_salience: salience_state.dopamine_level as f32,
-            processing_time_ms: 0, // Would track cumulative time
+            quantization_ratio: EngineMetrics::average(&self.metrics.compression_ratio_sum, &self.metrics.quantized_requests),
+            avg_salience: EngineMetrics::average(&self.metrics.salience_sum, &self.metrics.salience_samples),
+            processing_time_ms: self.metrics.processing_time_us.load(Ordering::Relaxed) / 1000,
         }
     }
 
-    pub async fn clear_cache(&self) -> Result<()> {
-        // Would need to implement clear method on UnifiedKVCache
-        info!("Cache clear requested");
+    /// Drop every KV cache entry; with `reset_salience` the salience
+    /// system's history and mesolimbic state are reset as well
+    pub async fn clear_cache(&self, reset_salience: bool) -> Result<()> {
+        info!("Clearing KV cache (reset salience: {})", reset_salience);
+        self.kv_cache.clear().await;
+
+        if reset_salience {
+            self.salience_system.write().await.reset();
+        }
         Ok(())
     }
 
@@ -254,6 +501,17 @@ pub async fn update_config(&mut self, config: ZetaConfig) -> Result<()> {
     }
 }
 
+/// Run CPU-bound work off the async workers so concurrent requests can overlap
+async fn run_blocking<T, F>(work: F) -> Result<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| ZetaError::Runtime(format!("Inference worker task failed: {}", e)))
+}
+
 /// Factory function for creating inference engines
 pub async fn create_inference_engine(config: ZetaConfig) -> Result<UnifiedInferenceEngine> {
     UnifiedInferenceEngine::new(config).await
@@ -275,7 +533,357 @@ pub async fn infer(
         top_p: None,
         use_cache: true,
         compute_salience: true,
+        seed: None,
+        eos_token_id: None,
     };
     
     engine.process_inference(request).await
 }
+
+/// Divisor applied to the logit of a token each time it is generated
+const REPETITION_PENALTY: f32 = 1.3;
+
+fn penalize_repeat(logits: &mut [f32], token: usize) {
+    if let Some(logit) = logits.get_mut(token) {
+        *logit = if *logit > 0.0 { *logit / REPETITION_PENALTY } else { *logit * REPETITION_PENALTY };
+    }
+}
+
+/// Sample a token index from `logits` with temperature scaling and optional
+/// nucleus (top-p) filtering. A temperature of zero or below picks the argmax.
+pub fn sample_token(logits: &[f32], temperature: f32, top_p: Option<f32>) -> u32 {
+    sample_token_with_rng(logits, temperature, top_p, &mut rand::thread_rng())
+}
+
+/// `sample_token` drawing from a caller-supplied RNG, for reproducible sampling
+pub fn sample_token_with_rng<R: Rng + ?Sized>(logits: &[f32], temperature: f32, top_p: Option<f32>, rng: &mut R) -> u32 {
+    if logits.is_empty() {
+        return 0;
+    }
+    if temperature <= f32::EPSILON {
+        return argmax(logits);
+    }
+
+    // Softmax over temperature-scaled logits, shifted by the max for stability
+    let max_logit = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut candidates: Vec<(u32, f32)> = logits.iter()
+        .enumerate()
+        .map(|(i, &logit)| (i as u32, ((logit - max_logit) / temperature).exp()))
+        .collect();
+    let total: f32 = candidates.iter().map(|&(_, p)| p).sum();
+    if !total.is_finite() || total <= 0.0 {
+        return argmax(logits);
+    }
+    candidates.iter_mut().for_each(|(_, p)| *p /= total);
+
+    // Keep the smallest set of most likely tokens whose mass reaches top_p
+    if let Some(top_p) = top_p.filter(|&p| p > 0.0 && p < 1.0) {
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let mut cumulative = 0.0;
+        let keep = candidates.iter()
+            .position(|&(_, p)| {
+                cumulative += p;
+                cumulative >= top_p
+            })
+            .map_or(candidates.len(), |i| i + 1);
+        candidates.truncate(keep);
+    }
+
+    let mass: f32 = candidates.iter().map(|&(_, p)| p).sum();
+    let mut threshold = rng.gen::<f32>() * mass;
+    for &(token, p) in &candidates {
+        if threshold < p {
+            return token;
+        }
+        threshold -= p;
+    }
+    candidates.last().map_or(0, |&(token, _)| token)
+}
+
+fn argmax(logits: &[f32]) -> u32 {
+    logits.iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map_or(0, |(i, _)| i as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use zeta_shared::PrecisionLevel;
+
+    async fn test_engine() -> UnifiedInferenceEngine {
+        let engine = UnifiedInferenceEngine::new(ZetaConfig::default()).await.unwrap();
+        engine.register_model(ModelMetadata {
+            name: "test-model".to_string(),
+            version: "1".to_string(),
+            architecture: "test".to_string(),
+            parameters: 0,
+            precision: PrecisionLevel::Int8,
+            created_at: String::new(),
+            sha256: String::new(),
+        }).await.unwrap();
+        engine
+    }
+
+    fn test_request(max_tokens: usize) -> InferenceRequest {
+        let mut input_data: Vec<f32> = (0..16).map(|i| (i % 5) as f32 * 0.1).collect();
+        input_data[9] = 4.0;
+        InferenceRequest {
+            model_id: "test-model".to_string(),
+            input_tokens: (0..16).collect(),
+            input_data,
+            max_tokens: Some(max_tokens),
+            temperature: Some(0.7),
+            top_p: None,
+            use_cache: true,
+            compute_salience: false,
+            seed: Some(3),
+            eos_token_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_honors_max_tokens() {
+        let engine = test_engine().await;
+        let response = engine.process_inference(test_request(5)).await.unwrap();
+        assert_eq!(response.output_tokens.len(), 5);
+        assert!(response.output_tokens.iter().all(|&t| t < 16));
+    }
+
+    #[tokio::test]
+    async fn test_generation_stops_at_eos() {
+        let engine = test_engine().await;
+        let request = InferenceRequest {
+            temperature: Some(0.0),
+            eos_token_id: Some(9),
+            ..test_request(5)
+        };
+        let response = engine.process_inference(request).await.unwrap();
+        assert_eq!(response.output_tokens, vec![9]);
+    }
+
+    #[test]
+    fn test_zero_temperature_is_argmax() {
+        let logits = [0.1, 2.5, -1.0, 2.4, 0.0];
+        for _ in 0..20 {
+            assert_eq!(sample_token(&logits, 0.0, None), 1);
+            assert_eq!(sample_token(&logits, 0.0, Some(0.5)), 1);
+        }
+    }
+
+    #[test]
+    fn test_seeded_sampling_is_reproducible() {
+        let logits: Vec<f32> = (0..64).map(|i| ((i * 37) % 11) as f32 * 0.3).collect();
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..32).map(|_| sample_token_with_rng(&logits, 0.8, Some(1.0), &mut rng)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(draw(7), draw(7));
+        assert!(draw(7).iter().any(|&t| t != draw(7)[0]), "sampling should not collapse to one token");
+    }
+
+    #[test]
+    fn test_top_p_restricts_to_nucleus() {
+        // Token 2 alone holds well over half of the mass
+        let logits = [0.0, 0.0, 5.0, 0.0];
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            assert_eq!(sample_token_with_rng(&logits, 1.0, Some(0.5), &mut rng), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_inference_preserves_order() {
+        let engine = test_engine().await;
+        let requests: Vec<InferenceRequest> = (0..12u64)
+            .map(|seed| InferenceRequest {
+                input_tokens: (0..16).map(|t| t + seed as u32 * 100).collect(),
+                seed: Some(seed),
+                use_cache: false,
+                ..test_request(4)
+            })
+            .collect();
+
+        let mut expected = Vec::new();
+        for request in requests.clone() {
+            expected.push(engine.process_inference(request).await.unwrap().output_tokens);
+        }
+
+        let responses = engine.batch_inference(requests).await.unwrap();
+        let actual: Vec<Vec<u32>> = responses.into_iter().map(|r| r.output_tokens).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_batch_requests_overlap() {
+        // The first request's salience stage stalls until the other three have been
+        // quantized, which can only happen if they run while it is in flight
+        let engine = Arc::new(test_engine().await);
+        let stalled = Arc::new(AtomicBool::new(false));
+        let released = Arc::new(AtomicBool::new(false));
+        {
+            let (stalled, released) = (Arc::clone(&stalled), Arc::clone(&released));
+            engine.salience_system.write().await.set_on_state_change(move |_| {
+                stalled.store(true, Ordering::Release);
+                let start = std::time::Instant::now();
+                while !released.load(Ordering::Acquire) && start.elapsed() < Duration::from_secs(5) {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            });
+        }
+
+        let mut requests = vec![InferenceRequest { compute_salience: true, ..test_request(1) }];
+        requests.extend((1..4u32).map(|i| InferenceRequest {
+            input_tokens: (0..16).map(|t| t + i * 100).collect(),
+            ..test_request(1)
+        }));
+        let batch = tokio::spawn({
+            let engine = Arc::clone(&engine);
+            async move { engine.batch_inference(requests).await }
+        });
+
+        let others_quantized = async {
+            while engine.metrics.quantized_requests.load(Ordering::Relaxed) < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        let overlapped = tokio::time::timeout(Duration::from_secs(2), others_quantized).await.is_ok();
+        released.store(true, Ordering::Release);
+
+        let responses = batch.await.unwrap().unwrap();
+        assert!(stalled.load(Ordering::Acquire));
+        assert!(overlapped, "requests ran one at a time behind the stalled one");
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0].salience_scores.len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_stream_matches_process_inference() {
+        let request = InferenceRequest {
+            compute_salience: true,
+            ..test_request(6)
+        };
+        let expected = test_engine().await.process_inference(request.clone()).await.unwrap();
+
+        let engine = test_engine().await;
+        let chunks: Vec<StreamChunk> = engine.stream_inference(request).try_collect().await.unwrap();
+        let tokens: Vec<u32> = chunks.iter().map(|chunk| chunk.token_id).collect();
+        assert_eq!(tokens, expected.output_tokens);
+        assert!(chunks.iter().enumerate().all(|(i, chunk)| chunk.index == i));
+        assert!(chunks.windows(2).all(|pair| pair[0].elapsed_ms <= pair[1].elapsed_ms));
+    }
+
+    #[tokio::test]
+    async fn test_clear_cache() {
+        let engine = test_engine().await;
+        engine.process_inference(InferenceRequest {
+            compute_salience: true,
+            ..test_request(3)
+        }).await.unwrap();
+        assert!(engine.get_processing_stats().await.tokens_processed > 0);
+
+        engine.clear_cache(false).await.unwrap();
+        assert_eq!(engine.get_processing_stats().await.tokens_processed, 0);
+        assert_ne!(engine.salience_system.read().await.get_state().dopamine_level, 0.5);
+
+        engine.clear_cache(true).await.unwrap();
+        assert_eq!(engine.salience_system.read().await.get_state().dopamine_level, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_inference_timeout() {
+        let mut config = ZetaConfig::default();
+        config.runtime.timeout_seconds = 1;
+        let engine = UnifiedInferenceEngine::new(config).await.unwrap();
+        engine.models.write().await.extend(test_engine().await.models.read().await.clone());
+
+        // Holding the quantizer lock stalls the request past its deadline
+        let stall = engine.quantizer.write().await;
+        let err = engine.process_inference(test_request(2)).await.unwrap_err();
+        assert!(matches!(err, ZetaError::Runtime(ref msg) if msg == "inference timed out"), "{}", err);
+        drop(stall);
+
+        // The timed-out request released everything it held
+        assert_eq!(engine.process_inference(test_request(2)).await.unwrap().output_tokens.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_processing_stats_accumulate() {
+        let engine = test_engine().await;
+        let mut previous = engine.get_processing_stats().await;
+        let mut previous_us = engine.metrics.processing_time_us.load(Ordering::Relaxed);
+        assert_eq!(previous.processing_time_ms, 0);
+
+        for seed in 0..4 {
+            engine.process_inference(InferenceRequest {
+                input_tokens: (0..128).collect(),
+                input_data: (0..128).map(|i| (i % 7) as f32).collect(),
+                compute_salience: true,
+                seed: Some(seed),
+                ..test_request(2)
+            }).await.unwrap();
+
+            // Requests can finish within a millisecond, so compare the microsecond counter
+            let stats = engine.get_processing_stats().await;
+            let elapsed_us = engine.metrics.processing_time_us.load(Ordering::Relaxed);
+            assert!(elapsed_us > previous_us);
+            assert_eq!(stats.processing_time_ms, elapsed_us / 1000);
+            previous = stats;
+            previous_us = elapsed_us;
+        }
+
+        // Default Int4 quantization packs 8 values per f32
+        assert!((previous.quantization_ratio - 8.0).abs() < 1e-3, "{}", previous.quantization_ratio);
+        assert!(previous.avg_salience > 0.0 && previous.avg_salience <= 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_register_model_from_safetensors() {
+        // Two F16 tensors: an 8x4 embedding and a 4-element bias
+        let header = r#"{"embed":{"dtype":"F16","shape":[8,4],"data_offsets":[0,64]},"bias":{"dtype":"F16","shape":[4],"data_offsets":[64,72]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&[0u8; 72]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny-llama.safetensors");
+        std::fs::write(&path, bytes).unwrap();
+        std::fs::write(dir.path().join("config.json"), r#"{"architectures":["LlamaForCausalLM"]}"#).unwrap();
+
+        let engine = UnifiedInferenceEngine::new(ZetaConfig::default()).await.unwrap();
+        let metadata = engine.register_model_from_path(&path).await.unwrap();
+        assert_eq!(metadata.name, "tiny-llama");
+        assert_eq!(metadata.parameters, 36);
+        assert_eq!(metadata.architecture, "LlamaForCausalLM");
+        assert_eq!(metadata.precision, PrecisionLevel::FP16);
+        assert!(engine.models.read().await.contains_key("tiny-llama"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_integrity_detects_corruption() {
+        let header = r#"{"weight":{"dtype":"F32","shape":[4],"data_offsets":[0,16]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend(1.5f32.to_le_bytes().repeat(4));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.safetensors");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let engine = UnifiedInferenceEngine::new(ZetaConfig::default()).await.unwrap();
+        let metadata = engine.register_model_from_path(&path).await.unwrap();
+        assert_eq!(metadata.sha256.len(), 64);
+        metadata.verify_integrity(&path).unwrap();
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(metadata.verify_integrity(&path), Err(ZetaError::Integrity(_))));
+
+        let unregistered = ModelMetadata { sha256: String::new(), ..metadata };
+        assert!(matches!(unregistered.verify_integrity(&path), Err(ZetaError::Integrity(_))));
+    }
+}
@@ -0,0 +1,612 @@
+// Copyright 2025 ZETA RETICULA INC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Model file inspection for engine registration
+//!
+//! Reads only the headers of safetensors and GGUF weight files, plus Hugging
+//! Face `config.json` files, following the format detection and sibling-config
+//! conventions of `unified-input`'s `ModelLoader`.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use half::{bf16, f16};
+use serde_json::Value;
+use zeta_shared::{sha256_file, ModelMetadata, PrecisionLevel, Result, ZetaError};
+
+/// Upper bound on header and string lengths, to reject corrupt files early
+const MAX_HEADER_BYTES: u64 = 100 * 1024 * 1024;
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+const GGUF_DEFAULT_ALIGNMENT: u64 = 32;
+
+/// Facts gathered from a model file before they become `ModelMetadata`
+#[derive(Debug, Default)]
+struct ModelSummary {
+    parameters: u64,
+    architecture: Option<String>,
+    version: Option<String>,
+    precision: Option<PrecisionLevel>,
+}
+
+/// Parameter counts per precision, used to pick the dominant weight precision
+#[derive(Debug, Default)]
+struct PrecisionTally(Vec<(PrecisionLevel, u64)>);
+
+impl PrecisionTally {
+    fn add(&mut self, precision: PrecisionLevel, count: u64) {
+        match self.0.iter_mut().find(|(p, _)| *p == precision) {
+            Some((_, total)) => *total += count,
+            None => self.0.push((precision, count)),
+        }
+    }
+
+    fn dominant(self) -> Option<PrecisionLevel> {
+        self.0.into_iter().max_by_key(|(_, count)| *count).map(|(p, _)| p)
+    }
+}
+
+/// How a tensor's elements are stored on disk
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TensorKind {
+    F64,
+    F32,
+    F16,
+    BF16,
+    /// Index or mask buffers that carry no weights
+    Integer,
+    /// Block-quantized or 8-bit float weights
+    Quantized,
+}
+
+impl TensorKind {
+    fn float_width(self) -> Option<u64> {
+        match self {
+            TensorKind::F64 => Some(8),
+            TensorKind::F32 => Some(4),
+            TensorKind::F16 | TensorKind::BF16 => Some(2),
+            TensorKind::Integer | TensorKind::Quantized => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TensorEntry {
+    name: String,
+    count: u64,
+    kind: TensorKind,
+    precision: Option<PrecisionLevel>,
+    /// Absolute byte offset of the tensor data in the file
+    offset: u64,
+}
+
+/// Everything a weight file's header says about the model
+#[derive(Debug, Default)]
+struct WeightHeader {
+    architecture: Option<String>,
+    version: Option<String>,
+    tensors: Vec<TensorEntry>,
+}
+
+impl WeightHeader {
+    fn summary(&self) -> ModelSummary {
+        let mut tally = PrecisionTally::default();
+        for tensor in &self.tensors {
+            if let Some(precision) = &tensor.precision {
+                tally.add(precision.clone(), tensor.count);
+            }
+        }
+        ModelSummary {
+            parameters: self.tensors.iter().map(|t| t.count).sum(),
+            architecture: self.architecture.clone(),
+            version: self.version.clone(),
+            precision: tally.dominant(),
+        }
+    }
+}
+
+/// Build `ModelMetadata` for a `.safetensors`, `.gguf` or `.json` model file
+pub(crate) fn read_model_metadata(path: &Path) -> Result<ModelMetadata> {
+    let extension = model_extension(path);
+    let mut summary = match extension.as_deref() {
+        Some("safetensors") | Some("gguf") => read_weight_header(path)?.summary(),
+        Some("json") => read_config_file(path)?,
+        _ => return Err(unsupported(path)),
+    };
+
+    // Weight files rarely carry the architecture; fall back to config.json beside them
+    if summary.architecture.is_none() && extension.as_deref() != Some("json") {
+        if let Some(config) = load_sibling_config(path)? {
+            summary.architecture = config_architecture(&config);
+        }
+    }
+
+    let name = path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("model")
+        .to_string();
+
+    Ok(ModelMetadata {
+        name,
+        version: summary.version.unwrap_or_else(|| "unknown".to_string()),
+        architecture: summary.architecture.unwrap_or_else(|| "unknown".to_string()),
+        parameters: summary.parameters,
+        precision: summary.precision.unwrap_or(PrecisionLevel::FP32),
+        created_at: modified_unix_secs(path).map(|secs| secs.to_string()).unwrap_or_default(),
+        sha256: sha256_file(path)?,
+    })
+}
+
+/// Read every floating point tensor of a `.safetensors` or `.gguf` file as f32,
+/// concatenated in file order. Integer buffers are skipped; weights that are
+/// already block-quantized are rejected.
+pub fn load_model_weights(path: &Path) -> Result<Vec<f32>> {
+    Ok(load_model_tensors(path)?.into_iter().flat_map(|(_, values)| values).collect())
+}
+
+/// Read every floating point tensor of a `.safetensors` or `.gguf` file as
+/// `(name, values)` pairs in file order, with the same rules as `load_model_weights`
+pub fn load_model_tensors(path: &Path) -> Result<Vec<(String, Vec<f32>)>> {
+    let header = read_weight_header(path)?;
+    let mut reader = open(path)?;
+    let mut tensors = Vec::new();
+
+    for tensor in &header.tensors {
+        let width = match tensor.kind.float_width() {
+            Some(width) => width,
+            None if tensor.kind == TensorKind::Integer => continue,
+            None => {
+                return Err(invalid(path, &format!(
+                    "tensor '{}' is already quantized", tensor.name
+                )))
+            }
+        };
+
+        let len = tensor.count.checked_mul(width)
+            .filter(|len| *len <= usize::MAX as u64)
+            .ok_or_else(|| invalid(path, &format!("tensor '{}' is too large", tensor.name)))?;
+        let mut bytes = vec![0u8; len as usize];
+        reader.seek(SeekFrom::Start(tensor.offset)).map_err(|e| io_error(path, e))?;
+        reader.read_exact(&mut bytes).map_err(|e| io_error(path, e))?;
+
+        let values: Vec<f32> = match tensor.kind {
+            TensorKind::F64 => bytes.chunks_exact(8)
+                .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32)
+                .collect(),
+            TensorKind::F32 => bytes.chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            TensorKind::F16 => bytes.chunks_exact(2)
+                .map(|b| f16::from_le_bytes([b[0], b[1]]).to_f32())
+                .collect(),
+            TensorKind::BF16 => bytes.chunks_exact(2)
+                .map(|b| bf16::from_le_bytes([b[0], b[1]]).to_f32())
+                .collect(),
+            TensorKind::Integer | TensorKind::Quantized => unreachable!(),
+        };
+        tensors.push((tensor.name.clone(), values));
+    }
+
+    if tensors.iter().all(|(_, values)| values.is_empty()) {
+        return Err(invalid(path, "no floating point tensors"));
+    }
+    Ok(tensors)
+}
+
+fn model_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
+fn read_weight_header(path: &Path) -> Result<WeightHeader> {
+    match model_extension(path).as_deref() {
+        Some("safetensors") => read_safetensors_header(path),
+        Some("gguf") => read_gguf_header(path),
+        _ => Err(unsupported(path)),
+    }
+}
+
+fn read_safetensors_header(path: &Path) -> Result<WeightHeader> {
+    let mut reader = open(path)?;
+    let header_len = read_u64(&mut reader, path)?;
+    if header_len > MAX_HEADER_BYTES {
+        return Err(invalid(path, "safetensors header too large"));
+    }
+
+    let mut header = vec![0u8; header_len as usize];
+    reader.read_exact(&mut header).map_err(|e| io_error(path, e))?;
+    let header: serde_json::Map<String, Value> = serde_json::from_slice(&header)
+        .map_err(|e| invalid(path, &format!("bad safetensors header: {}", e)))?;
+    let data_start = 8 + header_len;
+
+    let mut parsed = WeightHeader::default();
+    for (name, entry) in &header {
+        if name == "__metadata__" {
+            if let Some(metadata) = entry.as_object() {
+                parsed.architecture = ["architecture", "model_type"].iter()
+                    .find_map(|key| metadata.get(*key).and_then(Value::as_str))
+                    .map(str::to_string);
+                parsed.version = metadata.get("version").and_then(Value::as_str).map(str::to_string);
+            }
+            continue;
+        }
+
+        let bad_entry = || invalid(path, &format!("tensor '{}' has a bad header entry", name));
+        let count = entry.get("shape")
+            .and_then(Value::as_array)
+            .ok_or_else(bad_entry)?
+            .iter()
+            .map(|dim| dim.as_u64().ok_or_else(bad_entry))
+            .product::<Result<u64>>()?;
+        let dtype = entry.get("dtype").and_then(Value::as_str).ok_or_else(bad_entry)?;
+        let begin = entry.get("data_offsets")
+            .and_then(|offsets| offsets.get(0))
+            .and_then(Value::as_u64)
+            .ok_or_else(bad_entry)?;
+
+        let (kind, precision) = safetensors_dtype(dtype);
+        parsed.tensors.push(TensorEntry {
+            name: name.clone(),
+            count,
+            kind,
+            precision,
+            offset: data_start + begin,
+        });
+    }
+    parsed.tensors.sort_by_key(|tensor| tensor.offset);
+    Ok(parsed)
+}
+
+fn safetensors_dtype(dtype: &str) -> (TensorKind, Option<PrecisionLevel>) {
+    match dtype {
+        "F64" => (TensorKind::F64, Some(PrecisionLevel::FP32)),
+        "F32" => (TensorKind::F32, Some(PrecisionLevel::FP32)),
+        "F16" => (TensorKind::F16, Some(PrecisionLevel::FP16)),
+        "BF16" => (TensorKind::BF16, Some(PrecisionLevel::FP16)),
+        "F8_E4M3" | "F8_E5M2" => (TensorKind::Quantized, Some(PrecisionLevel::Int8)),
+        "I64" | "U64" | "I32" | "U32" => (TensorKind::Integer, Some(PrecisionLevel::FP32)),
+        "I16" | "U16" => (TensorKind::Integer, Some(PrecisionLevel::FP16)),
+        "I8" | "U8" => (TensorKind::Integer, Some(PrecisionLevel::Int8)),
+        "BOOL" => (TensorKind::Integer, Some(PrecisionLevel::Int1)),
+        _ => (TensorKind::Integer, None),
+    }
+}
+
+/// A GGUF metadata value, keeping only the variants the loader reads
+enum GgufValue {
+    Str(String),
+    Uint(u64),
+    Other,
+}
+
+fn read_gguf_header(path: &Path) -> Result<WeightHeader> {
+    let mut reader = open(path)?;
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(|e| io_error(path, e))?;
+    if &magic != GGUF_MAGIC {
+        return Err(invalid(path, "missing GGUF magic"));
+    }
+    let version = read_u32(&mut reader, path)?;
+    if version < 2 {
+        return Err(invalid(path, &format!("GGUF version {} is not supported", version)));
+    }
+
+    let tensor_count = read_u64(&mut reader, path)?;
+    let kv_count = read_u64(&mut reader, path)?;
+
+    let mut parsed = WeightHeader::default();
+    let mut alignment = GGUF_DEFAULT_ALIGNMENT;
+    for _ in 0..kv_count {
+        let key = read_gguf_string(&mut reader, path)?;
+        let value_type = read_u32(&mut reader, path)?;
+        match (key.as_str(), read_gguf_value(&mut reader, path, value_type)?) {
+            ("general.architecture", GgufValue::Str(value)) => parsed.architecture = Some(value),
+            ("general.version", GgufValue::Str(value)) => parsed.version = Some(value),
+            ("general.alignment", GgufValue::Uint(value)) if value > 0 => alignment = value,
+            _ => {}
+        }
+    }
+
+    for _ in 0..tensor_count {
+        let name = read_gguf_string(&mut reader, path)?;
+        let n_dims = read_u32(&mut reader, path)?;
+        let mut count = 1u64;
+        for _ in 0..n_dims {
+            count = count.saturating_mul(read_u64(&mut reader, path)?);
+        }
+        let tensor_type = read_u32(&mut reader, path)?;
+        let offset = read_u64(&mut reader, path)?;
+
+        parsed.tensors.push(TensorEntry {
+            name,
+            count,
+            kind: ggml_kind(tensor_type),
+            precision: ggml_precision(tensor_type),
+            offset,
+        });
+    }
+
+    // Tensor offsets are relative to the aligned start of the data section
+    let header_end = reader.stream_position().map_err(|e| io_error(path, e))?;
+    let data_start = (header_end + alignment - 1) / alignment * alignment;
+    for tensor in &mut parsed.tensors {
+        tensor.offset += data_start;
+    }
+    parsed.tensors.sort_by_key(|tensor| tensor.offset);
+    Ok(parsed)
+}
+
+/// Read one GGUF metadata value, keeping strings and unsigned integers
+fn read_gguf_value<R: Read>(reader: &mut R, path: &Path, value_type: u32) -> Result<GgufValue> {
+    let width = match value_type {
+        0 | 1 | 7 => 1,  // u8, i8, bool
+        2 | 3 => 2,      // u16, i16
+        4..=6 => 4,      // u32, i32, f32
+        10..=12 => 8,    // u64, i64, f64
+        8 => return read_gguf_string(reader, path).map(GgufValue::Str),
+        9 => {
+            let element_type = read_u32(reader, path)?;
+            let len = read_u64(reader, path)?;
+            for _ in 0..len {
+                read_gguf_value(reader, path, element_type)?;
+            }
+            return Ok(GgufValue::Other);
+        }
+        other => return Err(invalid(path, &format!("unknown GGUF value type {}", other))),
+    };
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf[..width]).map_err(|e| io_error(path, e))?;
+    Ok(match value_type {
+        0 | 2 | 4 | 10 => GgufValue::Uint(u64::from_le_bytes(buf)),
+        _ => GgufValue::Other,
+    })
+}
+
+fn read_gguf_string<R: Read>(reader: &mut R, path: &Path) -> Result<String> {
+    let len = read_u64(reader, path)?;
+    if len > MAX_HEADER_BYTES {
+        return Err(invalid(path, "GGUF string too long"));
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes).map_err(|e| io_error(path, e))?;
+    String::from_utf8(bytes).map_err(|_| invalid(path, "GGUF string is not UTF-8"))
+}
+
+fn ggml_kind(tensor_type: u32) -> TensorKind {
+    match tensor_type {
+        0 => TensorKind::F32,
+        1 => TensorKind::F16,
+        28 => TensorKind::F64,
+        30 => TensorKind::BF16,
+        24..=27 => TensorKind::Integer,
+        _ => TensorKind::Quantized,
+    }
+}
+
+/// Map a ggml tensor type to the narrowest precision level that holds it
+fn ggml_precision(tensor_type: u32) -> Option<PrecisionLevel> {
+    match tensor_type {
+        0 | 26..=28 => Some(PrecisionLevel::FP32),                  // F32, I32, I64, F64
+        1 | 25 | 30 => Some(PrecisionLevel::FP16),                  // F16, I16, BF16
+        6..=9 | 13..=15 | 24 => Some(PrecisionLevel::Int8),         // Q5_x, Q8_x, Q5_K, Q6_K, Q8_K, I8
+        2 | 3 | 11 | 12 | 18 | 20 | 21 | 23 => Some(PrecisionLevel::Int4), // Q4_x, Q3_K, Q4_K, IQ3, IQ4
+        10 | 16 | 17 | 19 | 22 | 29 => Some(PrecisionLevel::Int2),  // Q2_K, IQ2, IQ1
+        _ => None,
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<ModelSummary> {
+    let config = read_json(path)?;
+    let parameters = estimate_parameters(&config)
+        .ok_or_else(|| invalid(path, "config has no parameter count or model dimensions to estimate it from"))?;
+
+    Ok(ModelSummary {
+        parameters,
+        architecture: config_architecture(&config),
+        version: config.get("transformers_version").and_then(Value::as_str).map(str::to_string),
+        precision: config.get("torch_dtype").and_then(Value::as_str).map(|dtype| match dtype {
+            "float16" | "bfloat16" => PrecisionLevel::FP16,
+            "int8" | "uint8" => PrecisionLevel::Int8,
+            _ => PrecisionLevel::FP32,
+        }),
+    })
+}
+
+/// Use `num_parameters` when present, otherwise estimate a decoder-only
+/// transformer: embeddings plus 4h² attention and 2·h·ffn MLP weights per layer.
+/// Dimensions too large for the count to fit in a u64 give `None`.
+fn estimate_parameters(config: &Value) -> Option<u64> {
+    if let Some(count) = config.get("num_parameters").and_then(Value::as_u64) {
+        return Some(count);
+    }
+
+    let field = |keys: &[&str]| keys.iter().find_map(|key| config.get(*key).and_then(Value::as_u64));
+    let hidden = field(&["hidden_size", "n_embd", "d_model"])?;
+    let layers = field(&["num_hidden_layers", "n_layer", "num_layers"])?;
+    let vocab = field(&["vocab_size"]).unwrap_or(0);
+    let ffn = match field(&["intermediate_size", "n_inner"]) {
+        Some(ffn) => ffn,
+        None => hidden.checked_mul(4)?,
+    };
+
+    let attention = hidden.checked_mul(hidden)?.checked_mul(4)?;
+    let mlp = hidden.checked_mul(ffn)?.checked_mul(2)?;
+    let per_layer = attention.checked_add(mlp)?;
+    vocab.checked_mul(hidden)?.checked_add(layers.checked_mul(per_layer)?)
+}
+
+fn config_architecture(config: &Value) -> Option<String> {
+    config.get("architectures")
+        .and_then(Value::as_array)
+        .and_then(|archs| archs.first())
+        .and_then(Value::as_str)
+        .or_else(|| config.get("model_type").and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn load_sibling_config(path: &Path) -> Result<Option<Value>> {
+    let config_path = path.with_file_name("config.json");
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    read_json(&config_path).map(Some)
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let contents = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    serde_json::from_slice(&contents).map_err(|e| invalid(path, &format!("bad JSON: {}", e)))
+}
+
+fn modified_unix_secs(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    File::open(path).map(BufReader::new).map_err(|e| io_error(path, e))
+}
+
+fn read_u32<R: Read>(reader: &mut R, path: &Path) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(|e| io_error(path, e))?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R, path: &Path) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).map_err(|e| io_error(path, e))?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> ZetaError {
+    ZetaError::Runtime(format!("Failed to read {}: {}", path.display(), err))
+}
+
+fn unsupported(path: &Path) -> ZetaError {
+    ZetaError::Config(format!("Unsupported model format: {}", path.display()))
+}
+
+fn invalid(path: &Path, reason: &str) -> ZetaError {
+    ZetaError::Config(format!("Invalid model file {}: {}", path.display(), reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gguf_string(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    #[test]
+    fn test_gguf_header() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(GGUF_MAGIC);
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&2u64.to_le_bytes()); // tensors
+        bytes.extend_from_slice(&2u64.to_le_bytes()); // metadata entries
+
+        gguf_string(&mut bytes, "general.architecture");
+        bytes.extend_from_slice(&8u32.to_le_bytes());
+        gguf_string(&mut bytes, "llama");
+        gguf_string(&mut bytes, "llama.context_length");
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        bytes.extend_from_slice(&2048u32.to_le_bytes());
+
+        for (name, dims, tensor_type) in [("tok_embd", [32u64, 8], 8u32), ("norm", [8, 1], 0)] {
+            gguf_string(&mut bytes, name);
+            bytes.extend_from_slice(&2u32.to_le_bytes());
+            for dim in dims {
+                bytes.extend_from_slice(&dim.to_le_bytes());
+            }
+            bytes.extend_from_slice(&tensor_type.to_le_bytes());
+            bytes.extend_from_slice(&0u64.to_le_bytes());
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        std::fs::write(&path, bytes).unwrap();
+
+        let metadata = read_model_metadata(&path).unwrap();
+        assert_eq!(metadata.name, "tiny");
+        assert_eq!(metadata.architecture, "llama");
+        assert_eq!(metadata.parameters, 32 * 8 + 8);
+        assert_eq!(metadata.precision, PrecisionLevel::Int8);
+    }
+
+    #[test]
+    fn test_gguf_weights_respect_alignment() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(GGUF_MAGIC);
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&2u64.to_le_bytes());
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        gguf_string(&mut bytes, "general.alignment");
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        bytes.extend_from_slice(&64u32.to_le_bytes());
+
+        // F16 tensor stored after the F32 one, listed first
+        for (name, len, tensor_type, offset) in [("b", 2u64, 1u32, 64u64), ("a", 3, 0, 0)] {
+            gguf_string(&mut bytes, name);
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.extend_from_slice(&len.to_le_bytes());
+            bytes.extend_from_slice(&tensor_type.to_le_bytes());
+            bytes.extend_from_slice(&offset.to_le_bytes());
+        }
+        bytes.resize((bytes.len() + 63) / 64 * 64, 0);
+        let data_start = bytes.len();
+        for value in [1.0f32, -2.5, 0.125] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.resize(data_start + 64, 0);
+        for value in [0.5f32, -4.0] {
+            bytes.extend_from_slice(&f16::from_f32(value).to_le_bytes());
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("weights.gguf");
+        std::fs::write(&path, bytes).unwrap();
+
+        assert_eq!(load_model_weights(&path).unwrap(), vec![1.0, -2.5, 0.125, 0.5, -4.0]);
+    }
+
+    #[test]
+    fn test_config_parameter_estimate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{
+            "model_type": "gpt2", "vocab_size": 100, "n_embd": 16, "n_layer": 2,
+            "torch_dtype": "float16"
+        }"#).unwrap();
+
+        let metadata = read_model_metadata(&path).unwrap();
+        assert_eq!(metadata.architecture, "gpt2");
+        assert_eq!(metadata.parameters, 100 * 16 + 2 * (4 * 16 * 16 + 2 * 16 * 64));
+        assert_eq!(metadata.precision, PrecisionLevel::FP16);
+
+        std::fs::write(&path, r#"{"vocab_size": 100, "n_embd": 4294967296, "n_layer": 2}"#).unwrap();
+        assert!(matches!(read_model_metadata(&path), Err(ZetaError::Config(_))));
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(matches!(
+            read_model_metadata(Path::new("model.bin")),
+            Err(ZetaError::Config(_))
+        ));
+    }
+}
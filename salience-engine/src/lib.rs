@@ -33,7 +33,7 @@
 #[cfg(feature = "server")]
 use actix_web::{web, App, HttpServer, Responder, HttpResponse};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use log::info;
 
@@ -59,12 +59,89 @@ pub struct SalienceResponse {
     pub upgrade_prompt: Option<String>,
 }
 
+/// Function words that carry no salience on their own
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "had", "has",
+    "have", "he", "her", "his", "i", "in", "is", "it", "its", "of", "on", "or", "she",
+    "so", "that", "the", "their", "them", "they", "this", "to", "was", "we", "were",
+    "will", "with", "you",
+];
+
+/// Split text into words and derive the `TokenFeatures` `SalienceQuantizer` expects
+///
+/// Token ids are word positions, so quantization results map back to `words[token_id]`.
+/// Frequency is the word's count relative to the most common word; context relevance
+/// favours longer words and is zero for stopwords. No sentiment model is applied.
+pub fn tokenize_text(text: &str) -> (Vec<String>, Vec<TokenFeatures>) {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in &words {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    let max_count = counts.values().copied().max().unwrap_or(1) as f32;
+
+    let features = words
+        .iter()
+        .enumerate()
+        .map(|(position, word)| {
+            let lower = word.to_lowercase();
+            let context_relevance = if STOPWORDS.contains(&lower.as_str()) {
+                0.0
+            } else {
+                (word.chars().count() as f32 / 8.0).min(1.0)
+            };
+            TokenFeatures {
+                token_id: position as u32,
+                frequency: counts[&lower] as f32 / max_count,
+                sentiment_score: 1.0,
+                context_relevance,
+                role: "word".to_string(),
+            }
+        })
+        .collect();
+
+    (words, features)
+}
+
+/// Return the `k` distinct words of `text` with the highest `SalienceQuantizer` scores
+///
+/// Ties keep their order of appearance in the text.
+pub fn extract_salient_phrases(text: &str, k: usize) -> Vec<String> {
+    let (words, features) = tokenize_text(text);
+    let quantizer = SalienceQuantizer::new(0.0);
+    let (mut scored, _, _) = quantizer.quantize_tokens(features, "default", &Bump::new());
+
+    scored.sort_by(|a, b| {
+        b.salience_score
+            .total_cmp(&a.salience_score)
+            .then(a.token_id.cmp(&b.token_id))
+    });
+
+    let mut seen = HashSet::new();
+    scored
+        .iter()
+        .map(|result| &words[result.token_id as usize])
+        .filter(|word| seen.insert(word.to_lowercase()))
+        .take(k)
+        .cloned()
+        .collect()
+}
+
 /// Usage tracker for salience engine
 #[cfg(feature = "server")]
 lazy_static::lazy_static! {
     static ref USAGE_TRACKER: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
 }
 
+/// Number of salient phrases returned per `/api/salience` request
+#[cfg(feature = "server")]
+const SALIENT_PHRASE_COUNT: usize = 3;
+
 /// Process salience request
 #[cfg(feature = "server")]
 async fn process_salience(
@@ -75,23 +152,21 @@ async fn process_salience(
     let user_id = &req.user_id;
 
     //USAGE_TRACKER is a lazy_static::lazy_static! macro that creates a static variable
-    let mut tracker = USAGE_TRACKER.lock().unwrap();
-    let usage = tracker.entry(user_id.clone()).and_modify(|e| *e += 1).or_insert(1);
+    let usage = {
+        let mut tracker = USAGE_TRACKER.lock().unwrap();
+        *tracker.entry(user_id.clone()).and_modify(|e| *e += 1).or_insert(1)
+    };
 
     // If the user has exceeded the free tier limit, return an upgrade prompt
     // cfg!(feature = "enterprise") is a compile-time feature flag, if it is not enabled, the code will not be compiled
-    let upgrade_prompt = if *usage > 30 && !cfg!(feature = "enterprise") {
+    let upgrade_prompt = if usage > 30 && !cfg!(feature = "enterprise") {
         Some("Upgrade to Enterprise for more salience processing!".to_string())
     } else {
         None
     };
 
-    // Simple salience extraction (splits text into words and takes first 3 as "salient")
-    let salient_phrases: Vec<String> = req.text
-        .split_whitespace() // splits text into words
-        .take(3) // takes first 3 words
-        .map(|s| s.to_string()) // maps each word to a String
-        .collect(); // collects into a Vec<String>
+    // Score every token with the quantizer and keep the most salient ones
+    let salient_phrases = extract_salient_phrases(&req.text, SALIENT_PHRASE_COUNT);
 
     info!("Processed salience request for user: {}", user_id);
 
@@ -133,28 +208,19 @@ pub async fn run_main() -> std::io::Result<()> {
 
 
 
-pub enum PrecisionLevel {
-    Bit4,
-    Bit8,
-    Bit16,
-}
+/// Per-token precision, shared with the `quantizer` module
+pub use quantizer::PrecisionLevel;
 
-impl PrecisionLevel {
-    fn to_string(&self) -> String {
-        match self {
-            PrecisionLevel::Bit4 => "Bit4".to_string(),
-            PrecisionLevel::Bit8 => "Bit8".to_string(),
-            PrecisionLevel::Bit16 => "Bit16".to_string(),
-        }
-    }
-}
 
 
 
 
+/// Tokens per frame unless overridden with `SalienceQuantizer::with_frame_size`
+pub const DEFAULT_FRAME_SIZE: usize = 10;
 
 pub struct SalienceQuantizer {
     threshold: f32,
+    frame_size: usize,
         frames: Arc<RwLock<Vec<Frame<'static>>>>, 
 }
 
@@ -164,7 +230,7 @@ pub fn quantize_tokens_test(
         input: &str,
         theory_key: &str,
         bump: &Bump,
-    ) -> Result<(Vec<QuantizationResult>, YoungTableau), String> {
+    ) -> Result<(Vec<QuantizationResult>, Vec<QuantizationResult>, YoungTableau), String> {
         let token_features: Vec<TokenFeatures> = serde_json::from_str(input)
             .map_err(|_| "Invalid input format".to_string())?;
 
@@ -175,22 +241,32 @@ pub fn quantize_tokens_test(
     pub fn new(threshold: f32) -> Self {
         SalienceQuantizer {
             threshold,
+            frame_size: DEFAULT_FRAME_SIZE,
             frames: Arc::new(RwLock::new(Vec::with_capacity(100))),
         }
     }
 
+    /// Set how many tokens are grouped into each frame; zero is treated as one
+    pub fn with_frame_size(mut self, frame_size: usize) -> Self {
+        self.frame_size = frame_size.max(1);
+        self
+    }
+
+    /// Quantize tokens frame by frame
+    ///
+    /// Returns the per-token results, one aggregated result per frame (`role: "frame"`,
+    /// `token_id` set to the frame id), and the tableau holding the per-token results.
     pub fn quantize_tokens(
         &self,
         features: Vec<TokenFeatures>,
         _theory_key: &str,
         _bump: &Bump,
-    ) -> (Vec<QuantizationResult>, YoungTableau) {
+    ) -> (Vec<QuantizationResult>, Vec<QuantizationResult>, YoungTableau) {
         let mut results = Vec::with_capacity(features.len());
-        let mut tableau = YoungTableau::new(10, self.threshold);
 
-        // Group tokens into frames
-        let frame_size = 10;
-        let chunks: Vec<_> = features.chunks(frame_size).collect();
+        // Group tokens into frames, with one tableau row per frame so no token is dropped
+        let chunks: Vec<_> = features.chunks(self.frame_size).collect();
+        let mut tableau = YoungTableau::new(chunks.len(), self.threshold);
         let mut frames = vec![Frame::new(0, &[]); chunks.len()];
 
         // Process frames in parallel, creating a new Bump allocator for each thread
@@ -266,7 +342,7 @@ pub fn quantize_tokens(
             })
             .collect();
 
-        (aggregated_results, tableau)
+        (results, aggregated_results, tableau)
     }
 }
 
@@ -287,7 +363,7 @@ fn quantize_tokens(cx: FunctionContext) -> JsResult<JsString> {
 
     let quantizer = SalienceQuantizer::new(0.7);
     let bump = Bump::new();
-    let (results, _tableau) = quantizer.quantize_tokens(token_features, &theory_key, &bump);
+    let (results, _aggregated, _tableau) = quantizer.quantize_tokens(token_features, &theory_key, &bump);
 
     let output = serde_json::to_string(&results)
         .or_else(|_| cx.throw_error("Failed to serialize result"))?;
@@ -300,6 +376,27 @@ fn quantize_tokens(cx: FunctionContext) -> JsResult<JsString> {
 mod tests {
     use super::*;
     use serde_json::json;
+    use zeta_quantization::{PrecisionLevel as Canonical, UnsupportedPrecision};
+
+    #[test]
+    fn test_precision_round_trips_through_canonical() {
+        for level in [PrecisionLevel::Bit4, PrecisionLevel::Bit8, PrecisionLevel::Bit16] {
+            let canonical = Canonical::from(level.clone());
+            assert_eq!(PrecisionLevel::try_from(canonical), Ok(level));
+        }
+        assert_eq!(Canonical::from(PrecisionLevel::Bit16), Canonical::FP16);
+        assert_eq!(PrecisionLevel::Bit8.to_string(), "Bit8");
+        assert_eq!(PrecisionLevel::try_from(Canonical::FP32), Err(UnsupportedPrecision(Canonical::FP32)));
+
+        use quantization::PrecisionLevel as Matrix;
+        for level in [Matrix::Bit4, Matrix::Bit8, Matrix::Bit16, Matrix::Bit32] {
+            let canonical = Canonical::from(level.clone());
+            assert_eq!(Matrix::try_from(canonical), Ok(level));
+        }
+        for level in [Canonical::Int1, Canonical::Int2, Canonical::NF4] {
+            assert_eq!(Matrix::try_from(level.clone()), Err(UnsupportedPrecision(level)));
+        }
+    }
 
     #[test]
     fn test_quantize_tokens() {
@@ -318,6 +415,105 @@ fn test_quantize_tokens() {
         // Test passes if quantization completes without panic
         assert!(result.is_ok(), "Quantization should complete successfully");
     }
+
+    #[test]
+    fn test_quantize_tokens_returns_per_token_results() {
+        let features: Vec<TokenFeatures> = [(7, 0.4), (8, 0.7), (9, 1.2)]
+            .into_iter()
+            .map(|(token_id, frequency)| TokenFeatures {
+                token_id,
+                frequency,
+                sentiment_score: 0.5,
+                context_relevance: 0.9,
+                role: "subject".to_string(),
+            })
+            .collect();
+        let quantizer = SalienceQuantizer::new(0.3);
+
+        let (per_token, aggregated, tableau) = quantizer.quantize_tokens(features, "default", &Bump::new());
+
+        let precisions: Vec<_> = per_token.iter()
+            .map(|result| (result.token_id, result.precision.as_str(), result.role.as_str()))
+            .collect();
+        assert_eq!(precisions, vec![
+            (7, "Bit4", "subject"),
+            (8, "Bit8", "subject"),
+            (9, "Bit16", "subject"),
+        ]);
+        assert!((per_token[1].salience_score - 0.7 * 0.5 * 0.9).abs() < 1e-6);
+
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].role, "frame");
+        assert_eq!(tableau.rows[0].len(), 3);
+    }
+
+    #[test]
+    fn test_empty_input_produces_no_nan() {
+        let mut frame = Frame::new(0, &[]);
+        frame.compute_salience(0.0, &Bump::new());
+        assert_eq!(frame.aggregated_salience, 0.0);
+
+        let quantizer = SalienceQuantizer::new(0.3);
+        let (per_token, aggregated, tableau) = quantizer.quantize_tokens(Vec::new(), "default", &Bump::new());
+        assert!(per_token.is_empty());
+        assert!(aggregated.iter().all(|result| !result.salience_score.is_nan()));
+        assert!(tableau.rows.iter().flatten().all(|result| !result.salience_score.is_nan()));
+    }
+
+    #[test]
+    fn test_long_inputs_keep_every_token() {
+        let features: Vec<TokenFeatures> = (0..250)
+            .map(|token_id| TokenFeatures {
+                token_id,
+                frequency: 0.8,
+                sentiment_score: 0.5,
+                context_relevance: 0.9,
+                role: "object".to_string(),
+            })
+            .collect();
+
+        for quantizer in [SalienceQuantizer::new(0.3), SalienceQuantizer::new(0.3).with_frame_size(32)] {
+            let frame_size = quantizer.frame_size;
+            let (per_token, aggregated, tableau) = quantizer.quantize_tokens(features.clone(), "default", &Bump::new());
+
+            assert_eq!(per_token.len(), 250);
+            assert_eq!(aggregated.len(), features.chunks(frame_size).count());
+            assert_eq!(tableau.rows.len(), aggregated.len());
+            assert_eq!(tableau.rows.iter().map(Vec::len).sum::<usize>(), 250);
+        }
+    }
+
+    #[test]
+    fn test_extract_salient_phrases_ranks_by_score() {
+        let text = "The cat saw an extraordinary, magnificent constellation and the cat purred.";
+        let phrases = extract_salient_phrases(text, 3);
+        assert_eq!(phrases, vec!["extraordinary", "magnificent", "constellation"]);
+
+        // Stopwords never score, so they are never salient
+        assert!(extract_salient_phrases("the and of", 3).is_empty());
+    }
+
+    #[cfg(feature = "server")]
+    #[actix_rt::test]
+    async fn test_salience_endpoint_returns_scored_tokens() {
+        use actix_web::test;
+
+        let app = test::init_service(
+            App::new().route("/api/salience", web::post().to(process_salience)),
+        ).await;
+        let request = test::TestRequest::post()
+            .uri("/api/salience")
+            .set_json(SalienceRequest {
+                text: "A tiny dog chased an enormous, bewildered elephant".to_string(),
+                user_id: "salience-endpoint-test".to_string(),
+            })
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        // Positional extraction would have returned ["A", "tiny", "dog"]
+        assert_eq!(body["salient_phrases"], json!(["enormous", "bewildered", "elephant"]));
+        assert!(body["upgrade_prompt"].is_null());
+    }
 }
 
 
@@ -379,7 +575,12 @@ pub fn compute_salience(&mut self, threshold: f32, _bump: &Bump) {
             .map(|t| t.frequency * t.context_relevance * (1.0 + t.sentiment_score.abs()))
             .collect();
             
-        self.aggregated_salience = saliences.iter().sum::<f32>() / saliences.len() as f32;
+        // An empty frame has no salience rather than a NaN mean
+        self.aggregated_salience = if saliences.is_empty() {
+            0.0
+        } else {
+            saliences.iter().sum::<f32>() / saliences.len() as f32
+        };
             
         // Apply threshold
         if self.aggregated_salience < threshold {
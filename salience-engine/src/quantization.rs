@@ -35,3 +35,29 @@ fn default() -> Self {
         Self::Bit32
     }
 }
+
+impl From<PrecisionLevel> for zeta_quantization::PrecisionLevel {
+    fn from(level: PrecisionLevel) -> Self {
+        match level {
+            PrecisionLevel::Bit4 => Self::Int4,
+            PrecisionLevel::Bit8 => Self::Int8,
+            PrecisionLevel::Bit16 => Self::FP16,
+            PrecisionLevel::Bit32 => Self::FP32,
+        }
+    }
+}
+
+impl TryFrom<zeta_quantization::PrecisionLevel> for PrecisionLevel {
+    type Error = zeta_quantization::UnsupportedPrecision;
+
+    fn try_from(level: zeta_quantization::PrecisionLevel) -> Result<Self, Self::Error> {
+        use zeta_quantization::PrecisionLevel as Canonical;
+        match level {
+            Canonical::Int4 => Ok(Self::Bit4),
+            Canonical::Int8 => Ok(Self::Bit8),
+            Canonical::FP16 => Ok(Self::Bit16),
+            Canonical::FP32 => Ok(Self::Bit32),
+            other => Err(zeta_quantization::UnsupportedPrecision(other)),
+        }
+    }
+}
@@ -42,6 +42,36 @@ pub enum PrecisionLevel {
     Bit16,
 }
 
+impl std::fmt::Display for PrecisionLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl From<PrecisionLevel> for zeta_quantization::PrecisionLevel {
+    fn from(level: PrecisionLevel) -> Self {
+        match level {
+            PrecisionLevel::Bit4 => Self::Int4,
+            PrecisionLevel::Bit8 => Self::Int8,
+            PrecisionLevel::Bit16 => Self::FP16,
+        }
+    }
+}
+
+impl TryFrom<zeta_quantization::PrecisionLevel> for PrecisionLevel {
+    type Error = zeta_quantization::UnsupportedPrecision;
+
+    fn try_from(level: zeta_quantization::PrecisionLevel) -> Result<Self, Self::Error> {
+        use zeta_quantization::PrecisionLevel as Canonical;
+        match level {
+            Canonical::Int4 => Ok(Self::Bit4),
+            Canonical::Int8 => Ok(Self::Bit8),
+            Canonical::FP16 => Ok(Self::Bit16),
+            other => Err(zeta_quantization::UnsupportedPrecision(other)),
+        }
+    }
+}
+
 pub struct SalienceQuantizer {
     threshold: f32,
     hardware: Option<String>,
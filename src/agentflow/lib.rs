@@ -13,7 +13,12 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use std::cmp::Ordering;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock, mpsc};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinHandle;
 use std::collections::BinaryHeap;
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
@@ -25,6 +30,11 @@
 use quantize::Quantizer;
 use llm_rs::LLMModel;
 
+/// Number of worker tasks `process_tasks` runs when none is configured
+pub const DEFAULT_CONCURRENT_WORKERS: usize = 4;
+/// Number of queued tasks `enqueue_task` accepts before rejecting, when none is configured
+pub const DEFAULT_MAX_QUEUE_DEPTH: usize = 100;
+
 #[derive(Error, Debug)]
 pub enum AgentFlowError {
     #[error("Task error: {0}")]
@@ -54,14 +64,38 @@ struct Task {
     assigned_gpu: Option<u32>,
 }
 
+// Highest priority first; among equal priorities the oldest task wins
+impl Ord for Task {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.priority
+            .cmp(&other.priority.priority)
+            .then_with(|| other.priority.timestamp.cmp(&self.priority.timestamp))
+    }
+}
+
+impl PartialOrd for Task {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Task {}
+
 pub struct AgentFlow {
     attention_store: Arc<AttentionStore>,
     vault: Arc<ZetaVaultSynergy>,
     model: Arc<LLMModel>,
     quantizer: Arc<Quantizer>,
     task_queue: Arc<RwLock<BinaryHeap<Task>>>,
-    task_sender: mpsc::Sender<Task>,
-    task_receiver: Arc<RwLock<mpsc::Receiver<Task>>>,
+    task_sender: mpsc::Sender<()>,
+    task_receiver: Arc<Mutex<mpsc::Receiver<()>>>,
+    concurrent_workers: usize,
 }
 
 impl AgentFlow {
@@ -71,7 +105,30 @@ pub async fn new(
         model: Arc<LLMModel>,
         quantizer: Arc<Quantizer>,
     ) -> Result<Arc<Self>, AgentFlowError> {
-        let (tx, rx) = mpsc::channel(100);
+        Self::with_workers(attention_store, vault, model, quantizer, DEFAULT_CONCURRENT_WORKERS).await
+    }
+
+    /// Like `new`, but runs `concurrent_workers` tasks in parallel (e.g. `RuntimeConfig::worker_threads`)
+    pub async fn with_workers(
+        attention_store: Arc<AttentionStore>,
+        vault: Arc<ZetaVaultSynergy>,
+        model: Arc<LLMModel>,
+        quantizer: Arc<Quantizer>,
+        concurrent_workers: usize,
+    ) -> Result<Arc<Self>, AgentFlowError> {
+        Self::with_queue_depth(attention_store, vault, model, quantizer, concurrent_workers, DEFAULT_MAX_QUEUE_DEPTH).await
+    }
+
+    /// Like `with_workers`, but `enqueue_task` rejects tasks once `max_queue_depth` are waiting
+    pub async fn with_queue_depth(
+        attention_store: Arc<AttentionStore>,
+        vault: Arc<ZetaVaultSynergy>,
+        model: Arc<LLMModel>,
+        quantizer: Arc<Quantizer>,
+        concurrent_workers: usize,
+        max_queue_depth: usize,
+    ) -> Result<Arc<Self>, AgentFlowError> {
+        let (tx, rx) = mpsc::channel(max_queue_depth.max(1));
         Ok(Arc::new(AgentFlow {
             attention_store,
             vault,
@@ -79,49 +136,56 @@ pub async fn new(
             quantizer,
             task_queue: Arc::new(RwLock::new(BinaryHeap::new())),
             task_sender: tx,
-            task_receiver: Arc::new(RwLock::new(rx)),
+            task_receiver: Arc::new(Mutex::new(rx)),
+            concurrent_workers: concurrent_workers.max(1),
         }))
     }
 
+    /// Queue `task` for the workers, or return `AgentFlowError::Queue` without
+    /// queueing it when the configured maximum depth is already waiting
     pub async fn enqueue_task(&self, task: AgentTask, priority: i32) -> Result<(), AgentFlowError> {
         let timestamp = Utc::now().timestamp() as u64;
         let task_priority = TaskPriority { priority, timestamp };
-        let mut queue = self.task_queue.write().await;
         let assigned_gpu = match &task {
             AgentTask::Inference { .. } => Some(0), // Mock GPU assignment
             _ => None,
         };
-        let task_instance = Task { task, priority: task_priority, assigned_gpu };
-        queue.push(task_instance.clone());
-        self.task_sender.send(task_instance).await.map_err(|e| AgentFlowError::Queue(e.to_string()))?;
-        Ok(())
+        enqueue(&self.task_queue, &self.task_sender, Task { task, priority: task_priority, assigned_gpu }).await
     }
 
     pub async fn process_tasks(self: Arc<Self>) {
-        let receiver = Arc::clone(&self.task_receiver);
-        tokio::spawn(async move {
-            while let Ok(task) = receiver.write().await.recv().await {
-                match task.task {
-                    AgentTask::Inference { session_id, token, kv_cache } => {
-                        log::info!("Processing inference task for session {}", session_id);
-                        let (next_token, new_kv_cache) = self.attention_store.decode(session_id, token, kv_cache).await
-                            .map_err(|e| log::error!("Inference failed: {}", e)).unwrap_or((0, vec![]));
-                        // Update KV cache in vault
-                        self.vault.store_kv_cache(&session_id, new_kv_cache).await.ok();
-                    }
-                    AgentTask::Quantization { model_id, bit_width } => {
-                        log::info!("Processing quantization task for model {}", model_id);
-                        let mut kv_cache = vec![KVCache::new(vec![AllocatedBufferDescriptor { buffer_address_: 0, size_: 8192 }])];
-                        self.quantizer.quantize_kv_cache(&mut kv_cache).map_err(|e| log::error!("Quantization failed: {}", e)).ok();
-                        self.vault.store_kv_cache(&model_id, kv_cache).await.ok();
-                    }
-                    AgentTask::Compaction { segment_id } => {
-                        log::info!("Processing compaction for segment {}", segment_id);
-                        self.vault.compact_segment(&segment_id).await.ok();
-                    }
-                }
+        let flow = Arc::clone(&self);
+        spawn_workers(
+            self.concurrent_workers,
+            Arc::clone(&self.task_queue),
+            Arc::clone(&self.task_receiver),
+            move |task| {
+                let flow = Arc::clone(&flow);
+                async move { flow.run_task(task).await }
+            },
+        );
+    }
+
+    async fn run_task(&self, task: Task) {
+        match task.task {
+            AgentTask::Inference { session_id, token, kv_cache } => {
+                log::info!("Processing inference task for session {}", session_id);
+                let (next_token, new_kv_cache) = self.attention_store.decode(session_id.clone(), token, kv_cache).await
+                    .map_err(|e| log::error!("Inference failed: {}", e)).unwrap_or((0, vec![]));
+                // Update KV cache in vault
+                self.vault.store_kv_cache(&session_id, new_kv_cache).await.ok();
             }
-        });
+            AgentTask::Quantization { model_id, bit_width } => {
+                log::info!("Processing quantization task for model {}", model_id);
+                let mut kv_cache = vec![KVCache::new(vec![AllocatedBufferDescriptor { buffer_address_: 0, size_: 8192 }])];
+                self.quantizer.quantize_kv_cache(&mut kv_cache).map_err(|e| log::error!("Quantization failed: {}", e)).ok();
+                self.vault.store_kv_cache(&model_id, kv_cache).await.ok();
+            }
+            AgentTask::Compaction { segment_id } => {
+                log::info!("Processing compaction for segment {}", segment_id);
+                self.vault.compact_segment(&segment_id).await.ok();
+            }
+        }
     }
 
     pub async fn monitor_slos(self: Arc<Self>) {
@@ -140,4 +204,121 @@ pub async fn monitor_slos(self: Arc<Self>) {
             }
         });
     }
-}
\ No newline at end of file
+}
+
+/// Push `task` onto `queue` and signal a worker, rejecting it when `sender` is full
+///
+/// The signal slot is reserved before the push, so a rejected task never reaches
+/// the queue and the queue can't outgrow the channel.
+async fn enqueue(queue: &RwLock<BinaryHeap<Task>>, sender: &mpsc::Sender<()>, task: Task) -> Result<(), AgentFlowError> {
+    let permit = sender.try_reserve().map_err(|e| match e {
+        TrySendError::Full(()) => {
+            AgentFlowError::Queue(format!("task queue is full ({} tasks waiting)", sender.max_capacity()))
+        }
+        TrySendError::Closed(()) => AgentFlowError::Queue("task queue is closed".to_string()),
+    })?;
+    queue.write().await.push(task);
+    // One signal per queued task; whichever worker takes it runs the highest-priority task
+    permit.send(());
+    Ok(())
+}
+
+/// Spawn `workers` tasks that share `receiver`; each signal makes one worker pop and run the
+/// highest-priority task in `queue`, so a slow task only occupies its own worker
+fn spawn_workers<F, Fut>(
+    workers: usize,
+    queue: Arc<RwLock<BinaryHeap<Task>>>,
+    receiver: Arc<Mutex<mpsc::Receiver<()>>>,
+    run: F,
+) -> Vec<JoinHandle<()>>
+where
+    F: Fn(Task) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    (0..workers.max(1))
+        .map(|worker_id| {
+            let queue = Arc::clone(&queue);
+            let receiver = Arc::clone(&receiver);
+            let run = run.clone();
+            tokio::spawn(async move {
+                loop {
+                    // Only idle workers wait here; the lock is released before the task runs
+                    let signal = receiver.lock().await.recv().await;
+                    if signal.is_none() {
+                        log::debug!("Worker {} stopping: task channel closed", worker_id);
+                        break;
+                    }
+                    let next = queue.write().await.pop();
+                    if let Some(task) = next {
+                        run(task).await;
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn push(queue: &RwLock<BinaryHeap<Task>>, sender: &mpsc::Sender<()>, task: AgentTask, priority: i32) {
+        let priority = TaskPriority { priority, timestamp: Utc::now().timestamp() as u64 };
+        enqueue(queue, sender, Task { task, priority, assigned_gpu: None }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fast_tasks_do_not_wait_for_slow_task() {
+        let queue = Arc::new(RwLock::new(BinaryHeap::new()));
+        let (tx, rx) = mpsc::channel(16);
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+
+        let _workers = spawn_workers(4, Arc::clone(&queue), Arc::new(Mutex::new(rx)), move |task: Task| {
+            let done_tx = done_tx.clone();
+            async move {
+                if let AgentTask::Quantization { .. } = task.task {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                }
+                done_tx.send(task.task).ok();
+            }
+        });
+
+        push(&queue, &tx, AgentTask::Quantization { model_id: "model".to_string(), bit_width: 4 }, 5).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for token in 0..3 {
+            let task = AgentTask::Inference { session_id: format!("session-{}", token), token, kv_cache: Vec::new() };
+            push(&queue, &tx, task, 10).await;
+        }
+
+        for _ in 0..3 {
+            let done = tokio::time::timeout(Duration::from_secs(1), done_rx.recv())
+                .await
+                .expect("inference task waited behind quantization")
+                .unwrap();
+            assert!(matches!(done, AgentTask::Inference { .. }));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects_tasks() {
+        let queue = RwLock::new(BinaryHeap::new());
+        // No workers drain the channel, so it fills up
+        let (tx, _rx) = mpsc::channel(8);
+
+        let mut rejected = 0;
+        for token in 0..50 {
+            let task = Task {
+                task: AgentTask::Compaction { segment_id: format!("segment-{}", token) },
+                priority: TaskPriority { priority: 0, timestamp: token as u64 },
+                assigned_gpu: None,
+            };
+            if let Err(e) = enqueue(&queue, &tx, task).await {
+                assert!(matches!(e, AgentFlowError::Queue(_)), "{}", e);
+                rejected += 1;
+            }
+        }
+
+        assert_eq!(rejected, 42);
+        assert_eq!(queue.read().await.len(), 8);
+    }
+}
@@ -249,7 +249,8 @@ async fn analyze_model_salience(&self, model: &Tensor) -> Result<SalienceAnalysi
         let token_features = self.extract_token_features(model).await?;
         
         // Use salience quantizer to analyze tokens
-        let (quantization_decisions, _tableau) = self.salience_quantizer.quantize_tokens(
+        // Decisions are per token; the frame aggregates aren't needed here
+        let (quantization_decisions, _frames, _tableau) = self.salience_quantizer.quantize_tokens(
             token_features.clone(),
             "model_analysis",
             &self.bump_allocator,
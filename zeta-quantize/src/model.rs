@@ -1,5 +1,7 @@
 use crate::error::{QuantizationError, Result};
-use candle_core::{Device, Tensor};
+use crate::quantization::QuantizedTensor;
+use candle_core::{DType, Device, Tensor};
+use safetensors::tensor::TensorView;
 use safetensors::SafeTensors;
 use std::collections::HashMap;
 use std::path::Path;
@@ -23,6 +25,9 @@ pub struct ModelMetadata {
     pub dtype_info: HashMap<String, String>,
 }
 
+/// Suffix of the companion tensor holding a quantized tensor's parameters
+pub const QUANT_PARAMS_SUFFIX: &str = ".quant_params";
+
 pub struct ModelLoader {
     device: Device,
 }
@@ -308,9 +313,61 @@ pub fn create_test_model(&self, shape: &[usize]) -> Result<Tensor> {
     }
 }
 
+/// Write quantized tensors to a self-describing Safetensors file
+///
+/// Each tensor's integer codes are stored as `I32` under its name with its original
+/// shape. A companion `F64` tensor `<name>.quant_params` holds
+/// `[scale, zero_point, min_val, max_val]`, so values dequantize as
+/// `scale * (q - zero_point)`, and the file metadata maps each name to its precision.
+pub fn save_quantized_safetensors(tensors: &[(String, QuantizedTensor)], path: &Path) -> Result<()> {
+    info!("Saving {} quantized tensors to {:?}", tensors.len(), path);
+
+    let mut buffers = Vec::with_capacity(tensors.len());
+    let mut metadata = HashMap::new();
+    for (name, quantized) in tensors {
+        let codes: Vec<f32> = quantized.data.to_dtype(DType::F32)?.flatten_all()?.to_vec1()?;
+        let code_bytes: Vec<u8> = codes.iter()
+            .flat_map(|&q| (q.round() as i32).to_le_bytes())
+            .collect();
+
+        let params = &quantized.params;
+        let param_bytes: Vec<u8> = [params.scale, params.zero_point as f64, params.min_val, params.max_val]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+
+        metadata.insert(name.clone(), format!("{:?}", quantized.precision));
+        buffers.push((name.clone(), quantized.shape.clone(), code_bytes, param_bytes));
+    }
+
+    let mut views = Vec::with_capacity(buffers.len() * 2);
+    for (name, shape, code_bytes, param_bytes) in &buffers {
+        let serialize_error = |e: safetensors::SafeTensorError| {
+            QuantizationError::model_load(format!("Invalid tensor {}: {}", name, e))
+        };
+        views.push((
+            name.clone(),
+            TensorView::new(safetensors::Dtype::I32, shape.clone(), code_bytes).map_err(serialize_error)?,
+        ));
+        views.push((
+            format!("{}{}", name, QUANT_PARAMS_SUFFIX),
+            TensorView::new(safetensors::Dtype::F64, vec![4], param_bytes).map_err(serialize_error)?,
+        ));
+    }
+
+    let serialized = safetensors::serialize(views, &Some(metadata))
+        .map_err(|e| QuantizationError::model_load(format!("Failed to serialize: {}", e)))?;
+    std::fs::write(path, serialized)?;
+
+    debug!("Wrote quantized Safetensors file {:?}", path);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::PrecisionLevel;
+    use crate::quantization::QuantizationParams;
     use candle_core::Device;
     use tempfile::NamedTempFile;
 
@@ -358,4 +415,52 @@ async fn test_save_load_roundtrip() {
         // Check that shapes match (values might differ due to serialization)
         assert_eq!(loaded_model.elem_count(), original_model.elem_count());
     }
+
+    #[test]
+    fn test_save_quantized_safetensors_round_trip() {
+        let quantized = |codes: Vec<f32>, shape: &[usize], scale: f64, zero_point: i32, precision| QuantizedTensor {
+            data: Tensor::from_vec(codes, shape, &Device::Cpu).unwrap(),
+            params: QuantizationParams { scale, zero_point, min_val: -1.0, max_val: 2.0 },
+            shape: shape.to_vec(),
+            precision,
+        };
+        let tensors = vec![
+            ("layer.0.weight".to_string(), quantized(vec![0.0, 3.0, 15.0, 7.0, 1.0, 2.0], &[2, 3], 0.2, 5, PrecisionLevel::Int4)),
+            ("layer.1.weight".to_string(), quantized(vec![255.0, 0.0, 128.0, 64.0], &[4], 0.01, 100, PrecisionLevel::Int8)),
+        ];
+
+        let temp_file = NamedTempFile::with_suffix(".safetensors").unwrap();
+        save_quantized_safetensors(&tensors, temp_file.path()).unwrap();
+
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        let (_, file_metadata) = SafeTensors::read_metadata(&bytes).unwrap();
+        let precisions = file_metadata.metadata().as_ref().unwrap();
+        let loaded = SafeTensors::deserialize(&bytes).unwrap();
+        assert_eq!(loaded.names().len(), 4);
+
+        for (name, original) in &tensors {
+            let codes = loaded.tensor(name).unwrap();
+            assert_eq!(codes.dtype(), safetensors::Dtype::I32);
+            assert_eq!(codes.shape(), original.shape.as_slice());
+            let values: Vec<i32> = codes.data().chunks_exact(4)
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            let expected: Vec<i32> = original.data.flatten_all().unwrap().to_vec1::<f32>().unwrap()
+                .iter().map(|&q| q as i32).collect();
+            assert_eq!(values, expected);
+
+            let params = loaded.tensor(&format!("{}{}", name, QUANT_PARAMS_SUFFIX)).unwrap();
+            assert_eq!(params.shape(), &[4]);
+            let params: Vec<f64> = params.data().chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                .collect();
+            assert_eq!(params, vec![
+                original.params.scale,
+                original.params.zero_point as f64,
+                original.params.min_val,
+                original.params.max_val,
+            ]);
+            assert_eq!(precisions[name], format!("{:?}", original.precision));
+        }
+    }
 }
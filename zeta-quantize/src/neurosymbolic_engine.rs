@@ -316,7 +316,8 @@ async fn analyze_salience_with_phonemes(
         debug!("Analyzing salience with phoneme preservation");
 
         // Use salience quantizer with tableaux
-        let (salience_results, mut tableau) = self.salience_quantizer.quantize_tokens(
+        // Phoneme checks run per token, so the frame aggregates are dropped
+        let (salience_results, _frames, tableau) = self.salience_quantizer.quantize_tokens(
             features.to_vec(),
             "phoneme_analysis",
             &self.bump_allocator,
@@ -328,8 +329,11 @@ async fn analyze_salience_with_phonemes(
         // Convert to phoneme-aware results
         let mut phoneme_results = Vec::new();
         
-        for (i, result) in salience_results.iter().enumerate() {
-            let feature = &features[i.min(features.len() - 1)];
+        for result in &salience_results {
+            // Tokens below the threshold have no result, so match on id rather than position
+            let Some(feature) = features.iter().find(|f| f.token_id == result.token_id) else {
+                continue;
+            };
             let phoneme_invariant = self.calculate_phoneme_invariant(&[feature.frequency, feature.sentiment_score, feature.context_relevance]);
             
             // Determine if homogeneity is preserved